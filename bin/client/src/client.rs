@@ -1,19 +1,53 @@
 extern crate alloc;
 
 use alloc::sync::Arc;
+use alloc::vec::Vec;
 use core::fmt::Debug;
 
+use alloy_primitives::Bytes;
+use kona_derive::PipelineResult;
+
 use kona_client::single::FaultProofProgramError;
 use kona_preimage::{HintWriterClient, PreimageOracleClient};
 use kona_proof::{l1::OracleBlobProvider, CachingOracle};
 
-use hokulea_client::fp_client;
+use hokulea_client::{fp_client, replay};
 use hokulea_proof::eigenda_provider::OracleEigenDAPreimageProvider;
 
 use alloy_evm::{EvmFactory, FromRecoveredTx, FromTxWithEncoded};
 use op_alloy_consensus::OpTxEnvelope;
 use op_revm::OpSpecId;
 
+/// The default number of preimages kept by the [CachingOracle]
+pub const DEFAULT_ORACLE_LRU_SIZE: usize = 1024;
+
+/// Configuration shared by the client run functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientConfig {
+    /// Number of preimages kept by the [CachingOracle]. Chains with a large derivation window
+    /// benefit from a larger cache, while memory constrained zkVM runs want a smaller one
+    pub oracle_lru_size: usize,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            oracle_lru_size: DEFAULT_ORACLE_LRU_SIZE,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Wraps the oracle and hint client into a [CachingOracle] of the configured size
+    pub fn caching_oracle<P, H>(&self, oracle_client: P, hint_client: H) -> CachingOracle<P, H>
+    where
+        P: PreimageOracleClient + Send + Sync + Debug + Clone,
+        H: HintWriterClient + Send + Sync + Debug + Clone,
+    {
+        CachingOracle::new(self.oracle_lru_size, oracle_client, hint_client)
+    }
+}
+
 /// The function uses the identical function signature as the kona client
 /// This is the basic hokulea client containing the minimal layer between kona client and hokulea host
 #[allow(clippy::type_complexity)]
@@ -29,15 +63,113 @@ where
     Evm: EvmFactory<Spec = OpSpecId> + Send + Sync + Debug + Clone + 'static,
     <Evm as EvmFactory>::Tx: FromTxWithEncoded<OpTxEnvelope> + FromRecoveredTx<OpTxEnvelope>,
 {
-    const ORACLE_LRU_SIZE: usize = 1024;
-
-    let oracle = Arc::new(CachingOracle::new(
-        ORACLE_LRU_SIZE,
+    run_direct_client_with_config(
+        ClientConfig::default(),
         oracle_client,
         hint_client,
-    ));
+        evm_factory,
+    )
+    .await
+}
+
+/// Same as [run_direct_client], with a custom [ClientConfig]
+#[allow(clippy::type_complexity)]
+#[inline]
+pub async fn run_direct_client_with_config<P, H, Evm>(
+    config: ClientConfig,
+    oracle_client: P,
+    hint_client: H,
+    evm_factory: Evm,
+) -> Result<(), FaultProofProgramError>
+where
+    P: PreimageOracleClient + Send + Sync + Debug + Clone,
+    H: HintWriterClient + Send + Sync + Debug + Clone,
+    Evm: EvmFactory<Spec = OpSpecId> + Send + Sync + Debug + Clone + 'static,
+    <Evm as EvmFactory>::Tx: FromTxWithEncoded<OpTxEnvelope> + FromRecoveredTx<OpTxEnvelope>,
+{
+    let oracle = Arc::new(config.caching_oracle(oracle_client, hint_client));
     let beacon = OracleBlobProvider::new(oracle.clone());
     let eigenda_preimage_provider = OracleEigenDAPreimageProvider::new(oracle.clone());
 
     fp_client::run_fp_client(oracle, beacon, eigenda_preimage_provider, evm_factory).await
 }
+
+/// Replays the eigenda derivation of a single l1 block, using the same oracle backed providers
+/// as [run_direct_client]
+#[inline]
+pub async fn run_replay_client<P, H>(
+    oracle_client: P,
+    hint_client: H,
+    l1_block_number: u64,
+) -> PipelineResult<Vec<Bytes>>
+where
+    P: PreimageOracleClient + Send + Sync + Debug + Clone,
+    H: HintWriterClient + Send + Sync + Debug + Clone,
+{
+    let oracle = Arc::new(ClientConfig::default().caching_oracle(oracle_client, hint_client));
+    let beacon = OracleBlobProvider::new(oracle.clone());
+    let eigenda_preimage_provider = OracleEigenDAPreimageProvider::new(oracle.clone());
+
+    replay::replay_l1_block(oracle, beacon, eigenda_preimage_provider, l1_block_number).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::boxed::Box;
+    use alloc::vec;
+    use async_trait::async_trait;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use kona_preimage::errors::PreimageOracleResult;
+    use kona_preimage::{PreimageKey, PreimageKeyType};
+
+    // an oracle counting how many preimages are read from it
+    #[derive(Debug, Clone, Default)]
+    struct CountingOracle {
+        num_reads: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl PreimageOracleClient for CountingOracle {
+        async fn get(&self, _key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            self.num_reads.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![0u8; 32])
+        }
+
+        async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+            let preimage = self.get(key).await?;
+            buf.copy_from_slice(&preimage);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl HintWriterClient for CountingOracle {
+        async fn write(&self, _hint: &str) -> PreimageOracleResult<()> {
+            Ok(())
+        }
+    }
+
+    // read two distinct keys, then the first one again
+    fn num_underlying_reads(config: ClientConfig) -> usize {
+        let oracle_client = CountingOracle::default();
+        let oracle = config.caching_oracle(oracle_client.clone(), oracle_client.clone());
+        let key_1 = PreimageKey::new([1u8; 32], PreimageKeyType::GlobalGeneric);
+        let key_2 = PreimageKey::new([2u8; 32], PreimageKeyType::GlobalGeneric);
+        kona_proof::block_on(async {
+            for key in [key_1, key_2, key_1] {
+                oracle.get(key).await.unwrap();
+            }
+        });
+        oracle_client.num_reads.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_client_config_oracle_lru_size() {
+        assert_eq!(ClientConfig::default().oracle_lru_size, 1024);
+        // the first key is still cached
+        assert_eq!(num_underlying_reads(ClientConfig::default()), 2);
+        // the first key is evicted by the second one
+        assert_eq!(num_underlying_reads(ClientConfig { oracle_lru_size: 1 }), 3);
+    }
+}
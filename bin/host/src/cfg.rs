@@ -1,7 +1,14 @@
-use crate::eigenda_preimage::OnlineEigenDAPreimageProvider;
-use crate::handler::SingleChainHintHandlerWithEigenDA;
+use crate::eigenda_preimage::{
+    OnlineEigenDAPreimageProvider, DEFAULT_MAX_CONCURRENT_FETCHES, DEFAULT_PROXY_GZIP,
+    DEFAULT_PROXY_MAX_RETRIES, DEFAULT_PROXY_TIMEOUT_SECS,
+};
+use crate::handler::{prewarm_block, SingleChainHintHandlerWithEigenDA};
+use crate::metrics::{HostMetrics, SharedHostMetrics};
+use alloy_consensus::Transaction;
+use alloy_primitives::Bytes;
+use alloy_provider::Provider;
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use hokulea_proof::hint::ExtendedHintType;
 use kona_cli::cli_styles;
 use kona_client::fpvm_evm::FpvmOpEvmFactory;
@@ -14,8 +21,12 @@ use kona_preimage::{
 };
 use kona_std_fpvm::{FileChannel, FileDescriptor};
 use serde::Serialize;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
 use tokio::task::{self, JoinHandle};
+use tracing::{error, info};
 
 /// The host Eigenda binary CLI application arguments.
 #[derive(Default, Parser, Serialize, Clone, Debug)]
@@ -35,6 +46,31 @@ pub struct SingleChainHostWithEigenDA {
     )]
     pub eigenda_proxy_address: Option<String>,
 
+    /// Maximal number of eigenda-proxy requests the host keeps in flight at once.
+    #[clap(long, default_value_t = DEFAULT_MAX_CONCURRENT_FETCHES, env)]
+    pub eigenda_max_concurrent_fetches: usize,
+
+    /// Time in seconds an eigenda-proxy request may take before it is aborted, 0 disables the
+    /// timeout.
+    #[clap(long, default_value_t = DEFAULT_PROXY_TIMEOUT_SECS, env)]
+    pub eigenda_proxy_timeout: u64,
+
+    /// Number of times a failed eigenda-proxy request is retried before the hint fails.
+    #[clap(long, default_value_t = DEFAULT_PROXY_MAX_RETRIES, env)]
+    pub eigenda_proxy_max_retries: u32,
+
+    /// Whether eigenda-proxy responses are requested gzip encoded, they are decompressed by the
+    /// host. A proxy without gzip support answers uncompressed.
+    #[clap(long, default_value_t = DEFAULT_PROXY_GZIP, action = clap::ArgAction::Set, env)]
+    pub eigenda_proxy_gzip: bool,
+
+    /// Overrides the recency window served to the client, which otherwise is the sequencer window
+    /// size of the rollup config. Only meant for experimenting with recency policies: a client
+    /// verifying recency against the rollup config, like the zkvm verification does, derives a
+    /// different chain whenever the override differs from the sequencer window size.
+    #[clap(long, env)]
+    pub recency_window: Option<u64>,
+
     /// Verbosity level (-v, -vv, -vvv, etc.)
     /// TODO: think this should be upstreamed to kona_cfg
     #[clap(
@@ -44,12 +80,52 @@ pub struct SingleChainHostWithEigenDA {
         default_value_t = 0
     )]
     pub verbose: u8,
+
+    /// Runs a debugging command instead of the fault proof program
+    #[clap(subcommand)]
+    pub command: Option<HostCommand>,
+
+    /// Metrics recorded while serving eigenda hints, a no-op unless injected with
+    /// [SingleChainHostWithEigenDA::with_metrics]
+    #[clap(skip)]
+    #[serde(skip)]
+    pub metrics: SharedHostMetrics,
+}
+
+/// Debugging commands of the host
+#[derive(Subcommand, Serialize, Clone, Debug)]
+pub enum HostCommand {
+    /// Replays the eigenda derivation of a single l1 block from the preimages cached in the
+    /// data directory, and prints the derived items. No network access is made.
+    ReplayBlock {
+        /// Number of the l1 block to replay
+        #[clap(long)]
+        l1_block: u64,
+    },
+    /// Prefetches the eigenda preimages of the certs posted by the batcher in a range of l1
+    /// blocks into the data directory, such that a later run does not wait on the proxy.
+    Prewarm {
+        /// Number of the first l1 block of the range
+        #[clap(long)]
+        start: u64,
+        /// Number of the last l1 block of the range, included
+        #[clap(long)]
+        end: u64,
+    },
 }
 
 impl SingleChainHostWithEigenDA {
     /// Starts the [SingleChainHostWithEigenDA] application. This is copy from
     /// <https://github.com/op-rs/kona/blob/b3eef14771015f6f7427f4f05cf70e508b641802/bin/host/src/single/cfg.rs#L133-L143>
     pub async fn start(self) -> Result<(), SingleChainHostError> {
+        match &self.command {
+            Some(HostCommand::ReplayBlock { l1_block }) => {
+                return self.replay_block(*l1_block).await
+            }
+            Some(HostCommand::Prewarm { start, end }) => return self.prewarm(*start, *end).await,
+            None => {}
+        }
+
         if self.kona_cfg.server {
             let hint = FileChannel::new(FileDescriptor::HintRead, FileDescriptor::HintWrite);
             let preimage =
@@ -69,20 +145,40 @@ impl SingleChainHostWithEigenDA {
     ) -> Result<JoinHandle<Result<(), SingleChainHostError>>, SingleChainHostError>
     where
         C: Channel + Send + Sync + 'static,
+    {
+        self.start_server_with_shutdown(hint, preimage, std::future::pending())
+            .await
+    }
+
+    /// Same as [SingleChainHostWithEigenDA::start_server], but the server also stops once the
+    /// shutdown future resolves. Unlike a process exit, the server then drops its backend, which
+    /// flushes the key value store
+    pub async fn start_server_with_shutdown<C, S>(
+        &self,
+        hint: C,
+        preimage: C,
+        shutdown: S,
+    ) -> Result<JoinHandle<Result<(), SingleChainHostError>>, SingleChainHostError>
+    where
+        C: Channel + Send + Sync + 'static,
+        S: Future<Output = ()> + Send + 'static,
     {
         let kv_store = self.kona_cfg.create_key_value_store()?;
 
         let task_handle = if self.is_offline() {
-            task::spawn(async {
-                PreimageServer::new(
-                    OracleServer::new(preimage),
-                    HintReader::new(hint),
-                    Arc::new(OfflineHostBackend::new(kv_store)),
-                )
-                .start()
-                .await
-                .map_err(SingleChainHostError::from)
-            })
+            task::spawn(serve_until_shutdown(
+                async {
+                    PreimageServer::new(
+                        OracleServer::new(preimage),
+                        HintReader::new(hint),
+                        Arc::new(OfflineHostBackend::new(kv_store)),
+                    )
+                    .start()
+                    .await
+                    .map_err(SingleChainHostError::from)
+                },
+                shutdown,
+            ))
         } else {
             let providers = self.create_providers().await?;
             let backend = OnlineHostBackend::new(
@@ -92,16 +188,19 @@ impl SingleChainHostWithEigenDA {
                 SingleChainHintHandlerWithEigenDA,
             );
 
-            task::spawn(async {
-                PreimageServer::new(
-                    OracleServer::new(preimage),
-                    HintReader::new(hint),
-                    Arc::new(backend),
-                )
-                .start()
-                .await
-                .map_err(SingleChainHostError::from)
-            })
+            task::spawn(serve_until_shutdown(
+                async {
+                    PreimageServer::new(
+                        OracleServer::new(preimage),
+                        HintReader::new(hint),
+                        Arc::new(backend),
+                    )
+                    .start()
+                    .await
+                    .map_err(SingleChainHostError::from)
+                },
+                shutdown,
+            ))
         };
 
         Ok(task_handle)
@@ -112,12 +211,7 @@ impl SingleChainHostWithEigenDA {
         &self,
     ) -> Result<SingleChainProvidersWithEigenDA, SingleChainHostError> {
         let kona_providers = self.kona_cfg.create_providers().await?;
-
-        let eigenda_preimage_provider = OnlineEigenDAPreimageProvider::new_http(
-            self.eigenda_proxy_address
-                .clone()
-                .ok_or(SingleChainHostError::Other("EigenDA API URL must be set"))?,
-        );
+        let eigenda_preimage_provider = self.create_eigenda_preimage_provider()?;
 
         Ok(SingleChainProvidersWithEigenDA {
             kona_providers,
@@ -125,13 +219,36 @@ impl SingleChainHostWithEigenDA {
         })
     }
 
+    /// Creates the eigenda preimage provider querying the eigenda proxy
+    pub fn create_eigenda_preimage_provider(
+        &self,
+    ) -> Result<OnlineEigenDAPreimageProvider, SingleChainHostError> {
+        Ok(OnlineEigenDAPreimageProvider::new_http(
+            self.eigenda_proxy_address
+                .clone()
+                .ok_or(SingleChainHostError::Other("EigenDA API URL must be set"))?,
+        )
+        .with_max_concurrent_fetches(self.eigenda_max_concurrent_fetches)
+        .with_timeout(
+            (self.eigenda_proxy_timeout > 0)
+                .then_some(Duration::from_secs(self.eigenda_proxy_timeout)),
+        )
+        .with_max_retries(self.eigenda_proxy_max_retries)
+        .with_gzip(self.eigenda_proxy_gzip))
+    }
+
     /// Starts the host in native mode, running both the client and preimage server in the same
     /// process.
     async fn start_native(&self) -> Result<(), SingleChainHostError> {
         let hint = BidirectionalChannel::new()?;
         let preimage = BidirectionalChannel::new()?;
 
-        let server_task = self.start_server(hint.host, preimage.host).await?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let server_task = self
+            .start_server_with_shutdown(hint.host, preimage.host, async {
+                let _ = shutdown_rx.await;
+            })
+            .await?;
         // Start the client program in a separate child process.
 
         let client_task = task::spawn(hokulea_client_bin::client::run_direct_client(
@@ -143,14 +260,184 @@ impl SingleChainHostWithEigenDA {
             ),
         ));
 
-        let (_, client_result) = tokio::try_join!(server_task, client_task)?;
+        let client_result = client_task.await;
+        // stop the server whether the client succeeded or not, so that the key value store is
+        // flushed before the process exits
+        let _ = shutdown_tx.send(());
+        // the server may also have stopped by itself once the client closed its channels
+        let _ = server_task.await?;
 
         // Bubble up the exit status of the client program if execution completes.
-        std::process::exit(client_result.is_err() as i32)
+        std::process::exit(client_result?.is_err() as i32)
+    }
+
+    /// Replays the eigenda derivation of a single l1 block. The preimage server always runs with
+    /// the offline backend, so every preimage must already be in the key value store.
+    async fn replay_block(&self, l1_block_number: u64) -> Result<(), SingleChainHostError> {
+        if self.kona_cfg.data_dir.is_none() {
+            return Err(SingleChainHostError::Other(
+                "replay-block requires a data directory with cached preimages",
+            ));
+        }
+
+        let hint = BidirectionalChannel::new()?;
+        let preimage = BidirectionalChannel::new()?;
+
+        let kv_store = self.kona_cfg.create_key_value_store()?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let server_task = task::spawn(serve_until_shutdown(
+            async {
+                PreimageServer::new(
+                    OracleServer::new(preimage.host),
+                    HintReader::new(hint.host),
+                    Arc::new(OfflineHostBackend::new(kv_store)),
+                )
+                .start()
+                .await
+                .map_err(SingleChainHostError::from)
+            },
+            async {
+                let _ = shutdown_rx.await;
+            },
+        ));
+
+        let client_result = task::spawn(hokulea_client_bin::client::run_replay_client(
+            OracleReader::new(preimage.client),
+            HintWriter::new(hint.client),
+            l1_block_number,
+        ))
+        .await;
+        // the server only stops by itself when the client side of the channels is gone
+        let _ = shutdown_tx.send(());
+        let _ = server_task.await;
+
+        let items = client_result?.map_err(|e| {
+            error!("failed to replay l1 block {}: {}", l1_block_number, e);
+            SingleChainHostError::Other("failed to replay l1 block")
+        })?;
+        for (i, item) in items.iter().enumerate() {
+            info!(target: "hokulea-host", "replayed item {}: {}", i, item);
+        }
+        Ok(())
+    }
+
+    /// Prefetches the eigenda preimages of the l1 blocks from start to end into the key value
+    /// store. The certs are read from the calldata sent to the batch inbox by the batcher of the
+    /// genesis system config, a batcher rotated through a system config update is not followed.
+    async fn prewarm(&self, start: u64, end: u64) -> Result<(), SingleChainHostError> {
+        if self.kona_cfg.data_dir.is_none() {
+            return Err(SingleChainHostError::Other(
+                "prewarm requires a data directory to store the preimages",
+            ));
+        }
+        if start > end {
+            return Err(SingleChainHostError::Other(
+                "prewarm requires the start block to be at most the end block",
+            ));
+        }
+
+        let rollup_config = self.kona_cfg.read_rollup_config().map_err(|e| {
+            error!("failed to read the rollup config: {}", e);
+            SingleChainHostError::Other("failed to read the rollup config")
+        })?;
+        let batch_inbox_address = rollup_config.batch_inbox_address;
+        let batcher_address = rollup_config
+            .genesis
+            .system_config
+            .as_ref()
+            .map(|system_config| system_config.batcher_address)
+            .unwrap_or_default();
+
+        let providers = self.create_providers().await?;
+        let kv_store = self.kona_cfg.create_key_value_store()?;
+        let mut num_certs = 0;
+        for l1_block_number in start..=end {
+            let block = providers
+                .kona_providers
+                .l1
+                .get_block_by_number(l1_block_number.into())
+                .full()
+                .await
+                .map_err(|e| {
+                    error!("failed to fetch l1 block {}: {}", l1_block_number, e);
+                    SingleChainHostError::Other("failed to fetch l1 block")
+                })?
+                .ok_or(SingleChainHostError::Other("l1 block not found"))?;
+            let calldata: Vec<Bytes> = block
+                .transactions
+                .txns()
+                .filter(|tx| {
+                    tx.to() == Some(batch_inbox_address) && tx.inner.signer() == batcher_address
+                })
+                .map(|tx| tx.input().clone())
+                .collect();
+
+            num_certs += prewarm_block(
+                l1_block_number,
+                &calldata,
+                self,
+                &providers.eigenda_preimage_provider,
+                kv_store.clone(),
+            )
+            .await
+            .map_err(|e| {
+                error!("failed to prewarm l1 block {}: {}", l1_block_number, e);
+                SingleChainHostError::Other("failed to prewarm l1 block")
+            })?;
+        }
+        info!(
+            target: "hokulea-host",
+            "prewarmed {} certs of l1 blocks {} to {}", num_certs, start, end
+        );
+        Ok(())
+    }
+}
+
+/// Runs the preimage server until it stops by itself or the shutdown future resolves, whichever
+/// comes first. On shutdown, the server future is dropped along with the backend it owns
+async fn serve_until_shutdown<F, S>(server: F, shutdown: S) -> Result<(), SingleChainHostError>
+where
+    F: Future<Output = Result<(), SingleChainHostError>>,
+    S: Future<Output = ()>,
+{
+    tokio::select! {
+        result = server => result,
+        _ = shutdown => {
+            info!(target: "hokulea-host", "shutting down the preimage server");
+            Ok(())
+        }
     }
 }
 
 impl SingleChainHostWithEigenDA {
+    /// Creates the host arguments without parsing the command line, for library consumers
+    /// embedding the host. Every other option takes the default value of its command line flag,
+    /// and can be changed through the public fields before starting the host
+    pub fn new(
+        kona_cfg: kona_host::single::SingleChainHost,
+        eigenda_proxy_address: Option<String>,
+        verbose: u8,
+    ) -> Self {
+        Self {
+            kona_cfg,
+            eigenda_proxy_address,
+            eigenda_max_concurrent_fetches: DEFAULT_MAX_CONCURRENT_FETCHES,
+            eigenda_proxy_timeout: DEFAULT_PROXY_TIMEOUT_SECS,
+            eigenda_proxy_max_retries: DEFAULT_PROXY_MAX_RETRIES,
+            eigenda_proxy_gzip: DEFAULT_PROXY_GZIP,
+            recency_window: None,
+            verbose,
+            command: None,
+            metrics: SharedHostMetrics::default(),
+        }
+    }
+
+    /// Sets the [HostMetrics] implementation recording the work done for eigenda hints
+    pub fn with_metrics(mut self, metrics: impl HostMetrics + 'static) -> Self {
+        self.metrics = SharedHostMetrics::new(metrics);
+        self
+    }
+
     /// Returns `true` if the host is running in offline mode.
     pub const fn is_offline(&self) -> bool {
         self.kona_cfg.is_offline() && self.eigenda_proxy_address.is_none()
@@ -169,3 +456,98 @@ pub struct SingleChainProvidersWithEigenDA {
     /// The EigenDA preimage provider
     pub eigenda_preimage_provider: OnlineEigenDAPreimageProvider,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Bytes;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // stands for the key value store, which is flushed when dropped
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_observes_shutdown_when_client_returns() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let kv_store = DropFlag(dropped.clone());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        // a server that never stops by itself
+        let server_task = task::spawn(serve_until_shutdown(
+            async move {
+                let _kv_store = kv_store;
+                std::future::pending::<Result<(), SingleChainHostError>>().await
+            },
+            async {
+                let _ = shutdown_rx.await;
+            },
+        ));
+
+        let client_task = task::spawn(async { Err::<(), _>("client failed") });
+        assert!(client_task.await.unwrap().is_err());
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        shutdown_tx.send(()).unwrap();
+        assert!(server_task.await.unwrap().is_ok());
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_new_without_clap() {
+        // a stub eigenda proxy answering every request with an empty body
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 8192];
+                let _ = socket.read(&mut buf).await.unwrap();
+                socket
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let cfg = SingleChainHostWithEigenDA::new(
+            kona_host::single::SingleChainHost::default(),
+            Some(format!("http://{addr}")),
+            2,
+        );
+        // options not passed to the constructor take the defaults of their command line flags
+        assert_eq!(
+            cfg.eigenda_max_concurrent_fetches,
+            DEFAULT_MAX_CONCURRENT_FETCHES
+        );
+        assert_eq!(cfg.eigenda_proxy_timeout, DEFAULT_PROXY_TIMEOUT_SECS);
+        assert_eq!(cfg.eigenda_proxy_max_retries, DEFAULT_PROXY_MAX_RETRIES);
+        assert_eq!(cfg.eigenda_proxy_gzip, DEFAULT_PROXY_GZIP);
+        assert_eq!(cfg.verbose, 2);
+        assert!(cfg.command.is_none());
+        assert!(!cfg.is_offline());
+
+        let provider = cfg.create_eigenda_preimage_provider().unwrap();
+        assert_eq!(provider.max_retries(), DEFAULT_PROXY_MAX_RETRIES);
+        let response = provider
+            .fetch_eigenda_encoded_payload(&Bytes::from_static(&[1, 2, 3]))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        // without a proxy address, the host is offline and has no eigenda provider
+        let cfg =
+            SingleChainHostWithEigenDA::new(kona_host::single::SingleChainHost::default(), None, 0);
+        assert!(matches!(
+            cfg.create_eigenda_preimage_provider(),
+            Err(SingleChainHostError::Other(_))
+        ));
+    }
+}
@@ -1,5 +1,21 @@
-use alloy_primitives::Bytes;
+use crate::handler::ProxyDerivationStage;
+use alloy_primitives::{map::HashMap, Bytes, B256};
 use reqwest;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Default number of proxy round-trips allowed in flight at the same time
+pub const DEFAULT_MAX_CONCURRENT_FETCHES: usize = 8;
+
+/// Default time in seconds a proxy round-trip may take, including reading the response body
+pub const DEFAULT_PROXY_TIMEOUT_SECS: u64 = 60;
+
+/// Default number of times a failed proxy round-trip is retried before the fetch fails
+pub const DEFAULT_PROXY_MAX_RETRIES: u32 = 3;
+
+/// Whether gzip encoded proxy responses are requested by default
+pub const DEFAULT_PROXY_GZIP: bool = true;
 
 /// Fetches preimage from EigenDA via an eigenda-proxy instance.
 #[derive(Debug, Clone)]
@@ -8,6 +24,22 @@ pub struct OnlineEigenDAPreimageProvider {
     base: String,
     /// The inner reqwest client. Used to talk to proxy
     inner: reqwest::Client,
+    /// Processed proxy responses keyed by altda commitment digest, kept until their preimages are
+    /// written to the key-value store. The kona host retries a hint whenever the handler returns
+    /// an error, caching avoids fetching the same encoded payload again when the failure happened
+    /// after the proxy round-trip
+    cache: Arc<Mutex<HashMap<B256, ProxyDerivationStage>>>,
+    /// Bounds the number of concurrent proxy round-trips. Hints are served concurrently, the
+    /// permits keep a burst of eigenda hints from opening an unbounded number of requests
+    fetch_permits: Arc<Semaphore>,
+    /// Number of times a failed proxy round-trip is retried, a fetch makes at most
+    /// max_retries + 1 attempts
+    max_retries: u32,
+    /// Time a proxy round-trip may take, kept to rebuild the inner client
+    timeout: Option<Duration>,
+    /// Whether the proxy is asked for gzip encoded responses, which are decompressed by the
+    /// inner client before the body is read
+    gzip: bool,
 }
 
 const GET_METHOD: &str = "get";
@@ -26,8 +58,57 @@ impl OnlineEigenDAPreimageProvider {
     /// [OnlineEigenDAPreimageProvider] will attempt to load them dynamically at runtime if they are not
     /// provided.
     pub fn new_http(base: String) -> Self {
-        let inner = reqwest::Client::new();
-        Self { base, inner }
+        let timeout = Some(Duration::from_secs(DEFAULT_PROXY_TIMEOUT_SECS));
+        Self {
+            base,
+            inner: http_client(timeout, DEFAULT_PROXY_GZIP),
+            cache: Default::default(),
+            fetch_permits: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_FETCHES)),
+            max_retries: DEFAULT_PROXY_MAX_RETRIES,
+            timeout,
+            gzip: DEFAULT_PROXY_GZIP,
+        }
+    }
+
+    /// Sets the time a proxy round-trip may take, None waits for the proxy indefinitely
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self.inner = http_client(self.timeout, self.gzip);
+        self
+    }
+
+    /// Sets whether requests send `Accept-Encoding: gzip`. A proxy advertising gzip support
+    /// answers with a compressed body, which is decompressed transparently when it is read
+    pub fn with_gzip(mut self, gzip: bool) -> Self {
+        self.gzip = gzip;
+        self.inner = http_client(self.timeout, self.gzip);
+        self
+    }
+
+    /// Sets the number of times a failed proxy round-trip is retried before the fetch fails
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Returns the number of times a failed proxy round-trip is retried
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Sets the maximal number of concurrent proxy round-trips, at least one is always allowed
+    pub fn with_max_concurrent_fetches(mut self, max_concurrent_fetches: usize) -> Self {
+        self.fetch_permits = Arc::new(Semaphore::new(max_concurrent_fetches.max(1)));
+        self
+    }
+
+    /// Waits for a free slot to run a proxy round-trip. The slot is released when the permit
+    /// is dropped, so it should be held until the response body is read
+    pub async fn acquire_fetch_permit(&self) -> SemaphorePermit<'_> {
+        self.fetch_permits
+            .acquire()
+            .await
+            .expect("fetch permits semaphore is never closed")
     }
 
     pub async fn fetch_eigenda_encoded_payload(
@@ -40,4 +121,90 @@ impl OnlineEigenDAPreimageProvider {
         );
         self.inner.get(url).send().await
     }
+
+    /// Returns the cached proxy response for the altda commitment digest, if any
+    pub fn get_cached_derivation_stage(&self, digest: &B256) -> Option<ProxyDerivationStage> {
+        self.cache.lock().unwrap().get(digest).cloned()
+    }
+
+    /// Caches a processed proxy response for the altda commitment digest
+    pub fn cache_derivation_stage(&self, digest: B256, derivation_stage: ProxyDerivationStage) {
+        self.cache.lock().unwrap().insert(digest, derivation_stage);
+    }
+
+    /// Drops the cached proxy response for the altda commitment digest, once its preimages are in
+    /// the key-value store, such that the encoded payload is not held for the rest of the run
+    pub fn remove_cached_derivation_stage(&self, digest: &B256) {
+        self.cache.lock().unwrap().remove(digest);
+    }
+}
+
+fn http_client(timeout: Option<Duration>, gzip: bool) -> reqwest::Client {
+    let builder = reqwest::Client::builder().gzip(gzip);
+    let builder = match timeout {
+        Some(timeout) => builder.timeout(timeout),
+        None => builder,
+    };
+    builder
+        .build()
+        .expect("reqwest client configuration is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // 32 zero bytes compressed with gzip
+    const GZIP_BODY: &str = "1f8b08000000000002036360c00f00ad550a1920000000";
+
+    // a fake eigenda proxy compressing its response only when the request accepts gzip
+    async fn spawn_gzip_proxy() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 8192];
+                let n = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                let (encoding, body) = if request.contains("accept-encoding: gzip") {
+                    (
+                        "content-encoding: gzip\r\n",
+                        alloy_primitives::hex::decode(GZIP_BODY).unwrap(),
+                    )
+                } else {
+                    ("", vec![0u8; 32])
+                };
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\n{encoding}content-length: {}\r\nconnection: close\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(header.as_bytes()).await.unwrap();
+                socket.write_all(&body).await.unwrap();
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn test_fetch_gzip_encoded_payload() {
+        let base = spawn_gzip_proxy().await;
+        let cert = Bytes::from_static(&[1, 2, 3]);
+
+        // gzip is requested by default, and the body is read decompressed
+        let provider = OnlineEigenDAPreimageProvider::new_http(base.clone());
+        let response = provider.fetch_eigenda_encoded_payload(&cert).await.unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(response.bytes().await.unwrap().as_ref(), &[0u8; 32]);
+
+        // the setting survives a rebuild of the client by another option
+        let provider = OnlineEigenDAPreimageProvider::new_http(base.clone())
+            .with_gzip(false)
+            .with_timeout(None);
+        let response = provider.fetch_eigenda_encoded_payload(&cert).await.unwrap();
+        assert!(response.headers().get("content-encoding").is_none());
+        assert_eq!(response.bytes().await.unwrap().as_ref(), &[0u8; 32]);
+    }
 }
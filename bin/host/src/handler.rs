@@ -1,22 +1,27 @@
 use alloy_primitives::{keccak256, Bytes};
 
 use crate::cfg::SingleChainHostWithEigenDA;
+use crate::eigenda_preimage::OnlineEigenDAPreimageProvider;
+use crate::metrics::HostMetrics;
 use crate::status_code::{DerivationError, HostHandlerError, HTTP_RESPONSE_STATUS_CODE_TEAPOT};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use eigenda_cert::AltDACommitment;
-use hokulea_eigenda::HokuleaPreimageError;
+use eigenda_cert::{AltDACommitment, BlobKey};
+use futures::future::join_all;
+use hokulea_eigenda::{cert_span, HokuleaPreimageError};
 use hokulea_eigenda::{
-    BYTES_PER_FIELD_ELEMENT, ENCODED_PAYLOAD_HEADER_LEN_BYTES,
+    EncodedPayload, Payload, ALTDA_DERIVATION_VERSION, BYTES_PER_FIELD_ELEMENT,
+    ENCODED_PAYLOAD_HEADER_LEN_BYTES, PAYLOAD_ENCODING_VERSION_0,
     RESERVED_EIGENDA_API_BYTE_FOR_RECENCY, RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY,
     RESERVED_EIGENDA_API_BYTE_INDEX,
 };
 use hokulea_proof::hint::ExtendedHintType;
-use kona_host::SharedKeyValueStore;
 use kona_host::{single::SingleChainHintHandler, HintHandler, OnlineHostBackendCfg};
+use kona_host::{KeyValueStore, SharedKeyValueStore};
 use kona_preimage::{PreimageKey, PreimageKeyType};
 use kona_proof::Hint;
-use tracing::{info, trace};
+use std::time::Instant;
+use tracing::{info, trace, warn, Instrument};
 
 /// The [HintHandler] for the [SingleChainHostWithEigenDA].
 #[derive(Debug, Clone, Copy)]
@@ -35,7 +40,7 @@ impl HintHandler for SingleChainHintHandlerWithEigenDA {
     ) -> Result<()> {
         // route the hint to the right fetcher based on the hint type.
         match hint.ty {
-            ExtendedHintType::EigenDACert => {
+            ExtendedHintType::EigenDACert | ExtendedHintType::EigenDACertWithL1Inclusion => {
                 fetch_eigenda_hint(hint, cfg, providers, kv).await?;
             }
             ExtendedHintType::Original(ty) => {
@@ -66,21 +71,184 @@ pub async fn fetch_eigenda_hint(
     providers: &<SingleChainHostWithEigenDA as OnlineHostBackendCfg>::Providers,
     kv: SharedKeyValueStore,
 ) -> Result<()> {
-    let hint_type = hint.ty;
-    let altda_commitment_bytes = hint.data;
-    trace!(target: "fetcher_with_eigenda_support", "Fetching hint: {hint_type} {altda_commitment_bytes}");
+    let (hint, l1_inclusion_bn) = split_l1_inclusion_bn(hint)?;
+    fetch_eigenda_hints(
+        vec![hint],
+        l1_inclusion_bn,
+        cfg,
+        &providers.eigenda_preimage_provider,
+        kv,
+    )
+    .await
+}
+
+/// Splits the l1 inclusion block number off an [ExtendedHintType::EigenDACertWithL1Inclusion]
+/// hint, and returns the hint of the altda commitment alone. The l1 inclusion block of an
+/// [ExtendedHintType::EigenDACert] hint is unknown.
+fn split_l1_inclusion_bn(
+    hint: Hint<<SingleChainHostWithEigenDA as OnlineHostBackendCfg>::HintType>,
+) -> Result<(
+    Hint<<SingleChainHostWithEigenDA as OnlineHostBackendCfg>::HintType>,
+    Option<u64>,
+)> {
+    if hint.ty != ExtendedHintType::EigenDACertWithL1Inclusion {
+        return Ok((hint, None));
+    }
+    let Some(altda_commitment_len) = hint.data.len().checked_sub(8) else {
+        return Err(anyhow!(
+            "eigenda hint of {} bytes is too short for the l1 inclusion block number",
+            hint.data.len()
+        ));
+    };
+    let mut l1_inclusion_bn = [0u8; 8];
+    l1_inclusion_bn.copy_from_slice(&hint.data[altda_commitment_len..]);
+    Ok((
+        Hint {
+            ty: ExtendedHintType::EigenDACert,
+            data: hint.data.slice(..altda_commitment_len),
+        },
+        Some(u64::from_be_bytes(l1_inclusion_bn)),
+    ))
+}
 
-    // Convert commitment bytes to AltDACommitment
-    let altda_commitment: AltDACommitment = altda_commitment_bytes
-        .as_ref()
-        .try_into()
-        .map_err(|e| anyhow!("failed to parse AltDACommitment: {e}"))?;
+/// Fetches the preimages of several eigenda hints, e.g. all the certs of a dense l1 block, see
+/// [fetch_eigenda_hint]. The proxy round-trips run concurrently, bounded by the fetch permits of
+/// the [OnlineEigenDAPreimageProvider], and the preimages are written under a single lock of the
+/// key-value store rather than one lock per cert.
+///
+/// If the l1 block number at which the certs are included is known, a cert which is not recent
+/// relative to it is rejected before any proxy round-trip, only its recency window is stored,
+/// which is all the client reads before discarding it.
+pub async fn fetch_eigenda_hints(
+    hints: Vec<Hint<<SingleChainHostWithEigenDA as OnlineHostBackendCfg>::HintType>>,
+    l1_inclusion_bn: Option<u64>,
+    cfg: &SingleChainHostWithEigenDA,
+    eigenda_preimage_provider: &OnlineEigenDAPreimageProvider,
+    kv: SharedKeyValueStore,
+) -> Result<()> {
+    let mut altda_commitments = Vec::with_capacity(hints.len());
+    for hint in hints {
+        let hint_type = hint.ty;
+        let altda_commitment_bytes = hint.data;
+        trace!(target: "fetcher_with_eigenda_support", "Fetching hint: {hint_type} {altda_commitment_bytes}");
 
-    store_recency_window(kv.clone(), &altda_commitment, cfg).await?;
+        // Convert commitment bytes to AltDACommitment
+        let altda_commitment: AltDACommitment = altda_commitment_bytes
+            .as_ref()
+            .try_into()
+            .map_err(|e| anyhow!("failed to parse AltDACommitment: {e}"))?;
+        altda_commitments.push((altda_commitment_bytes, altda_commitment));
+    }
 
-    // Fetch preimage data and process response
-    let derivation_stage = fetch_data_from_proxy(providers, &altda_commitment_bytes).await?;
+    // the recency window does not depend on the proxy, it is available even if a round-trip fails
+    let recency = recency_window(cfg)?;
+    {
+        let mut kv_write_lock = kv.write().await;
+        for (_, altda_commitment) in altda_commitments.iter() {
+            store_recency_window(&mut *kv_write_lock, altda_commitment, recency)?;
+        }
+    }
 
+    // see spec <https://layr-labs.github.io/eigenda/integration/spec/6-secure-integration.html#1-rbn-recency-validation>
+    if let Some(l1_inclusion_bn) = l1_inclusion_bn {
+        altda_commitments.retain(|(_, altda_commitment)| {
+            let is_recent_cert = l1_inclusion_bn <= altda_commitment.get_rbn() + recency;
+            if !is_recent_cert {
+                info!(
+                    target = "hokulea-host",
+                    "discard a cert for not being recent {} without fetching its payload",
+                    altda_commitment.to_digest(),
+                );
+            }
+            is_recent_cert
+        });
+    }
+
+    // Fetch preimage data of all certs concurrently
+    let metrics: &dyn HostMetrics = &*cfg.metrics;
+    let prefetch_start = Instant::now();
+    let derivation_stages = join_all(altda_commitments.iter().map(
+        |(altda_commitment_bytes, altda_commitment)| {
+            fetch_data_from_proxy(
+                eigenda_preimage_provider,
+                altda_commitment,
+                altda_commitment_bytes,
+                metrics,
+            )
+            .instrument(cert_span(altda_commitment))
+        },
+    ))
+    .await;
+    metrics.record_prefetch_latency(prefetch_start.elapsed());
+
+    let mut kv_write_lock = kv.write().await;
+    for ((_, altda_commitment), derivation_stage) in altda_commitments.iter().zip(derivation_stages)
+    {
+        let derivation_stage = derivation_stage?;
+        cert_span(altda_commitment).in_scope(|| {
+            store_derivation_stage(
+                &mut *kv_write_lock,
+                altda_commitment,
+                derivation_stage,
+                metrics,
+            )
+        })?;
+        eigenda_preimage_provider.remove_cached_derivation_stage(&altda_commitment.to_digest());
+    }
+
+    Ok(())
+}
+
+/// Prefetches the preimages of the certs posted in an l1 block into the key-value store, given
+/// the calldata the batcher posted to the batch inbox in that block, see [fetch_eigenda_hints].
+/// Calldata which is not an altda commitment, like a frame posted to ethereum, is skipped, and so
+/// is a commitment which fails to parse, since the client discards it without any preimage.
+/// Returns the number of certs found in the block.
+pub async fn prewarm_block(
+    l1_block_number: u64,
+    calldata: &[Bytes],
+    cfg: &SingleChainHostWithEigenDA,
+    eigenda_preimage_provider: &OnlineEigenDAPreimageProvider,
+    kv: SharedKeyValueStore,
+) -> Result<usize> {
+    let hints: Vec<_> = calldata
+        .iter()
+        .filter(|data| data.first() == Some(&ALTDA_DERIVATION_VERSION))
+        .filter_map(|data| match AltDACommitment::try_from(&data[1..]) {
+            Ok(_) => Some(Hint {
+                ty: ExtendedHintType::EigenDACert,
+                data: data.slice(1..),
+            }),
+            Err(e) => {
+                warn!(
+                    target: "hokulea-host",
+                    "skip an altda commitment of l1 block {l1_block_number} failing to parse: {e}"
+                );
+                None
+            }
+        })
+        .collect();
+    let num_certs = hints.len();
+    if num_certs > 0 {
+        fetch_eigenda_hints(
+            hints,
+            Some(l1_block_number),
+            cfg,
+            eigenda_preimage_provider,
+            kv,
+        )
+        .await?;
+    }
+    Ok(num_certs)
+}
+
+/// Stores the preimages of a cert processed by the proxy, see [fetch_eigenda_hint]
+fn store_derivation_stage(
+    kv: &mut dyn KeyValueStore,
+    altda_commitment: &AltDACommitment,
+    derivation_stage: ProxyDerivationStage,
+    metrics: &dyn HostMetrics,
+) -> Result<()> {
     // If cert is not recent, log and return early
     if !derivation_stage.is_recent_cert {
         info!(
@@ -92,12 +260,7 @@ pub async fn fetch_eigenda_hint(
     }
 
     // Write validity status to key-value store
-    store_cert_validity(
-        kv.clone(),
-        &altda_commitment,
-        derivation_stage.is_valid_cert,
-    )
-    .await?;
+    store_cert_validity(kv, altda_commitment, derivation_stage.is_valid_cert)?;
 
     // If cert is invalid, log and return early
     if !derivation_stage.is_valid_cert {
@@ -111,38 +274,39 @@ pub async fn fetch_eigenda_hint(
 
     // Store encoded payload data field-by-field in key-value store
     store_encoded_payload(
-        kv.clone(),
-        &altda_commitment,
+        kv,
+        altda_commitment,
         derivation_stage.encoded_payload,
+        metrics,
     )
-    .await?;
+}
 
-    Ok(())
+/// Returns the recency window size served to the client
+fn recency_window(cfg: &SingleChainHostWithEigenDA) -> Result<u64> {
+    // We use the sequencer_window as the recency_window, unless overridden for experimentation.
+    // See https://layr-labs.github.io/eigenda/integration/spec/6-secure-integration.html#1-rbn-recency-validation
+    // for the reasoning behind this choice.
+    match cfg.recency_window {
+        Some(recency) => Ok(recency),
+        None => Ok(cfg
+            .kona_cfg
+            .read_rollup_config()
+            .map_err(|e| anyhow!("should have been able to read rollup config {e}"))?
+            .seq_window_size),
+    }
 }
 
 /// Store recency window size in key-value store
-async fn store_recency_window(
-    kv: SharedKeyValueStore,
+fn store_recency_window(
+    kv: &mut dyn KeyValueStore,
     altda_commitment: &AltDACommitment,
-    cfg: &SingleChainHostWithEigenDA,
+    recency: u64,
 ) -> Result<()> {
-    // Acquire a lock on the key-value store
-    let mut kv_write_lock = kv.write().await;
-
-    let rollup_config = cfg
-        .kona_cfg
-        .read_rollup_config()
-        .map_err(|e| anyhow!("should have been able to read rollup config {e}"))?;
-
-    // We use the sequencer_window as the recency_window.
-    // See https://layr-labs.github.io/eigenda/integration/spec/6-secure-integration.html#1-rbn-recency-validation
-    // for the reasoning behind this choice.
-    let recency = rollup_config.seq_window_size;
     let recency_be_bytes = recency.to_be_bytes();
     let mut recency_address = altda_commitment.digest_template();
     recency_address[RESERVED_EIGENDA_API_BYTE_INDEX] = RESERVED_EIGENDA_API_BYTE_FOR_RECENCY;
 
-    kv_write_lock.set(
+    kv.set(
         PreimageKey::new(*keccak256(recency_address), PreimageKeyType::GlobalGeneric).into(),
         recency_be_bytes.to_vec(),
     )?;
@@ -161,16 +325,70 @@ pub struct ProxyDerivationStage {
     pub is_valid_cert: bool,
     // encoded_payload
     pub encoded_payload: Vec<u8>,
+    // derivation error returned by the proxy along a 418 response, None on success
+    pub derivation_error: Option<DerivationError>,
 }
 
-/// Process response from eigenda network
+/// Process response from eigenda network. A response that was processed successfully is cached
+/// by the provider until it is stored, so retrying the same hint does not query the proxy again
+/// when storing fails. The number of concurrent round-trips, their timeout and the number of
+/// retries are bounded by the provider.
 async fn fetch_data_from_proxy(
-    providers: &<SingleChainHostWithEigenDA as OnlineHostBackendCfg>::Providers,
+    eigenda_preimage_provider: &OnlineEigenDAPreimageProvider,
+    altda_commitment: &AltDACommitment,
+    altda_commitment_bytes: &Bytes,
+    metrics: &dyn HostMetrics,
+) -> Result<ProxyDerivationStage> {
+    let digest = altda_commitment.to_digest();
+    if let Some(derivation_stage) = eigenda_preimage_provider.get_cached_derivation_stage(&digest) {
+        trace!(target: "fetcher_with_eigenda_support", "Using cached proxy response for {digest}");
+        return Ok(derivation_stage);
+    }
+
+    let _permit = eigenda_preimage_provider.acquire_fetch_permit().await;
+    // the same cert may have been fetched while waiting for the permit
+    if let Some(derivation_stage) = eigenda_preimage_provider.get_cached_derivation_stage(&digest) {
+        return Ok(derivation_stage);
+    }
+
+    // a hung or failing proxy is retried a bounded number of times, such that the error surfaces
+    // instead of the round-trip blocking the hint forever
+    let max_attempts = eigenda_preimage_provider.max_retries() + 1;
+    let mut attempt = 1;
+    let derivation_stage = loop {
+        match fetch_derivation_stage(eigenda_preimage_provider, altda_commitment_bytes).await {
+            Ok(derivation_stage) => break derivation_stage,
+            Err(e) if attempt < max_attempts => {
+                metrics.record_proxy_error();
+                warn!(
+                    target: "fetcher_with_eigenda_support",
+                    "proxy round-trip {attempt}/{max_attempts} for {digest} failed, retrying: {e}"
+                );
+                attempt += 1;
+            }
+            Err(e) => {
+                metrics.record_proxy_error();
+                return Err(e.context(format!(
+                    "eigenda proxy round-trip for {digest} failed after {max_attempts} attempts"
+                )));
+            }
+        }
+    };
+    if !derivation_stage.encoded_payload.is_empty() {
+        metrics.record_blob_fetched();
+    }
+    eigenda_preimage_provider.cache_derivation_stage(digest, derivation_stage.clone());
+
+    Ok(derivation_stage)
+}
+
+/// Runs a single proxy round-trip for the altda commitment, and processes the response
+async fn fetch_derivation_stage(
+    eigenda_preimage_provider: &OnlineEigenDAPreimageProvider,
     altda_commitment_bytes: &Bytes,
 ) -> Result<ProxyDerivationStage> {
     // Fetch the encoded payload from the eigenda network
-    let response = providers
-        .eigenda_preimage_provider
+    let response = eigenda_preimage_provider
         .fetch_eigenda_encoded_payload(altda_commitment_bytes)
         .await
         .map_err(|e| anyhow!("failed to fetch eigenda encoded payload: {e}"))?;
@@ -178,12 +396,13 @@ async fn fetch_data_from_proxy(
     let mut is_valid_cert = true;
     let mut is_recent_cert = true;
     let mut encoded_payload = vec![];
+    let mut derivation_error = None;
 
     // Handle response based on status code
     if !response.status().is_success() {
         // Handle non-success response
         if response.status().as_u16() != HTTP_RESPONSE_STATUS_CODE_TEAPOT {
-            // The error is handled by host library in kona, currently this triggers an infinite retry loop.
+            // The error is retried by fetch_data_from_proxy, then handled by host library in kona.
             // https://github.com/op-rs/kona/blob/98543fe6d91f755b2383941391d93aa9bea6c9ab/bin/host/src/backend/online.rs#L135
             return Err(anyhow!(
                 "failed to fetch eigenda encoded payload, status {:?}",
@@ -196,6 +415,12 @@ async fn fetch_data_from_proxy(
             .json()
             .await
             .map_err(|e| anyhow!("failed to deserialize 418 body: {e}"))?;
+        info!(
+            target: "fetcher_with_eigenda_support",
+            "proxy returned derivation error with status code {}: {}",
+            status_code.status_code, status_code.msg,
+        );
+        derivation_error = Some(status_code.clone());
 
         match status_code.into() {
             HostHandlerError::HokuleaPreimageError(c) => match c {
@@ -221,21 +446,20 @@ async fn fetch_data_from_proxy(
         is_recent_cert,
         is_valid_cert,
         encoded_payload,
+        derivation_error,
     })
 }
 
 /// Store certificate validity in key-value store
-async fn store_cert_validity(
-    kv: SharedKeyValueStore,
+fn store_cert_validity(
+    kv: &mut dyn KeyValueStore,
     altda_commitment: &AltDACommitment,
     is_valid: bool,
 ) -> Result<()> {
-    // Acquire a lock on the key-value store
-    let mut kv_write_lock = kv.write().await;
     let mut validity_address = altda_commitment.digest_template();
     validity_address[RESERVED_EIGENDA_API_BYTE_INDEX] = RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY;
 
-    kv_write_lock.set(
+    kv.set(
         PreimageKey::new(*keccak256(validity_address), PreimageKeyType::GlobalGeneric).into(),
         vec![is_valid as u8],
     )?;
@@ -244,17 +468,48 @@ async fn store_cert_validity(
 }
 
 /// Store encoded payload data in key-value store
-async fn store_encoded_payload(
-    kv: SharedKeyValueStore,
+fn store_encoded_payload(
+    kv: &mut dyn KeyValueStore,
     altda_commitment: &AltDACommitment,
     encoded_payload: Vec<u8>,
+    metrics: &dyn HostMetrics,
 ) -> Result<()> {
-    // Acquire a lock on the key-value store
-    let mut kv_write_lock = kv.write().await;
     // encoded_payload has identical length as eigenda blob
     let blob_length_fe = altda_commitment.get_num_field_element();
+    // a blob holds at least the header field element, nothing would be stored for the client
+    if blob_length_fe == 0 {
+        return Err(anyhow!(
+            "cert {} declares a blob of zero field elements",
+            altda_commitment.to_digest()
+        ));
+    }
+    // an empty encoded payload is stored as the encoding of an empty payload, i.e. the header
+    // field element claiming a zero length followed by zero field elements
+    let encoded_payload = if encoded_payload.is_empty() {
+        warn!(
+            target = "hokulea-host",
+            "proxy returned an empty encoded payload for cert {}, storing an empty payload",
+            altda_commitment.to_digest(),
+        );
+        EncodedPayload::from_payload(&Payload::new(), PAYLOAD_ENCODING_VERSION_0)
+            .encoded_payload
+            .to_vec()
+    } else {
+        encoded_payload
+    };
     // Verify encoded_payload data is properly formatted
-    assert!(encoded_payload.len() % 32 == 0 && !encoded_payload.is_empty());
+    if encoded_payload.len() % BYTES_PER_FIELD_ELEMENT != 0 {
+        return Err(anyhow!(
+            "encoded payload of {} bytes is not a multiple of field elements",
+            encoded_payload.len()
+        ));
+    }
+    // the field elements beyond the blob declared by the cert would not be stored for the client
+    let encoded_payload = EncodedPayload::deserialize(encoded_payload.into());
+    encoded_payload
+        .assert_matches_cert(altda_commitment)
+        .map_err(|e| anyhow!("cert {}: {}", altda_commitment.to_digest(), e))?;
+    let encoded_payload = encoded_payload.serialize();
 
     // Preliminary defense check against malicious eigenda proxy host
     // Validate field elements (keeping existing field element validation for compatibility)
@@ -277,25 +532,596 @@ async fn store_encoded_payload(
 
     let fetch_num_element = (encoded_payload.len() / BYTES_PER_FIELD_ELEMENT) as u64;
     // Store each field element
-    let mut field_element_key = altda_commitment.digest_template();
+    let blob_key = BlobKey::from_commitment(altda_commitment);
     for i in 0..blob_length_fe as u64 {
-        field_element_key[72..].copy_from_slice(i.to_be_bytes().as_ref());
-        let encoded_payload_key_hash = keccak256(field_element_key.as_ref());
+        let encoded_payload_key_hash = keccak256(blob_key.with_index(i));
 
         if i < fetch_num_element {
             // Store actual encoded payload data
-            kv_write_lock.set(
+            kv.set(
                 PreimageKey::new(*encoded_payload_key_hash, PreimageKeyType::GlobalGeneric).into(),
                 encoded_payload[(i as usize) << 5..(i as usize + 1) << 5].to_vec(),
             )?;
         } else {
             // Fill remaining elements with zeros
-            kv_write_lock.set(
+            kv.set(
                 PreimageKey::new(*encoded_payload_key_hash, PreimageKeyType::GlobalGeneric).into(),
                 vec![0u8; 32],
             )?;
         }
     }
+    metrics.record_field_elements(blob_length_fe as u64);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::NoopHostMetrics;
+    use crate::status_code::ToHttpStatus;
+    use alloy_primitives::B256;
+    use eigenda_cert::test_utils::{v3_altda_commitment, v3_altda_commitment_calldata};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // a fake eigenda proxy serving every request on its own task, answering it with the status and
+    // body returned by respond, or never answering it if respond returns None
+    async fn spawn_fake_proxy_with<F, Fut>(respond: F) -> String
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Option<(u16, Vec<u8>)>> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let respond = Arc::new(respond);
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let respond = respond.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 8192];
+                    let _ = socket.read(&mut buf).await.unwrap();
+                    let Some((status, body)) = respond().await else {
+                        // keep the connection open, such that only the timeout ends the request
+                        return std::future::pending::<()>().await;
+                    };
+                    let header = format!(
+                        "HTTP/1.1 {status} {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                        reqwest::StatusCode::from_u16(status)
+                            .unwrap()
+                            .canonical_reason()
+                            .unwrap_or_default(),
+                        body.len()
+                    );
+                    socket.write_all(header.as_bytes()).await.unwrap();
+                    socket.write_all(&body).await.unwrap();
+                });
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    // a fake eigenda proxy returning a single field element encoded payload for every request,
+    // and counting how many requests it has served
+    async fn spawn_fake_proxy(num_requests: Arc<AtomicUsize>) -> String {
+        spawn_fake_proxy_with(move || {
+            num_requests.fetch_add(1, Ordering::SeqCst);
+            async { Some((200, vec![0u8; BYTES_PER_FIELD_ELEMENT])) }
+        })
+        .await
+    }
+
+    // a fake eigenda proxy serving every request after a delay, recording the highest number of
+    // requests it had in flight at once
+    async fn spawn_slow_fake_proxy(max_in_flight: Arc<AtomicUsize>) -> String {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        spawn_fake_proxy_with(move || {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            async move {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Some((200, vec![0u8; BYTES_PER_FIELD_ELEMENT]))
+            }
+        })
+        .await
+    }
+
+    // a fake eigenda proxy never answering a request, and counting how many requests it has
+    // received
+    async fn spawn_hung_fake_proxy(num_requests: Arc<AtomicUsize>) -> String {
+        spawn_fake_proxy_with(move || {
+            num_requests.fetch_add(1, Ordering::SeqCst);
+            async { None }
+        })
+        .await
+    }
+
+    // a fake eigenda proxy returning the derivation error on a teapot response for every request
+    async fn spawn_teapot_fake_proxy(derivation_error: DerivationError) -> String {
+        let body = serde_json::to_vec(&derivation_error).unwrap();
+        spawn_fake_proxy_with(move || {
+            let body = body.clone();
+            async move { Some((HTTP_RESPONSE_STATUS_CODE_TEAPOT, body)) }
+        })
+        .await
+    }
+
+    // reads the preimages the client reads for the recency window, the validity and every field
+    // element of the encoded payload of the cert
+    fn read_preimages(
+        kv: &dyn KeyValueStore,
+        altda_commitment: &AltDACommitment,
+    ) -> (Option<Vec<u8>>, Option<Vec<u8>>, Vec<Option<Vec<u8>>>) {
+        let get = |key: B256| kv.get(PreimageKey::new(*key, PreimageKeyType::GlobalGeneric).into());
+        let preimage_keys = altda_commitment.preimage_keys();
+        let blob_key = BlobKey::from_commitment(altda_commitment);
+        let field_elements = (0..altda_commitment.get_num_field_element() as u64)
+            .map(|i| get(keccak256(blob_key.with_index(i))))
+            .collect();
+        (get(preimage_keys[0]), get(preimage_keys[1]), field_elements)
+    }
+
+    #[tokio::test]
+    async fn test_store_recency_window_override() {
+        let kv: SharedKeyValueStore = Arc::new(tokio::sync::RwLock::new(
+            kona_host::MemoryKeyValueStore::new(),
+        ));
+        // no rollup config is set, the override must be used without reading it
+        let cfg = SingleChainHostWithEigenDA {
+            recency_window: Some(42),
+            ..Default::default()
+        };
+        let altda_commitment = v3_altda_commitment();
+
+        let recency = recency_window(&cfg).unwrap();
+        store_recency_window(&mut *kv.write().await, &altda_commitment, recency).unwrap();
+
+        let (recency, _, _) = read_preimages(&*kv.read().await, &altda_commitment);
+        assert_eq!(recency, Some(42u64.to_be_bytes().to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_data_from_proxy_bounded_parallelism() {
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let base = spawn_slow_fake_proxy(max_in_flight.clone()).await;
+        let provider = OnlineEigenDAPreimageProvider::new_http(base).with_max_concurrent_fetches(2);
+
+        // distinct certs, so that none of them is served from the cache
+        let altda_commitment = v3_altda_commitment();
+        let mut handles = Vec::new();
+        for i in 0..6 {
+            let mut altda_commitment = altda_commitment.clone();
+            if let eigenda_cert::EigenDAVersionedCert::V3(c) = &mut altda_commitment.versioned_cert
+            {
+                c.batch_header_v2.reference_block_number += i;
+            }
+            let provider = provider.clone();
+            handles.push(tokio::spawn(async move {
+                let altda_commitment_bytes: Bytes = altda_commitment.to_rlp_bytes().into();
+                fetch_data_from_proxy(
+                    &provider,
+                    &altda_commitment,
+                    &altda_commitment_bytes,
+                    &NoopHostMetrics,
+                )
+                .await
+            }));
+        }
+        for handle in handles {
+            assert!(handle.await.unwrap().is_ok());
+        }
+
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_data_from_proxy_uses_cache() {
+        let num_requests = Arc::new(AtomicUsize::new(0));
+        let base = spawn_fake_proxy(num_requests.clone()).await;
+        let provider = OnlineEigenDAPreimageProvider::new_http(base);
+
+        let altda_commitment_bytes = v3_altda_commitment_calldata();
+        let altda_commitment: AltDACommitment = altda_commitment_bytes.as_ref().try_into().unwrap();
+
+        let first = fetch_data_from_proxy(
+            &provider,
+            &altda_commitment,
+            &altda_commitment_bytes,
+            &NoopHostMetrics,
+        )
+        .await
+        .unwrap();
+        let second = fetch_data_from_proxy(
+            &provider,
+            &altda_commitment,
+            &altda_commitment_bytes,
+            &NoopHostMetrics,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(num_requests.load(Ordering::SeqCst), 1);
+        assert_eq!(first.encoded_payload, second.encoded_payload);
+        assert!(second.is_recent_cert && second.is_valid_cert);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_eigenda_hints_stores_all_certs() {
+        let num_requests = Arc::new(AtomicUsize::new(0));
+        let base = spawn_fake_proxy(num_requests.clone()).await;
+        let provider = OnlineEigenDAPreimageProvider::new_http(base);
+        let kv: SharedKeyValueStore = Arc::new(tokio::sync::RwLock::new(
+            kona_host::MemoryKeyValueStore::new(),
+        ));
+        let cfg = SingleChainHostWithEigenDA {
+            recency_window: Some(42),
+            ..Default::default()
+        };
+
+        // two distinct certs, as if both were included in the same l1 block
+        let altda_commitment = v3_altda_commitment();
+        let mut altda_commitments = vec![altda_commitment.clone(), altda_commitment];
+        if let eigenda_cert::EigenDAVersionedCert::V3(c) = &mut altda_commitments[1].versioned_cert
+        {
+            c.batch_header_v2.reference_block_number += 1;
+        }
+        let hints = altda_commitments
+            .iter()
+            .map(|altda_commitment| Hint {
+                ty: ExtendedHintType::EigenDACert,
+                data: altda_commitment.to_rlp_bytes().into(),
+            })
+            .collect();
+
+        fetch_eigenda_hints(hints, None, &cfg, &provider, kv.clone())
+            .await
+            .unwrap();
+        assert_eq!(num_requests.load(Ordering::SeqCst), 2);
+
+        let kv_read_lock = kv.read().await;
+        for altda_commitment in altda_commitments.iter() {
+            // the payload is not kept by the provider once stored
+            assert!(provider
+                .get_cached_derivation_stage(&altda_commitment.to_digest())
+                .is_none());
+
+            let (_, validity, field_elements) = read_preimages(&*kv_read_lock, altda_commitment);
+            assert_eq!(validity, Some(vec![1]));
+            for field_element in field_elements {
+                assert_eq!(field_element, Some(vec![0u8; BYTES_PER_FIELD_ELEMENT]));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prewarm_block_range() {
+        let num_requests = Arc::new(AtomicUsize::new(0));
+        let base = spawn_fake_proxy(num_requests.clone()).await;
+        let provider = OnlineEigenDAPreimageProvider::new_http(base);
+        let kv: SharedKeyValueStore = Arc::new(tokio::sync::RwLock::new(
+            kona_host::MemoryKeyValueStore::new(),
+        ));
+        let cfg = SingleChainHostWithEigenDA {
+            recency_window: Some(42),
+            ..Default::default()
+        };
+
+        // a two block range, each block holding a distinct cert posted by the batcher, the first
+        // block also holds a frame posted to ethereum
+        let altda_commitment = v3_altda_commitment();
+        let mut altda_commitments = vec![altda_commitment.clone(), altda_commitment];
+        if let eigenda_cert::EigenDAVersionedCert::V3(c) = &mut altda_commitments[1].versioned_cert
+        {
+            c.batch_header_v2.reference_block_number += 1;
+        }
+        let calldata = |altda_commitment: &AltDACommitment| -> Bytes {
+            let mut data = vec![ALTDA_DERIVATION_VERSION];
+            data.extend(altda_commitment.to_rlp_bytes());
+            data.into()
+        };
+        let start = altda_commitments[1].get_rbn() + 1;
+        let blocks = [
+            (
+                start,
+                vec![Bytes::from(vec![0u8; 8]), calldata(&altda_commitments[0])],
+            ),
+            (start + 1, vec![calldata(&altda_commitments[1])]),
+        ];
+        for (l1_block_number, calldata) in blocks.iter() {
+            let num_certs = prewarm_block(*l1_block_number, calldata, &cfg, &provider, kv.clone())
+                .await
+                .unwrap();
+            assert_eq!(num_certs, 1);
+        }
+        assert_eq!(num_requests.load(Ordering::SeqCst), 2);
+
+        let kv_read_lock = kv.read().await;
+        for altda_commitment in altda_commitments.iter() {
+            let (recency, validity, field_elements) =
+                read_preimages(&*kv_read_lock, altda_commitment);
+            assert_eq!(recency, Some(42u64.to_be_bytes().to_vec()));
+            assert_eq!(validity, Some(vec![1]));
+            for field_element in field_elements {
+                assert_eq!(field_element, Some(vec![0u8; BYTES_PER_FIELD_ELEMENT]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_store_encoded_payload_zero_length_blob() {
+        let mut kv = kona_host::MemoryKeyValueStore::new();
+        let mut altda_commitment = v3_altda_commitment();
+        if let eigenda_cert::EigenDAVersionedCert::V3(c) = &mut altda_commitment.versioned_cert {
+            c.blob_inclusion_info
+                .blob_certificate
+                .blob_header
+                .commitment
+                .length = 0;
+        }
+
+        let err = store_encoded_payload(
+            &mut kv,
+            &altda_commitment,
+            vec![0u8; BYTES_PER_FIELD_ELEMENT],
+            &NoopHostMetrics,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("zero field elements"));
+    }
+
+    #[test]
+    fn test_store_encoded_payload_empty_payload() {
+        let mut kv = kona_host::MemoryKeyValueStore::new();
+        let altda_commitment = v3_altda_commitment();
+
+        store_encoded_payload(&mut kv, &altda_commitment, vec![], &NoopHostMetrics).unwrap();
+
+        // the client decodes an empty payload from the stored field elements
+        let (_, _, field_elements) = read_preimages(&kv, &altda_commitment);
+        let encoded_payload = EncodedPayload {
+            encoded_payload: field_elements
+                .into_iter()
+                .flat_map(|field_element| field_element.unwrap())
+                .collect::<Vec<u8>>()
+                .into(),
+        };
+        assert!(encoded_payload.decode().unwrap().is_empty());
+
+        // a payload which is not made of field elements is rejected
+        let err =
+            store_encoded_payload(&mut kv, &altda_commitment, vec![0u8; 33], &NoopHostMetrics)
+                .unwrap_err();
+        assert!(err.to_string().contains("not a multiple of field elements"));
+
+        // a payload longer than the blob declared by the cert is rejected
+        let num_field_elements = altda_commitment.get_num_field_element() + 1;
+        let err = store_encoded_payload(
+            &mut kv,
+            &altda_commitment,
+            vec![0u8; num_field_elements * BYTES_PER_FIELD_ELEMENT],
+            &NoopHostMetrics,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("symbols"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_data_from_proxy_timeout_and_retries() {
+        let num_requests = Arc::new(AtomicUsize::new(0));
+        let base = spawn_hung_fake_proxy(num_requests.clone()).await;
+        let provider = OnlineEigenDAPreimageProvider::new_http(base)
+            .with_timeout(Some(std::time::Duration::from_millis(100)))
+            .with_max_retries(2);
+
+        let altda_commitment_bytes = v3_altda_commitment_calldata();
+        let altda_commitment: AltDACommitment = altda_commitment_bytes.as_ref().try_into().unwrap();
+
+        let err = fetch_data_from_proxy(
+            &provider,
+            &altda_commitment,
+            &altda_commitment_bytes,
+            &NoopHostMetrics,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("failed after 3 attempts"));
+        assert_eq!(num_requests.load(Ordering::SeqCst), 3);
+        // a failed fetch is not cached
+        assert!(provider
+            .get_cached_derivation_stage(&altda_commitment.to_digest())
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_eigenda_hints_skips_stale_cert() {
+        let num_requests = Arc::new(AtomicUsize::new(0));
+        let base = spawn_fake_proxy(num_requests.clone()).await;
+        let provider = OnlineEigenDAPreimageProvider::new_http(base);
+        let kv: SharedKeyValueStore = Arc::new(tokio::sync::RwLock::new(
+            kona_host::MemoryKeyValueStore::new(),
+        ));
+        let cfg = SingleChainHostWithEigenDA {
+            recency_window: Some(42),
+            ..Default::default()
+        };
+
+        let altda_commitment = v3_altda_commitment();
+        let hint = Hint {
+            ty: ExtendedHintType::EigenDACert,
+            data: v3_altda_commitment_calldata(),
+        };
+        // included right after the end of its recency window
+        let l1_inclusion_bn = altda_commitment.get_rbn() + 43;
+        fetch_eigenda_hints(
+            vec![hint],
+            Some(l1_inclusion_bn),
+            &cfg,
+            &provider,
+            kv.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(num_requests.load(Ordering::SeqCst), 0);
+
+        let (recency, validity, field_elements) =
+            read_preimages(&*kv.read().await, &altda_commitment);
+        assert_eq!(recency, Some(42u64.to_be_bytes().to_vec()));
+        assert_eq!(validity, None);
+        assert!(field_elements.iter().all(Option::is_none));
+
+        // the last block of the recency window still fetches the payload
+        let hint = Hint {
+            ty: ExtendedHintType::EigenDACert,
+            data: v3_altda_commitment_calldata(),
+        };
+        fetch_eigenda_hints(vec![hint], Some(l1_inclusion_bn - 1), &cfg, &provider, kv)
+            .await
+            .unwrap();
+        assert_eq!(num_requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_split_l1_inclusion_bn() {
+        let hint = Hint {
+            ty: ExtendedHintType::EigenDACert,
+            data: v3_altda_commitment_calldata(),
+        };
+        let (split, l1_inclusion_bn) = split_l1_inclusion_bn(hint).unwrap();
+        assert_eq!(split.ty, ExtendedHintType::EigenDACert);
+        assert_eq!(split.data, v3_altda_commitment_calldata());
+        assert_eq!(l1_inclusion_bn, None);
+
+        let hint = Hint {
+            ty: ExtendedHintType::EigenDACertWithL1Inclusion,
+            data: [&v3_altda_commitment_calldata()[..], &1234u64.to_be_bytes()]
+                .concat()
+                .into(),
+        };
+        let (split, l1_inclusion_bn) = split_l1_inclusion_bn(hint).unwrap();
+        assert_eq!(split.ty, ExtendedHintType::EigenDACert);
+        assert_eq!(split.data, v3_altda_commitment_calldata());
+        assert_eq!(l1_inclusion_bn, Some(1234));
+
+        let hint = Hint {
+            ty: ExtendedHintType::EigenDACertWithL1Inclusion,
+            data: Bytes::from(vec![0u8; 7]),
+        };
+        assert!(split_l1_inclusion_bn(hint).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_derivation_stage_retains_derivation_error() {
+        for err in [
+            HokuleaPreimageError::InvalidCert,
+            HokuleaPreimageError::NotRecentCert,
+        ] {
+            let base = spawn_teapot_fake_proxy(err.to_derivation_error()).await;
+            let provider = OnlineEigenDAPreimageProvider::new_http(base);
+
+            let derivation_stage =
+                fetch_derivation_stage(&provider, &v3_altda_commitment_calldata())
+                    .await
+                    .unwrap();
+            assert_eq!(
+                derivation_stage.derivation_error,
+                Some(err.to_derivation_error())
+            );
+            assert_eq!(
+                derivation_stage.is_valid_cert,
+                err != HokuleaPreimageError::InvalidCert
+            );
+            assert_eq!(
+                derivation_stage.is_recent_cert,
+                err != HokuleaPreimageError::NotRecentCert
+            );
+            assert!(derivation_stage.encoded_payload.is_empty());
+        }
+
+        // a successful response carries no derivation error
+        let base = spawn_fake_proxy(Arc::new(AtomicUsize::new(0))).await;
+        let provider = OnlineEigenDAPreimageProvider::new_http(base);
+        let derivation_stage = fetch_derivation_stage(&provider, &v3_altda_commitment_calldata())
+            .await
+            .unwrap();
+        assert_eq!(derivation_stage.derivation_error, None);
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingHostMetrics {
+        blobs_fetched: AtomicUsize,
+        field_elements: AtomicUsize,
+        proxy_errors: AtomicUsize,
+        prefetches: AtomicUsize,
+    }
+
+    impl HostMetrics for Arc<CountingHostMetrics> {
+        fn record_blob_fetched(&self) {
+            self.blobs_fetched.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn record_field_elements(&self, n: u64) {
+            self.field_elements.fetch_add(n as usize, Ordering::SeqCst);
+        }
+
+        fn record_proxy_error(&self) {
+            self.proxy_errors.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn record_prefetch_latency(&self, _latency: std::time::Duration) {
+            self.prefetches.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_eigenda_hints_records_metrics() {
+        let base = spawn_fake_proxy(Arc::new(AtomicUsize::new(0))).await;
+        let provider = OnlineEigenDAPreimageProvider::new_http(base);
+        let kv: SharedKeyValueStore = Arc::new(tokio::sync::RwLock::new(
+            kona_host::MemoryKeyValueStore::new(),
+        ));
+        let metrics = Arc::new(CountingHostMetrics::default());
+        let cfg = SingleChainHostWithEigenDA {
+            recency_window: Some(42),
+            ..Default::default()
+        }
+        .with_metrics(metrics.clone());
+
+        let altda_commitment = v3_altda_commitment();
+        let hint = Hint {
+            ty: ExtendedHintType::EigenDACert,
+            data: v3_altda_commitment_calldata(),
+        };
+        fetch_eigenda_hints(vec![hint], None, &cfg, &provider, kv.clone())
+            .await
+            .unwrap();
+        assert_eq!(metrics.blobs_fetched.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            metrics.field_elements.load(Ordering::SeqCst),
+            altda_commitment.get_num_field_element()
+        );
+        assert_eq!(metrics.proxy_errors.load(Ordering::SeqCst), 0);
+        assert_eq!(metrics.prefetches.load(Ordering::SeqCst), 1);
+
+        // every failed round-trip to a hung proxy is recorded
+        let base = spawn_hung_fake_proxy(Arc::new(AtomicUsize::new(0))).await;
+        let provider = OnlineEigenDAPreimageProvider::new_http(base)
+            .with_timeout(Some(std::time::Duration::from_millis(100)))
+            .with_max_retries(1);
+        let hint = Hint {
+            ty: ExtendedHintType::EigenDACert,
+            data: v3_altda_commitment_calldata(),
+        };
+        assert!(fetch_eigenda_hints(vec![hint], None, &cfg, &provider, kv)
+            .await
+            .is_err());
+        assert_eq!(metrics.proxy_errors.load(Ordering::SeqCst), 2);
+        assert_eq!(metrics.blobs_fetched.load(Ordering::SeqCst), 1);
+        assert_eq!(metrics.prefetches.load(Ordering::SeqCst), 2);
+    }
+}
@@ -4,6 +4,8 @@ pub mod cfg;
 
 pub mod handler;
 
+pub mod metrics;
+
 pub mod status_code;
 
 use tracing_subscriber::{filter::LevelFilter, prelude::*, EnvFilter};
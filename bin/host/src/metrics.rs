@@ -0,0 +1,59 @@
+//! Metrics hook of the host, letting operators of the preimage server count the work done for
+//! eigenda hints with their own metrics backend.
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// HostMetrics is called by the eigenda hint handler as it fetches and stores preimages. Every
+/// method defaults to a no-op, such that an implementation only overrides what it records
+pub trait HostMetrics: Send + Sync {
+    /// an encoded payload was downloaded from the eigenda proxy
+    fn record_blob_fetched(&self) {}
+
+    /// n field elements of an encoded payload were written to the key-value store
+    fn record_field_elements(&self, _n: u64) {}
+
+    /// a round-trip to the eigenda proxy failed, each retry is recorded
+    fn record_proxy_error(&self) {}
+
+    /// time spent fetching the preimages of a batch of eigenda hints from the proxy
+    fn record_prefetch_latency(&self, _latency: Duration) {}
+}
+
+/// NoopHostMetrics records nothing, it is the default of [SharedHostMetrics]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopHostMetrics;
+
+impl HostMetrics for NoopHostMetrics {}
+
+/// SharedHostMetrics is the [HostMetrics] implementation held by the host config, clones share
+/// the same implementation
+#[derive(Clone)]
+pub struct SharedHostMetrics(Arc<dyn HostMetrics>);
+
+impl SharedHostMetrics {
+    /// Wraps the implementation to share it across clones of the host config
+    pub fn new(metrics: impl HostMetrics + 'static) -> Self {
+        Self(Arc::new(metrics))
+    }
+}
+
+impl Default for SharedHostMetrics {
+    fn default() -> Self {
+        Self::new(NoopHostMetrics)
+    }
+}
+
+impl fmt::Debug for SharedHostMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedHostMetrics")
+    }
+}
+
+impl std::ops::Deref for SharedHostMetrics {
+    type Target = dyn HostMetrics;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
@@ -1,5 +1,5 @@
 use hokulea_eigenda::HokuleaPreimageError;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 pub const HTTP_RESPONSE_STATUS_CODE_TEAPOT: u16 = 418;
 
@@ -14,7 +14,7 @@ pub const STATUS_CODE_BLOB_DECODING_ERROR: u8 = 4;
 // code at https://github.com/Layr-Labs/eigenda/blob/4fa89635da76a0dbde6ad48f4de15c6059c7f11a/api/clients/v2/coretypes/derivation_errors.go#L10
 //
 // https://github.com/Layr-Labs/eigenda/blob/f4ef5cd5/docs/spec/src/integration/spec/6-secure-integration.md#derivation-process
-#[derive(Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DerivationError {
     #[serde(rename = "StatusCode")]
     pub status_code: u8,
@@ -62,3 +62,52 @@ impl From<DerivationError> for HostHandlerError {
         }
     }
 }
+
+/// The reverse of the conversion above, so that a host serving derivation results can emit the
+/// same status codes as the proxy
+pub trait ToHttpStatus {
+    /// HTTP status code of the response
+    fn to_http_status(&self) -> u16;
+    /// The derivation error carried as json in the response body
+    fn to_derivation_error(&self) -> DerivationError;
+}
+
+impl ToHttpStatus for HokuleaPreimageError {
+    fn to_http_status(&self) -> u16 {
+        // every derivation error is returned on a teapot response
+        HTTP_RESPONSE_STATUS_CODE_TEAPOT
+    }
+
+    fn to_derivation_error(&self) -> DerivationError {
+        let status_code = match self {
+            HokuleaPreimageError::InvalidCert => STATUS_CODE_INVALID_CERT_ERROR,
+            HokuleaPreimageError::NotRecentCert => STATUS_CODE_RECENCY_ERROR,
+        };
+        DerivationError {
+            status_code,
+            msg: self.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hokulea_preimage_error_status_code_roundtrip() {
+        for err in [
+            HokuleaPreimageError::InvalidCert,
+            HokuleaPreimageError::NotRecentCert,
+        ] {
+            assert_eq!(err.to_http_status(), HTTP_RESPONSE_STATUS_CODE_TEAPOT);
+
+            let body = serde_json::to_vec(&err.to_derivation_error()).unwrap();
+            let derivation_error: DerivationError = serde_json::from_slice(&body).unwrap();
+            assert_eq!(
+                HostHandlerError::from(derivation_error),
+                HostHandlerError::HokuleaPreimageError(err)
+            );
+        }
+    }
+}
@@ -1,5 +1,7 @@
 #![no_std]
+extern crate alloc;
 
+use alloc::vec::Vec;
 use alloy_sol_types::sol;
 
 sol! {
@@ -134,3 +136,156 @@ sol! {
     }
 
 }
+
+impl Journal {
+    /// Serializes the journal into its wire format, the bincode encoding which
+    /// [serialize_journals] applies to every journal. Unlike abi_encode, it is the encoding
+    /// committed by the zkVM guests, so it is the one to use when comparing journals
+    pub fn to_wire_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("should be able to serialize journal")
+    }
+
+    /// Deserializes a journal from the bytes produced by [Journal::to_wire_bytes]
+    pub fn from_wire_bytes(journal_bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(journal_bytes)
+    }
+}
+
+/// Serializes journals into the bytes committed by the zkVM guests. Both the guests and the canoe
+/// verifiers must go through this function, since a verifier accepts a proof only if it rebuilds
+/// byte-identical journals. Bincode keeps the bytes deserializable by the host, it is also used in
+/// op-succinct aggregate program
+/// <https://github.com/succinctlabs/op-succinct/blob/c30c5a083fdc7e2da99ece249ca2fffb7d2498e5/programs/aggregation/src/main.rs#L43>
+pub fn serialize_journals(journals: &[Journal]) -> Vec<u8> {
+    bincode::serialize(journals).expect("should be able to serialize journals")
+}
+
+/// Deserializes journals from the bytes produced by [serialize_journals]
+pub fn deserialize_journals(journals_bytes: &[u8]) -> Result<Vec<Journal>, bincode::Error> {
+    bincode::deserialize(journals_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloy_primitives::{Address, B256};
+
+    #[test]
+    fn test_serialize_journals() {
+        let journals = vec![
+            Journal {
+                blockhash: B256::repeat_byte(1),
+                certVerifierAddress: Address::repeat_byte(2),
+                input: vec![3u8; 5].into(),
+                output: true,
+                l1ChainId: 1,
+                chainConfigHash: B256::repeat_byte(4),
+            },
+            Journal::default(),
+        ];
+
+        let journals_bytes = serialize_journals(&journals);
+        // serializing a slice is byte-identical to serializing the vector it borrows from
+        assert_eq!(journals_bytes, bincode::serialize(&journals).unwrap());
+        // the canonical format starts with the number of journals as a little endian u64, a change
+        // of the bincode configuration would make guests and verifiers built apart disagree
+        assert_eq!(journals_bytes[..8], 2u64.to_le_bytes());
+        assert_eq!(serialize_journals(&journals), journals_bytes);
+        assert_eq!(serialize_journals(&[]), 0u64.to_le_bytes());
+
+        let decoded = deserialize_journals(&journals_bytes).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].input, journals[0].input);
+        assert_eq!(decoded[0].chainConfigHash, journals[0].chainConfigHash);
+        assert_eq!(decoded[1].input, journals[1].input);
+
+        assert!(deserialize_journals(&journals_bytes[..journals_bytes.len() - 1]).is_err());
+    }
+
+    // the wire bytes are pinned field by field, each fixed size byte array is prefixed by its
+    // length as a little endian u64 like a variable size one
+    #[test]
+    fn test_journal_wire_bytes_golden_vectors() {
+        let valid = Journal {
+            blockhash: B256::repeat_byte(1),
+            certVerifierAddress: Address::repeat_byte(2),
+            input: vec![3u8; 5].into(),
+            output: true,
+            l1ChainId: 1,
+            chainConfigHash: B256::repeat_byte(4),
+        };
+        let valid_golden = alloy_primitives::hex::decode(concat!(
+            // blockhash
+            "2000000000000000",
+            "0101010101010101010101010101010101010101010101010101010101010101",
+            // certVerifierAddress
+            "1400000000000000",
+            "0202020202020202020202020202020202020202",
+            // input
+            "0500000000000000",
+            "0303030303",
+            // output
+            "01",
+            // l1ChainId
+            "0100000000000000",
+            // chainConfigHash
+            "2000000000000000",
+            "0404040404040404040404040404040404040404040404040404040404040404",
+        ))
+        .unwrap();
+
+        let invalid = Journal {
+            blockhash: B256::repeat_byte(0xaa),
+            certVerifierAddress: Address::repeat_byte(0xbb),
+            input: Default::default(),
+            output: false,
+            l1ChainId: 17000,
+            chainConfigHash: B256::ZERO,
+        };
+        let invalid_golden = alloy_primitives::hex::decode(concat!(
+            // blockhash
+            "2000000000000000",
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            // certVerifierAddress
+            "1400000000000000",
+            "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+            // input
+            "0000000000000000",
+            // output
+            "00",
+            // l1ChainId
+            "6842000000000000",
+            // chainConfigHash
+            "2000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        ))
+        .unwrap();
+
+        for (journal, golden) in [(valid, valid_golden), (invalid, invalid_golden)] {
+            assert_eq!(journal.to_wire_bytes(), golden);
+            // bincode encodes a struct as its fields in declaration order, so the golden vector
+            // must match the bincode output of the fields taken one by one
+            let fields = [
+                bincode::serialize(&journal.blockhash).unwrap(),
+                bincode::serialize(&journal.certVerifierAddress).unwrap(),
+                bincode::serialize(&journal.input).unwrap(),
+                bincode::serialize(&journal.output).unwrap(),
+                bincode::serialize(&journal.l1ChainId).unwrap(),
+                bincode::serialize(&journal.chainConfigHash).unwrap(),
+            ];
+            assert_eq!(fields.concat(), golden);
+            let decoded = Journal::from_wire_bytes(&golden).unwrap();
+            assert_eq!(decoded.to_wire_bytes(), golden);
+            assert_eq!(decoded.output, journal.output);
+            assert_eq!(decoded.l1ChainId, journal.l1ChainId);
+
+            // the journals committed by the zkVM are the wire bytes of each journal after their count
+            assert_eq!(
+                serialize_journals(core::slice::from_ref(&journal)),
+                [1u64.to_le_bytes().to_vec(), golden.clone()].concat()
+            );
+            assert!(Journal::from_wire_bytes(&golden[..golden.len() - 1]).is_err());
+        }
+    }
+}
@@ -1,9 +1,13 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use alloy_primitives::{Address, B256};
-use eigenda_cert::AltDACommitment;
+use canoe_bindings::{serialize_journals, Journal};
+use canoe_verifier::{mock_journal, CanoeProofBackend, CanoeVerifier, CertValidity};
+use eigenda_cert::{AltDACommitment, AltDACommitmentParseError, EigenDAVersionedCert, G1Point};
+
+use crate::ProofTimings;
 
 /// CanoeInput contains all the necessary data to create a ZK proof
 /// attesting the validity of a cert within an altda commitment
@@ -26,6 +30,306 @@ pub struct CanoeInput {
     /// verifier_address must not be manipulated by the zkvm host. It can be set either with a single router address or a set of
     /// fixed cert verifier address
     pub verifier_address: Address,
+    /// kzg commitment that the blob of the cert is expected to have, taken from the encoded payload
+    /// entry which is checked by batch_verify. When provided, the zk view proof asserts that the cert
+    /// commitment is identical, such that cert validity and blob consistency refer to the same blob
+    #[serde(default)]
+    pub expected_kzg_commitment: Option<G1Point>,
+}
+
+/// CanoeInputError is returned when a canoe input is malformed, or when a batch of canoe inputs
+/// cannot be proven by a single proof
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CanoeInputError {
+    #[error("no canoe input is provided")]
+    Empty,
+    #[error("canoe input contains a zero l1_head_block_hash")]
+    ZeroL1HeadBlockHash,
+    #[error("canoe input contains a zero l1_chain_id")]
+    ZeroL1ChainId,
+    #[error("canoe input contains a zero verifier_address")]
+    ZeroVerifierAddress,
+    #[error("canoe input contains a {0} cert, which has no cert verifier interface")]
+    UnsupportedCertVersion(&'static str),
+    #[error("canoe input contains an altda commitment which does not parse: {0}")]
+    InvalidAltDACommitment(AltDACommitmentParseError),
+    #[error("cert kzg commitment differs from expected kzg commitment")]
+    KzgCommitmentMismatch,
+    #[error("canoe input {index} has l1 chain id {found}, expected {expected}")]
+    L1ChainIdMismatch {
+        index: usize,
+        expected: u64,
+        found: u64,
+    },
+    #[error("canoe input {index} has l1 head block number {found}, expected {expected}")]
+    L1HeadBlockNumberMismatch {
+        index: usize,
+        expected: u64,
+        found: u64,
+    },
+    #[error("canoe input {index} has l1 head block hash {found}, expected {expected}")]
+    L1HeadBlockHashMismatch {
+        index: usize,
+        expected: B256,
+        found: B256,
+    },
+}
+
+impl CanoeInput {
+    /// validate checks the canoe input is well formed before any proving effort is spent on it.
+    /// A zero l1_head_block_hash can never be the anchor of a legit view call, it usually means the
+    /// boot info was not populated correctly. Likewise a zero l1_chain_id or verifier_address points
+    /// to a misconfigured host, and the altda commitment must parse back from its rlp bytes, which
+    /// is the form the zk guest and the verifier call consume. A V1 cert parses, but cannot be
+    /// proven since no cert verifier is deployed for it. The kzg commitment of the cert must match
+    /// the expected one, see [CanoeInput::check_kzg_commitment]
+    pub fn validate(&self) -> Result<(), CanoeInputError> {
+        if self.l1_head_block_hash == B256::ZERO {
+            return Err(CanoeInputError::ZeroL1HeadBlockHash);
+        }
+        if self.l1_chain_id == 0 {
+            return Err(CanoeInputError::ZeroL1ChainId);
+        }
+        if self.verifier_address == Address::ZERO {
+            return Err(CanoeInputError::ZeroVerifierAddress);
+        }
+        if let EigenDAVersionedCert::V1(_) = self.altda_commitment.versioned_cert {
+            return Err(CanoeInputError::UnsupportedCertVersion(
+                self.altda_commitment.cert_version_str(),
+            ));
+        }
+        AltDACommitment::try_from(self.altda_commitment.to_rlp_bytes().as_slice())
+            .map_err(CanoeInputError::InvalidAltDACommitment)?;
+        self.check_kzg_commitment()
+    }
+
+    /// l1_context_matches ensures all canoe inputs share the l1 context a single proof is anchored at,
+    /// i.e. the same l1_chain_id, l1_head_block_number and l1_head_block_hash as the first canoe input.
+    /// It returns the first mismatch encountered
+    pub fn l1_context_matches(inputs: &[CanoeInput]) -> Result<(), CanoeInputError> {
+        let Some(first) = inputs.first() else {
+            return Err(CanoeInputError::Empty);
+        };
+        for (index, canoe_input) in inputs.iter().enumerate().skip(1) {
+            if canoe_input.l1_chain_id != first.l1_chain_id {
+                return Err(CanoeInputError::L1ChainIdMismatch {
+                    index,
+                    expected: first.l1_chain_id,
+                    found: canoe_input.l1_chain_id,
+                });
+            }
+            if canoe_input.l1_head_block_number != first.l1_head_block_number {
+                return Err(CanoeInputError::L1HeadBlockNumberMismatch {
+                    index,
+                    expected: first.l1_head_block_number,
+                    found: canoe_input.l1_head_block_number,
+                });
+            }
+            if canoe_input.l1_head_block_hash != first.l1_head_block_hash {
+                return Err(CanoeInputError::L1HeadBlockHashMismatch {
+                    index,
+                    expected: first.l1_head_block_hash,
+                    found: canoe_input.l1_head_block_hash,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// to_cert_validity returns the cert validity the client holds for the canoe input. The chain
+    /// config hash is left to be populated from the canoe receipt
+    pub fn to_cert_validity(&self) -> CertValidity {
+        CertValidity {
+            claimed_validity: self.claimed_validity,
+            l1_head_block_hash: self.l1_head_block_hash,
+            l1_chain_id: self.l1_chain_id,
+            chain_config_hash: None,
+            verifier_address: self.verifier_address,
+        }
+    }
+
+    /// check_kzg_commitment ensures the kzg commitment of the cert matches expected_kzg_commitment,
+    /// if provided. It is asserted by the zk guests before any view call, and checked by the host
+    /// before proving, such that a mismatch fails early instead of failing in the guest
+    pub fn check_kzg_commitment(&self) -> Result<(), CanoeInputError> {
+        if let Some(expected_kzg_commitment) = self.expected_kzg_commitment {
+            if self.altda_commitment.get_kzg_commitment() != expected_kzg_commitment {
+                return Err(CanoeInputError::KzgCommitmentMismatch);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// dedup_canoe_inputs drops the repeated occurrences of a canoe input, keeping the first one, such
+/// that a cert appearing in several l1 blocks of a derivation window is proven once. Canoe inputs
+/// are identified by the digest of the altda commitment along with their cert validity, mirroring
+/// [canoe_verifier::dedup_cert_validity_pairs] which verifiers apply before building journals
+pub fn dedup_canoe_inputs(inputs: Vec<CanoeInput>) -> Vec<CanoeInput> {
+    let mut seen: Vec<(B256, CertValidity)> = vec![];
+    inputs
+        .into_iter()
+        .filter(|canoe_input| {
+            let key = (
+                canoe_input.altda_commitment.to_digest(),
+                canoe_input.to_cert_validity(),
+            );
+            if seen.contains(&key) {
+                false
+            } else {
+                seen.push(key);
+                true
+            }
+        })
+        .collect()
+}
+
+/// batch_canoe_inputs groups canoe inputs into consecutive batches, such that each batch can be proven
+/// by a single call to [CanoeProvider::create_certs_validity_proof] without its journal growing past
+/// max_journal_bytes. The cost of a canoe input is the length of the rlp encoded cert, which is the
+/// dominant part of the journal. The order of canoe inputs is preserved across batches.
+/// A canoe input whose cost alone exceeds max_journal_bytes is placed in its own batch
+pub fn batch_canoe_inputs(
+    inputs: Vec<CanoeInput>,
+    max_journal_bytes: usize,
+) -> Vec<Vec<CanoeInput>> {
+    let mut batches = vec![];
+    let mut batch = vec![];
+    let mut batch_bytes = 0;
+    for canoe_input in inputs {
+        let cost = canoe_input.altda_commitment.to_rlp_bytes().len();
+        if !batch.is_empty() && batch_bytes + cost > max_journal_bytes {
+            batches.push(core::mem::take(&mut batch));
+            batch_bytes = 0;
+        }
+        batch_bytes += cost;
+        batch.push(canoe_input);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+/// CanoeInputGroup identifies the canoe inputs anchored at a common l1 block. A single canoe proof
+/// can cover several groups, each having its own view of the l1 state at l1_head_block_number
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanoeInputGroup {
+    /// l1 chain id shared by all canoe inputs of the group
+    pub l1_chain_id: u64,
+    /// l1 block number shared by all canoe inputs of the group
+    pub l1_head_block_number: u64,
+    /// l1 block hash shared by all canoe inputs of the group
+    pub l1_head_block_hash: B256,
+}
+
+impl CanoeInputGroup {
+    /// contains returns true if the canoe input is anchored at the l1 block of the group
+    pub fn contains(&self, canoe_input: &CanoeInput) -> bool {
+        self.l1_chain_id == canoe_input.l1_chain_id
+            && self.l1_head_block_number == canoe_input.l1_head_block_number
+    }
+
+    /// check_anchor_hash ensures the anchor hash returned by a view call executed against the l1
+    /// state of the group is the block hash the canoe inputs of the group are anchored at
+    pub fn check_anchor_hash(&self, anchor_hash: B256) -> Result<()> {
+        if anchor_hash != self.l1_head_block_hash {
+            bail!(
+                "anchor hash {} differs from l1 head block hash {} at l1 block number {}",
+                anchor_hash,
+                self.l1_head_block_hash,
+                self.l1_head_block_number
+            );
+        }
+        Ok(())
+    }
+}
+
+/// AnchorGroup is a [CanoeInputGroup] along with the positions of its canoe inputs within the
+/// grouped slice, in increasing order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorGroup {
+    /// l1 block the canoe inputs of the group are anchored at
+    pub anchor: CanoeInputGroup,
+    /// indices of the canoe inputs of the group
+    pub indices: Vec<usize>,
+}
+
+/// group_inputs_by_anchor groups canoe inputs by (l1_chain_id, l1_head_block_number,
+/// l1_head_block_hash), ordered by first appearance, such that both the zk host and guest derive
+/// the identical groups. It rejects an empty input, and canoe inputs sharing an l1 block number
+/// but claiming different l1 block hashes, in which case the first input of the group is expected
+pub fn group_inputs_by_anchor(inputs: &[CanoeInput]) -> Result<Vec<AnchorGroup>, CanoeInputError> {
+    if inputs.is_empty() {
+        return Err(CanoeInputError::Empty);
+    }
+    let mut groups: Vec<AnchorGroup> = vec![];
+    for (index, canoe_input) in inputs.iter().enumerate() {
+        match groups
+            .iter_mut()
+            .find(|group| group.anchor.contains(canoe_input))
+        {
+            Some(group) => {
+                if group.anchor.l1_head_block_hash != canoe_input.l1_head_block_hash {
+                    return Err(CanoeInputError::L1HeadBlockHashMismatch {
+                        index,
+                        expected: group.anchor.l1_head_block_hash,
+                        found: canoe_input.l1_head_block_hash,
+                    });
+                }
+                group.indices.push(index);
+            }
+            None => groups.push(AnchorGroup {
+                anchor: CanoeInputGroup {
+                    l1_chain_id: canoe_input.l1_chain_id,
+                    l1_head_block_number: canoe_input.l1_head_block_number,
+                    l1_head_block_hash: canoe_input.l1_head_block_hash,
+                },
+                indices: vec![index],
+            }),
+        }
+    }
+    Ok(groups)
+}
+
+/// group_canoe_inputs returns the distinct l1 blocks canoe inputs are anchored at, see
+/// [group_inputs_by_anchor]. No canoe input yields no group
+pub fn group_canoe_inputs(inputs: &[CanoeInput]) -> Result<Vec<CanoeInputGroup>> {
+    if inputs.is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(group_inputs_by_anchor(inputs)?
+        .into_iter()
+        .map(|group| group.anchor)
+        .collect())
+}
+
+/// anchor_canoe_inputs anchors all canoe inputs at the supplied l1 block, overwriting the
+/// (l1_head_block_hash, l1_head_block_number) carried by each input, see
+/// [CanoeProvider::create_certs_validity_proof_at_anchor]
+pub fn anchor_canoe_inputs(
+    canoe_inputs: Vec<CanoeInput>,
+    l1_head_block_hash: B256,
+    l1_head_block_number: u64,
+) -> Vec<CanoeInput> {
+    canoe_inputs
+        .into_iter()
+        .map(|mut canoe_input| {
+            canoe_input.l1_head_block_hash = l1_head_block_hash;
+            canoe_input.l1_head_block_number = l1_head_block_number;
+            canoe_input
+        })
+        .collect()
+}
+
+/// ProvingEstimate is the cost of proving a batch of canoe inputs, measured by executing the zk
+/// guest without proving
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvingEstimate {
+    /// number of instructions executed by the zk guest
+    pub cycles: u64,
+    /// gas charged by the prover network, if the backend computes it
+    pub prover_gas: Option<u64>,
 }
 
 #[async_trait]
@@ -39,12 +343,52 @@ pub trait CanoeProvider: Clone + Send + 'static {
     /// at a certain block number on the verifier address.
     ///
     /// If the input does not contain any canoe_input to prove against, it returns None
-    /// All canoe CanoeInput must share common (l1_chain_id, l1_head_block_number)
+    /// A backend may require all CanoeInput to share common (l1_chain_id, l1_head_block_number), see
+    /// [group_canoe_inputs] for backends which support multiple l1 blocks
     async fn create_certs_validity_proof(
         &self,
         _canoe_inputs: Vec<CanoeInput>,
     ) -> Option<Result<Self::Receipt>>;
 
+    /// create_certs_validity_proof_at_anchor is identical to create_certs_validity_proof, except all canoe
+    /// inputs are anchored at the supplied (l1_head_block_hash, l1_head_block_number) rather than the one
+    /// carried by each input. It is meant for integrators which already hold the L1 header from the
+    /// derivation context. A backend which can load the l1 state by block hash overrides it, such that
+    /// the block number is never resolved by the rpc and a reorg cannot move the anchor. Either way, the
+    /// provider rejects the inputs if the state served by its rpc is not the one of the anchor
+    async fn create_certs_validity_proof_at_anchor(
+        &self,
+        canoe_inputs: Vec<CanoeInput>,
+        l1_head_block_hash: B256,
+        l1_head_block_number: u64,
+    ) -> Option<Result<Self::Receipt>> {
+        self.create_certs_validity_proof(anchor_canoe_inputs(
+            canoe_inputs,
+            l1_head_block_hash,
+            l1_head_block_number,
+        ))
+        .await
+    }
+
+    /// create_certs_validity_proof_with_timings is identical to create_certs_validity_proof, and also
+    /// returns the time spent on each phase of the proof generation. Providers which do not measure
+    /// their phases report zero timings, which is the default
+    async fn create_certs_validity_proof_with_timings(
+        &self,
+        canoe_inputs: Vec<CanoeInput>,
+    ) -> Option<Result<(Self::Receipt, ProofTimings)>> {
+        self.create_certs_validity_proof(canoe_inputs)
+            .await
+            .map(|receipt| receipt.map(|receipt| (receipt, ProofTimings::default())))
+    }
+
+    /// estimate returns the cost of proving the canoe inputs with create_certs_validity_proof, such
+    /// that callers can budget before committing to a full proof. It returns None if the backend
+    /// cannot estimate, or if there is nothing to prove against
+    async fn estimate(&self, _canoe_inputs: Vec<CanoeInput>) -> Option<Result<ProvingEstimate>> {
+        None
+    }
+
     /// get_config_hash allows getting l1 config hash from receipt. Note some backend like steel does not
     /// need it, and return None. It is up to the implementer to decide if its CanoeProvider provides it.
     /// Within the client program, sp1-cc allows custom genesis, whereas steel provides only a few genesis
@@ -56,6 +400,65 @@ pub trait CanoeProvider: Clone + Send + 'static {
 
     /// get_recursive_proof returns the zk proof which can be recursively verified by zk vm
     fn get_recursive_proof(&self, receipt: &Self::Receipt) -> Option<Self::Proof>;
+
+    /// proof_backend returns the backend tag to prefix the serialized recursive proof with, such
+    /// that the verifier can reject a proof from another backend. None if no proof is ever produced,
+    /// which is the default
+    fn proof_backend(&self) -> Option<CanoeProofBackend> {
+        None
+    }
+
+    /// resolved_chain_config describes the genesis or chain spec the provider would prove against
+    /// for the l1 chain id, e.g. whether it is built into the zk library or a custom one shipped by
+    /// hokulea. It lets integrators log and compare it against their expectation before proving,
+    /// and returns an error if the provider does not support the chain. Providers which do not
+    /// override it report the chain config as unsupported
+    fn resolved_chain_config(&self, l1_chain_id: u64) -> Result<String> {
+        bail!("provider does not report the chain config it uses for chain id {l1_chain_id}")
+    }
+}
+
+/// prove_and_verify creates a canoe proof for the canoe inputs with the provider, then verifies it with
+/// the verifier against cert validity pairs rebuilt from the same canoe inputs. It is meant for tooling
+/// which checks a proof right after producing it, and returns at the first failure of either step
+pub async fn prove_and_verify<P: CanoeProvider, V: CanoeVerifier>(
+    provider: &P,
+    verifier: &V,
+    canoe_inputs: Vec<CanoeInput>,
+) -> Result<()> {
+    let mut cert_validity_pairs: Vec<(AltDACommitment, CertValidity)> = canoe_inputs
+        .iter()
+        .map(|canoe_input| {
+            (
+                canoe_input.altda_commitment.clone(),
+                canoe_input.to_cert_validity(),
+            )
+        })
+        .collect();
+
+    let canoe_proof_bytes = match provider.create_certs_validity_proof(canoe_inputs).await {
+        Some(receipt) => {
+            let receipt = receipt?;
+            let chain_config_hash = provider.get_config_hash(&receipt);
+            cert_validity_pairs
+                .iter_mut()
+                .for_each(|(_, cert_validity)| cert_validity.chain_config_hash = chain_config_hash);
+            match provider.get_recursive_proof(&receipt) {
+                Some(recursive_proof) => {
+                    let proof_bytes = serde_json::to_vec(&recursive_proof)?;
+                    Some(match provider.proof_backend() {
+                        Some(proof_backend) => proof_backend.tag(proof_bytes),
+                        None => proof_bytes,
+                    })
+                }
+                None => None,
+            }
+        }
+        None => None,
+    };
+
+    verifier.validate_cert_receipt(cert_validity_pairs, canoe_proof_bytes)?;
+    Ok(())
 }
 
 #[derive(Clone)]
@@ -80,4 +483,701 @@ impl CanoeProvider for CanoeNoOpProvider {
     fn get_recursive_proof(&self, _receipt: &Self::Receipt) -> Option<Self::Proof> {
         None
     }
+
+    fn resolved_chain_config(&self, l1_chain_id: u64) -> Result<String> {
+        Ok(format!(
+            "noop provider proves nothing, no chain config for chain id {l1_chain_id}"
+        ))
+    }
+}
+
+/// CanoeMockProvider produces a mock proof embedding the journals it would have proven, which is
+/// accepted by [canoe_verifier::CanoeMockVerifier]. The output of every journal is the claimed
+/// validity of the canoe input unless the cert is listed in invalid_certs, since no cert is ever
+/// evaluated, so it must only be used in tests. Like the zk guests, it refuses to prove a canoe
+/// input whose cert does not carry the expected kzg commitment
+#[derive(Clone, Debug, Default)]
+pub struct CanoeMockProvider {
+    /// digests of the certs the mock attests to be invalid whatever their claimed validity, such
+    /// that tests can exercise the rejection of a false claim
+    pub invalid_certs: Vec<B256>,
+}
+
+#[async_trait]
+impl CanoeProvider for CanoeMockProvider {
+    type Receipt = Vec<u8>;
+    type Proof = Vec<u8>;
+
+    async fn create_certs_validity_proof(
+        &self,
+        canoe_inputs: Vec<CanoeInput>,
+    ) -> Option<Result<Self::Receipt>> {
+        if canoe_inputs.is_empty() {
+            return None;
+        }
+        if let Err(e) = canoe_inputs
+            .iter()
+            .try_for_each(CanoeInput::check_kzg_commitment)
+        {
+            return Some(Err(e.into()));
+        }
+
+        let journals: Vec<Journal> = dedup_canoe_inputs(canoe_inputs)
+            .iter()
+            .map(|canoe_input| {
+                let mut journal = mock_journal(
+                    &canoe_input.altda_commitment,
+                    &canoe_input.to_cert_validity(),
+                );
+                if self
+                    .invalid_certs
+                    .contains(&canoe_input.altda_commitment.to_digest())
+                {
+                    journal.output = false;
+                }
+                journal
+            })
+            .collect();
+        Some(Ok(serialize_journals(&journals)))
+    }
+
+    fn get_config_hash(&self, _receipt: &Self::Receipt) -> Option<B256> {
+        None
+    }
+
+    fn get_recursive_proof(&self, receipt: &Self::Receipt) -> Option<Self::Proof> {
+        Some(receipt.clone())
+    }
+
+    fn proof_backend(&self) -> Option<CanoeProofBackend> {
+        Some(CanoeProofBackend::Mock)
+    }
+
+    fn resolved_chain_config(&self, l1_chain_id: u64) -> Result<String> {
+        Ok(format!(
+            "mock provider evaluates no chain config for chain id {l1_chain_id}"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CertVerifierCall;
+    use alloy_primitives::U256;
+    use canoe_verifier::{CanoeMockVerifier, HokuleaCanoeVerificationError};
+    use eigenda_cert::test_utils::v3_altda_commitment;
+    use eigenda_cert::v1_cert::{
+        BatchHeaderV1, BatchMetadataV1, BlobHeaderV1, BlobQuorumParamV1, BlobVerificationProofV1,
+        G1CommitmentV1,
+    };
+    use eigenda_cert::BlobInfoV1;
+
+    fn canoe_input_with_block_hash(l1_head_block_hash: B256) -> CanoeInput {
+        CanoeInput {
+            altda_commitment: v3_altda_commitment(),
+            claimed_validity: true,
+            l1_head_block_hash,
+            l1_head_block_number: 1,
+            l1_chain_id: 1,
+            verifier_address: Address::repeat_byte(1),
+            expected_kzg_commitment: None,
+        }
+    }
+
+    fn v1_blob_info() -> BlobInfoV1 {
+        BlobInfoV1 {
+            blob_header: BlobHeaderV1 {
+                commitment: G1CommitmentV1 {
+                    x: vec![1u8; 32].into(),
+                    y: vec![2u8; 32].into(),
+                },
+                data_length: 16,
+                blob_quorum_params: vec![BlobQuorumParamV1 {
+                    quorum_number: 0,
+                    adversary_threshold_percentage: 33,
+                    confirmation_threshold_percentage: 55,
+                    chunk_length: 1,
+                }],
+            },
+            blob_verification_proof: BlobVerificationProofV1 {
+                batch_id: 7,
+                blob_index: 3,
+                batch_metadata: BatchMetadataV1 {
+                    batch_header: BatchHeaderV1 {
+                        batch_root: vec![3u8; 32].into(),
+                        quorum_numbers: vec![0].into(),
+                        quorum_signed_percentages: vec![100].into(),
+                        reference_block_number: 1234,
+                    },
+                    signatory_record_hash: vec![4u8; 32].into(),
+                    fee: vec![0].into(),
+                    confirmation_block_number: 1300,
+                    batch_header_hash: vec![5u8; 32].into(),
+                },
+                inclusion_proof: vec![6u8; 32].into(),
+                quorum_indexes: vec![0].into(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_certs_validity_proof_at_anchor() {
+        let anchor_hash = B256::repeat_byte(7);
+        let anchor_number = 42;
+        let canoe_inputs = vec![
+            canoe_input_with_block_hash(B256::repeat_byte(1)),
+            canoe_input_with_block_hash(B256::repeat_byte(2)),
+        ];
+
+        let anchored = anchor_canoe_inputs(canoe_inputs.clone(), anchor_hash, anchor_number);
+        assert_eq!(anchored.len(), 2);
+        for canoe_input in anchored {
+            assert_eq!(canoe_input.l1_head_block_hash, anchor_hash);
+            assert_eq!(canoe_input.l1_head_block_number, anchor_number);
+        }
+
+        // the journals proven by the default implementation are anchored at the supplied block
+        let receipt = CanoeMockProvider::default()
+            .create_certs_validity_proof_at_anchor(canoe_inputs, anchor_hash, anchor_number)
+            .await
+            .unwrap()
+            .unwrap();
+        let journals = canoe_bindings::deserialize_journals(&receipt).unwrap();
+        assert_eq!(journals.len(), 1);
+        assert_eq!(journals[0].blockhash, anchor_hash);
+    }
+
+    #[test]
+    fn test_batch_canoe_inputs() {
+        let canoe_inputs: Vec<CanoeInput> = (1..=5)
+            .map(|i| canoe_input_with_block_hash(B256::repeat_byte(i)))
+            .collect();
+        let cost = canoe_inputs[0].altda_commitment.to_rlp_bytes().len();
+
+        // limit, expected batch sizes
+        let cases = [
+            (2 * cost, vec![2, 2, 1]),
+            (2 * cost + 1, vec![2, 2, 1]),
+            (5 * cost, vec![5]),
+            // a single cert beyond the limit still gets proven on its own
+            (cost - 1, vec![1, 1, 1, 1, 1]),
+        ];
+
+        for (max_journal_bytes, expected) in cases {
+            let batches = batch_canoe_inputs(canoe_inputs.clone(), max_journal_bytes);
+            assert_eq!(
+                batches.iter().map(|batch| batch.len()).collect::<Vec<_>>(),
+                expected
+            );
+            for batch in &batches {
+                let batch_bytes: usize = batch
+                    .iter()
+                    .map(|canoe_input| canoe_input.altda_commitment.to_rlp_bytes().len())
+                    .sum();
+                assert!(batch.len() == 1 || batch_bytes <= max_journal_bytes);
+            }
+            // all inputs are preserved in order
+            let block_hashes: Vec<B256> = batches
+                .into_iter()
+                .flatten()
+                .map(|canoe_input| canoe_input.l1_head_block_hash)
+                .collect();
+            assert_eq!(
+                block_hashes,
+                canoe_inputs
+                    .iter()
+                    .map(|canoe_input| canoe_input.l1_head_block_hash)
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        assert!(batch_canoe_inputs(vec![], cost).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prove_and_verify() {
+        let canoe_input = canoe_input_with_block_hash(B256::repeat_byte(1));
+        let verifier = CanoeMockVerifier::default();
+
+        assert!(prove_and_verify(
+            &CanoeMockProvider::default(),
+            &verifier,
+            vec![canoe_input.clone()]
+        )
+        .await
+        .is_ok());
+
+        // the tampered cert still claims to be valid, but the provider cannot attest it
+        let mut tampered = canoe_input.clone();
+        let EigenDAVersionedCert::V3(cert) = &mut tampered.altda_commitment.versioned_cert else {
+            panic!("test cert should be a v3 cert");
+        };
+        cert.blob_inclusion_info
+            .blob_certificate
+            .blob_header
+            .commitment
+            .commitment
+            .x += U256::from(1);
+        let provider = CanoeMockProvider {
+            invalid_certs: vec![tampered.altda_commitment.to_digest()],
+        };
+        let err = prove_and_verify(&provider, &verifier, vec![canoe_input, tampered])
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<HokuleaCanoeVerificationError>(),
+            Some(HokuleaCanoeVerificationError::InconsistentPublicJournal)
+        ));
+
+        // without any proof the verifier rejects
+        let canoe_input = canoe_input_with_block_hash(B256::repeat_byte(1));
+        assert!(
+            prove_and_verify(&CanoeNoOpProvider {}, &verifier, vec![canoe_input])
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_group_canoe_inputs() {
+        let first = canoe_input_with_block_hash(B256::repeat_byte(1));
+        let mut second = canoe_input_with_block_hash(B256::repeat_byte(2));
+        second.l1_head_block_number = 2;
+        let canoe_inputs = vec![first.clone(), second.clone(), first.clone()];
+
+        let groups = group_canoe_inputs(&canoe_inputs).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].l1_head_block_hash, first.l1_head_block_hash);
+        assert_eq!(groups[1].l1_head_block_hash, second.l1_head_block_hash);
+
+        // every canoe input belongs to exactly one group
+        for canoe_input in canoe_inputs.iter() {
+            assert_eq!(
+                groups
+                    .iter()
+                    .filter(|group| group.contains(canoe_input))
+                    .count(),
+                1
+            );
+        }
+
+        // anchor hash is checked against the block hash of each group
+        assert!(groups[0]
+            .check_anchor_hash(first.l1_head_block_hash)
+            .is_ok());
+        assert!(groups[1]
+            .check_anchor_hash(second.l1_head_block_hash)
+            .is_ok());
+        assert!(groups[0]
+            .check_anchor_hash(second.l1_head_block_hash)
+            .is_err());
+        assert!(groups[1]
+            .check_anchor_hash(first.l1_head_block_hash)
+            .is_err());
+
+        // same l1 block number with a different block hash is rejected
+        let mut conflicting = first.clone();
+        conflicting.l1_head_block_hash = B256::repeat_byte(3);
+        assert!(group_canoe_inputs(&[first, conflicting]).is_err());
+
+        assert!(group_canoe_inputs(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_group_inputs_by_anchor() {
+        // canoe inputs sharing an anchor form a single group
+        let first = canoe_input_with_block_hash(B256::repeat_byte(1));
+        let canoe_inputs = vec![first.clone(), first.clone(), first.clone()];
+        let groups = group_inputs_by_anchor(&canoe_inputs).unwrap();
+        assert_eq!(
+            groups,
+            vec![AnchorGroup {
+                anchor: CanoeInputGroup {
+                    l1_chain_id: first.l1_chain_id,
+                    l1_head_block_number: first.l1_head_block_number,
+                    l1_head_block_hash: first.l1_head_block_hash,
+                },
+                indices: vec![0, 1, 2],
+            }]
+        );
+
+        // distinct l1 blocks form distinct groups, ordered by first appearance
+        let mut second = canoe_input_with_block_hash(B256::repeat_byte(2));
+        second.l1_head_block_number = 2;
+        let groups =
+            group_inputs_by_anchor(&[second.clone(), first.clone(), second.clone()]).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].anchor.l1_head_block_number, 2);
+        assert_eq!(groups[0].indices, vec![0, 2]);
+        assert_eq!(groups[1].indices, vec![1]);
+
+        // an l1 block claimed with two block hashes is inconsistent
+        let mut conflicting = first.clone();
+        conflicting.l1_head_block_hash = B256::repeat_byte(3);
+        assert_eq!(
+            group_inputs_by_anchor(&[first.clone(), second, conflicting]),
+            Err(CanoeInputError::L1HeadBlockHashMismatch {
+                index: 2,
+                expected: first.l1_head_block_hash,
+                found: B256::repeat_byte(3),
+            })
+        );
+
+        assert_eq!(group_inputs_by_anchor(&[]), Err(CanoeInputError::Empty));
+    }
+
+    #[test]
+    fn test_check_kzg_commitment() {
+        let mut canoe_input = canoe_input_with_block_hash(B256::repeat_byte(1));
+        assert_eq!(canoe_input.check_kzg_commitment(), Ok(()));
+
+        canoe_input.expected_kzg_commitment =
+            Some(canoe_input.altda_commitment.get_kzg_commitment());
+        assert_eq!(canoe_input.check_kzg_commitment(), Ok(()));
+        assert_eq!(canoe_input.validate(), Ok(()));
+
+        let mut mismatched_kzg_commitment = canoe_input.altda_commitment.get_kzg_commitment();
+        mismatched_kzg_commitment.x += U256::from(1);
+        canoe_input.expected_kzg_commitment = Some(mismatched_kzg_commitment);
+        assert_eq!(
+            canoe_input.check_kzg_commitment(),
+            Err(CanoeInputError::KzgCommitmentMismatch)
+        );
+        assert_eq!(
+            canoe_input.validate(),
+            Err(CanoeInputError::KzgCommitmentMismatch)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_rejects_kzg_commitment_mismatch() {
+        let mut canoe_input = canoe_input_with_block_hash(B256::repeat_byte(1));
+        let mut mismatched_kzg_commitment = canoe_input.altda_commitment.get_kzg_commitment();
+        mismatched_kzg_commitment.y += U256::from(1);
+        canoe_input.expected_kzg_commitment = Some(mismatched_kzg_commitment);
+
+        // the mock provider asserts the kzg commitment as the zk guests do, nothing is proven
+        let err = CanoeMockProvider::default()
+            .create_certs_validity_proof(vec![canoe_input.clone()])
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<CanoeInputError>(),
+            Some(&CanoeInputError::KzgCommitmentMismatch)
+        );
+
+        canoe_input.expected_kzg_commitment =
+            Some(canoe_input.altda_commitment.get_kzg_commitment());
+        assert!(CanoeMockProvider::default()
+            .create_certs_validity_proof(vec![canoe_input])
+            .await
+            .unwrap()
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_default_proof_timings() {
+        let canoe_input = canoe_input_with_block_hash(B256::repeat_byte(1));
+        let provider = CanoeMockProvider::default();
+
+        // the mock provider does not measure its phases, its receipt is returned with zero timings
+        let (receipt, timings) = provider
+            .create_certs_validity_proof_with_timings(vec![canoe_input.clone()])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            receipt,
+            provider
+                .create_certs_validity_proof(vec![canoe_input])
+                .await
+                .unwrap()
+                .unwrap()
+        );
+        assert_eq!(timings, ProofTimings::default());
+
+        assert!(provider
+            .create_certs_validity_proof_with_timings(vec![])
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn test_validate() {
+        struct Case {
+            input: CanoeInput,
+            result: Result<(), CanoeInputError>,
+        }
+
+        let valid = canoe_input_with_block_hash(B256::repeat_byte(1));
+        let mut zero_l1_chain_id = valid.clone();
+        zero_l1_chain_id.l1_chain_id = 0;
+        let mut zero_verifier_address = valid.clone();
+        zero_verifier_address.verifier_address = Address::ZERO;
+        let mut malformed_altda_commitment = valid.clone();
+        if let eigenda_cert::EigenDAVersionedCert::V3(c) =
+            &mut malformed_altda_commitment.altda_commitment.versioned_cert
+        {
+            c.nonsigner_stake_and_signature.apk_g2.x.truncate(1);
+        }
+        let mut v1_cert = valid.clone();
+        v1_cert.altda_commitment.versioned_cert = EigenDAVersionedCert::V1(v1_blob_info());
+
+        let cases = [
+            Case {
+                input: canoe_input_with_block_hash(B256::ZERO),
+                result: Err(CanoeInputError::ZeroL1HeadBlockHash),
+            },
+            Case {
+                input: zero_l1_chain_id,
+                result: Err(CanoeInputError::ZeroL1ChainId),
+            },
+            Case {
+                input: zero_verifier_address,
+                result: Err(CanoeInputError::ZeroVerifierAddress),
+            },
+            Case {
+                input: malformed_altda_commitment,
+                result: Err(CanoeInputError::InvalidAltDACommitment(
+                    AltDACommitmentParseError::MalformedG2Point { x_len: 1, y_len: 2 },
+                )),
+            },
+            Case {
+                input: v1_cert,
+                result: Err(CanoeInputError::UnsupportedCertVersion("V1")),
+            },
+            Case {
+                input: valid,
+                result: Ok(()),
+            },
+        ];
+
+        for case in cases {
+            assert_eq!(case.input.validate(), case.result);
+        }
+    }
+
+    #[test]
+    fn test_cert_verifier_call_rejects_v1() {
+        let mut canoe_input = canoe_input_with_block_hash(B256::repeat_byte(1));
+        assert!(CertVerifierCall::build(&canoe_input.altda_commitment).is_ok());
+
+        canoe_input.altda_commitment.versioned_cert = EigenDAVersionedCert::V1(v1_blob_info());
+        assert!(matches!(
+            CertVerifierCall::build(&canoe_input.altda_commitment),
+            Err(CanoeInputError::UnsupportedCertVersion("V1"))
+        ));
+    }
+
+    #[test]
+    fn test_l1_context_matches() {
+        let first = canoe_input_with_block_hash(B256::repeat_byte(1));
+        assert!(CanoeInput::l1_context_matches(&[first.clone(), first.clone()]).is_ok());
+        assert_eq!(
+            CanoeInput::l1_context_matches(&[]),
+            Err(CanoeInputError::Empty)
+        );
+
+        let mut second = first.clone();
+        second.l1_head_block_hash = B256::repeat_byte(2);
+        let mut third = first.clone();
+        third.l1_chain_id = 2;
+        // the first mismatch is reported
+        assert_eq!(
+            CanoeInput::l1_context_matches(&[first.clone(), first.clone(), second, third]),
+            Err(CanoeInputError::L1HeadBlockHashMismatch {
+                index: 2,
+                expected: B256::repeat_byte(1),
+                found: B256::repeat_byte(2),
+            })
+        );
+
+        let mut second = first.clone();
+        second.l1_head_block_number = 2;
+        assert_eq!(
+            CanoeInput::l1_context_matches(&[first, second]),
+            Err(CanoeInputError::L1HeadBlockNumberMismatch {
+                index: 1,
+                expected: 1,
+                found: 2,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_and_verifier() {
+        let canoe_inputs = vec![
+            canoe_input_with_block_hash(B256::repeat_byte(1)),
+            canoe_input_with_block_hash(B256::repeat_byte(1)),
+        ];
+        prove_and_verify(
+            &CanoeMockProvider::default(),
+            &CanoeMockVerifier::default(),
+            canoe_inputs.clone(),
+        )
+        .await
+        .unwrap();
+        // the mock provider executes no guest to estimate
+        assert!(CanoeMockProvider::default()
+            .estimate(canoe_inputs.clone())
+            .await
+            .is_none());
+
+        let receipt = CanoeMockProvider::default()
+            .create_certs_validity_proof(canoe_inputs.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        let canoe_proof = CanoeProofBackend::Mock.tag(serde_json::to_vec(&receipt).unwrap());
+        let mut cert_validity_pairs: Vec<(AltDACommitment, CertValidity)> = canoe_inputs
+            .iter()
+            .map(|canoe_input| {
+                (
+                    canoe_input.altda_commitment.clone(),
+                    canoe_input.to_cert_validity(),
+                )
+            })
+            .collect();
+        assert!(CanoeMockVerifier::default()
+            .validate_cert_receipt(cert_validity_pairs.clone(), Some(canoe_proof.clone()))
+            .is_ok());
+
+        // the proof attests the cert is valid, the host claims otherwise
+        cert_validity_pairs[1].1.claimed_validity = false;
+        assert!(matches!(
+            CanoeMockVerifier::default()
+                .validate_cert_receipt(cert_validity_pairs.clone(), Some(canoe_proof.clone())),
+            Err(HokuleaCanoeVerificationError::InconsistentPublicJournal)
+        ));
+        cert_validity_pairs[1].1.claimed_validity = true;
+
+        // a tampered proof embeds journals other than the claimed ones
+        let mut tampered_receipt = receipt.clone();
+        let last = tampered_receipt.len() - 1;
+        tampered_receipt[last] ^= 1;
+        let tampered_proof =
+            CanoeProofBackend::Mock.tag(serde_json::to_vec(&tampered_receipt).unwrap());
+        assert!(matches!(
+            CanoeMockVerifier::default()
+                .validate_cert_receipt(cert_validity_pairs.clone(), Some(tampered_proof)),
+            Err(HokuleaCanoeVerificationError::InconsistentPublicJournal)
+        ));
+
+        // a proof tagged with another backend is rejected
+        let steel_proof = CanoeProofBackend::Steel.tag(serde_json::to_vec(&receipt).unwrap());
+        assert!(matches!(
+            CanoeMockVerifier::default()
+                .validate_cert_receipt(cert_validity_pairs, Some(steel_proof)),
+            Err(HokuleaCanoeVerificationError::UnsupportedProofBackend(0))
+        ));
+    }
+
+    #[test]
+    fn test_proof_backend() {
+        // a provider producing no proof relies on the default
+        assert_eq!(CanoeNoOpProvider {}.proof_backend(), None);
+        assert_eq!(
+            CanoeMockProvider::default().proof_backend(),
+            Some(CanoeProofBackend::Mock)
+        );
+    }
+
+    #[test]
+    fn test_resolved_chain_config_unsupported_by_default() {
+        // a provider implementing only the required methods
+        #[derive(Clone)]
+        struct MinimalProvider {}
+
+        #[async_trait]
+        impl CanoeProvider for MinimalProvider {
+            type Receipt = ();
+            type Proof = ();
+
+            async fn create_certs_validity_proof(
+                &self,
+                _canoe_inputs: Vec<CanoeInput>,
+            ) -> Option<Result<Self::Receipt>> {
+                None
+            }
+
+            fn get_config_hash(&self, _receipt: &Self::Receipt) -> Option<B256> {
+                None
+            }
+
+            fn get_recursive_proof(&self, _receipt: &Self::Receipt) -> Option<Self::Proof> {
+                None
+            }
+        }
+
+        assert!(MinimalProvider {}.resolved_chain_config(1).is_err());
+        assert!(CanoeMockProvider::default()
+            .resolved_chain_config(1)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_dedup_canoe_inputs() {
+        let canoe_input = canoe_input_with_block_hash(B256::repeat_byte(1));
+        let mut invalid_claim = canoe_input.clone();
+        invalid_claim.claimed_validity = false;
+        let canoe_inputs = vec![
+            canoe_input.clone(),
+            invalid_claim.clone(),
+            canoe_input.clone(),
+        ];
+
+        // the second occurrence of the cert with the same validity is dropped
+        let deduped = dedup_canoe_inputs(canoe_inputs.clone());
+        assert_eq!(deduped.len(), 2);
+        assert!(deduped[0].claimed_validity);
+        assert!(!deduped[1].claimed_validity);
+
+        // the same cert included twice in a witness is proven once
+        let canoe_inputs = vec![canoe_input.clone(), canoe_input];
+        let receipt = CanoeMockProvider::default()
+            .create_certs_validity_proof(canoe_inputs.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            canoe_bindings::deserialize_journals(&receipt)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // both occurrences verify against the single proven journal
+        let cert_validity_pairs: Vec<(AltDACommitment, CertValidity)> = canoe_inputs
+            .iter()
+            .map(|canoe_input| {
+                (
+                    canoe_input.altda_commitment.clone(),
+                    canoe_input.to_cert_validity(),
+                )
+            })
+            .collect();
+        let canoe_proof = CanoeProofBackend::Mock.tag(serde_json::to_vec(&receipt).unwrap());
+        assert!(CanoeMockVerifier::default()
+            .validate_cert_receipt(cert_validity_pairs, Some(canoe_proof.clone()))
+            .is_ok());
+
+        // an occurrence with another validity is not covered by the proof
+        let cert_validity_pairs = vec![
+            (
+                invalid_claim.altda_commitment.clone(),
+                canoe_inputs[0].to_cert_validity(),
+            ),
+            (
+                invalid_claim.altda_commitment.clone(),
+                invalid_claim.to_cert_validity(),
+            ),
+        ];
+        assert!(matches!(
+            CanoeMockVerifier::default()
+                .validate_cert_receipt(cert_validity_pairs, Some(canoe_proof)),
+            Err(HokuleaCanoeVerificationError::InconsistentPublicJournal)
+        ));
+    }
 }
@@ -1,5 +1,12 @@
 pub mod canoe_provider;
-pub use canoe_provider::{CanoeInput, CanoeNoOpProvider, CanoeProvider};
+pub use canoe_provider::{
+    anchor_canoe_inputs, batch_canoe_inputs, dedup_canoe_inputs, group_canoe_inputs,
+    group_inputs_by_anchor, prove_and_verify, AnchorGroup, CanoeInput, CanoeInputError,
+    CanoeInputGroup, CanoeMockProvider, CanoeNoOpProvider, CanoeProvider, ProvingEstimate,
+};
+
+pub mod proof_timings;
+pub use proof_timings::{PhaseTimer, ProofTimings};
 
 pub mod verifier_caller;
 pub use verifier_caller::CertVerifierCall;
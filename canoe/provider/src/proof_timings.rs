@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+/// ProofTimings breaks down the time spent generating a canoe proof by phase. Preflight fetches the
+/// l1 state touched by the view calls, finalize turns the fetched state into the zk guest input, and
+/// prove runs the zk prover, or only executes the guest in mock mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProofTimings {
+    /// time spent on fetching the l1 state and executing the view calls on the host
+    pub preflight: Duration,
+    /// time spent on building the zk guest input from the fetched state
+    pub finalize: Duration,
+    /// time spent on proving
+    pub prove: Duration,
+}
+
+impl ProofTimings {
+    /// total returns the time spent across all phases
+    pub fn total(&self) -> Duration {
+        self.preflight + self.finalize + self.prove
+    }
+}
+
+/// PhaseTimer measures consecutive phases, each lap returns the time elapsed since the previous lap
+#[derive(Debug)]
+pub struct PhaseTimer {
+    last: Instant,
+}
+
+impl PhaseTimer {
+    /// start begins measuring the first phase
+    pub fn start() -> Self {
+        Self {
+            last: Instant::now(),
+        }
+    }
+
+    /// lap ends the current phase and begins the next one
+    pub fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last);
+        self.last = now;
+        elapsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn test_phase_timer_records_each_phase() {
+        let phase = Duration::from_millis(5);
+        let mut timings = ProofTimings::default();
+        let mut timer = PhaseTimer::start();
+
+        // mock a proof generation with a preflight and a prove phase, but an instant finalize
+        sleep(phase);
+        timings.preflight = timer.lap();
+        timings.finalize = timer.lap();
+        sleep(phase);
+        timings.prove = timer.lap();
+
+        assert!(timings.preflight >= phase);
+        assert!(timings.prove >= phase);
+        assert!(timings.finalize < timings.preflight);
+        assert_eq!(
+            timings.total(),
+            timings.preflight + timings.finalize + timings.prove
+        );
+    }
+}
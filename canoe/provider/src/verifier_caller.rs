@@ -1,3 +1,4 @@
+use crate::CanoeInputError;
 use alloy_sol_types::SolValue;
 use canoe_bindings::{IEigenDACertVerifier, IEigenDACertVerifierBase};
 use eigenda_cert::{AltDACommitment, EigenDAVersionedCert};
@@ -15,8 +16,15 @@ pub enum CertVerifierCall {
 
 impl CertVerifierCall {
     /// convert eigenda cert type into its solidity type that works with solidity cert verifier interface
-    pub fn build(altda_commitment: &AltDACommitment) -> Self {
-        match &altda_commitment.versioned_cert {
+    /// V1 cert has no cert verifier interface, and is rejected with
+    /// [CanoeInputError::UnsupportedCertVersion]
+    pub fn build(altda_commitment: &AltDACommitment) -> Result<Self, CanoeInputError> {
+        Ok(match &altda_commitment.versioned_cert {
+            EigenDAVersionedCert::V1(_) => {
+                return Err(CanoeInputError::UnsupportedCertVersion(
+                    altda_commitment.cert_version_str(),
+                ))
+            }
             EigenDAVersionedCert::V2(cert) => CertVerifierCall::LegacyV2Interface(
                 IEigenDACertVerifier::verifyDACertV2ForZKProofCall {
                     batchHeader: cert.batch_header_v2.to_sol(),
@@ -31,6 +39,6 @@ impl CertVerifierCall {
                     abiEncodedCert: v3_soltype_cert.abi_encode().into(),
                 })
             }
-        }
+        })
     }
 }
@@ -3,54 +3,72 @@ sp1_zkvm::entrypoint!(main);
 
 use alloy_primitives::Address;
 use alloy_sol_types::{sol_data::Bool, SolType};
-use canoe_bindings::{Journal, StatusCode};
-use canoe_provider::{CanoeInput, CertVerifierCall};
+use canoe_bindings::{serialize_journals, Journal, StatusCode};
+use canoe_provider::{group_canoe_inputs, CanoeInput, CertVerifierCall};
 use sp1_cc_client_executor::{io::EvmSketchInput, AnchorType, ClientExecutor, ContractInput};
 
 pub fn main() {
-    // Read the state sketch from stdin. Use this during the execution in order to
-    // access Ethereum state.
-    let state_sketch_bytes = sp1_zkvm::io::read::<Vec<u8>>();
-    let state_sketch = bincode::deserialize::<EvmSketchInput>(&state_sketch_bytes)
-        .expect("should be able to deserialize evm sketch state");
+    // Read the state sketches from stdin, one per distinct l1 block the canoe inputs are anchored at.
+    // Use them during the execution in order to access Ethereum state.
+    let state_sketches_bytes = sp1_zkvm::io::read::<Vec<Vec<u8>>>();
 
     // read a list of canoe inputs and prove them all together in one sp1-cc proof
     let canoe_inputs = sp1_zkvm::io::read::<Vec<CanoeInput>>();
-
-    // ensure all canoe_proof uses identical l1 chain id and l1 head block number
     assert!(!canoe_inputs.is_empty());
 
-    let l1_chain_id_from_canoe_input = canoe_inputs[0].l1_chain_id;
-    let l1_head_block_number = canoe_inputs[0].l1_head_block_number;
-    let l1_head_block_hash = canoe_inputs[0].l1_head_block_hash;
-    // require all canoe input share a common l1_chain_id
+    // the cert must carry the kzg commitment of the blob checked by batch_verify, if provided
     for canoe_input in canoe_inputs.iter() {
-        assert!(canoe_input.l1_chain_id == l1_chain_id_from_canoe_input);
-        assert!(canoe_input.l1_head_block_number == l1_head_block_number);
-        assert!(canoe_input.l1_head_block_hash == l1_head_block_hash);
+        canoe_input
+            .check_kzg_commitment()
+            .expect("cert kzg commitment should match the expected one");
     }
 
-    // Initialize the client executor with the state sketch.
-    // This step also validates all of the storage against state root provided by the host
-    let executor =
-        ClientExecutor::eth(&state_sketch).expect("should be able to initialize client executor");
-
-    // l1_head_block_number identical to executor's number
-    assert_eq!(l1_head_block_number, executor.header.number);
+    // group canoe inputs by (l1_chain_id, l1_head_block_number), canoe inputs within a group must share
+    // a common l1_head_block_hash. The host orders the state sketches identically to the groups
+    let groups = group_canoe_inputs(&canoe_inputs).expect("canoe inputs should be groupable");
+    assert_eq!(groups.len(), state_sketches_bytes.len());
 
-    // l1_chain_id is committed to the journal that would be compared aginast the journal generated by
-    // the hokulea program. Chain ID is checked implicitly
-    let l1_chain_id = executor.chain_spec.chain().id();
-    assert!(l1_chain_id_from_canoe_input == l1_chain_id);
+    // Initialize one client executor per group with its state sketch.
+    // This step also validates all of the storage against state root provided by the host
+    let state_sketches: Vec<EvmSketchInput> = state_sketches_bytes
+        .iter()
+        .map(|state_sketch_bytes| {
+            bincode::deserialize::<EvmSketchInput>(state_sketch_bytes)
+                .expect("should be able to deserialize evm sketch state")
+        })
+        .collect();
+    let mut executors = vec![];
+    for (group, state_sketch) in groups.iter().zip(state_sketches.iter()) {
+        let executor = ClientExecutor::eth(state_sketch)
+            .expect("should be able to initialize client executor");
+
+        // l1_head_block_number identical to executor's number
+        assert_eq!(group.l1_head_block_number, executor.header.number);
+
+        // l1_chain_id is committed to the journal that would be compared aginast the journal generated by
+        // the hokulea program. Chain ID is checked implicitly
+        assert!(group.l1_chain_id == executor.chain_spec.chain().id());
+        executors.push(executor);
+    }
 
     // Those journals are pushed into a vector and later serialized in a byte array which can be committed
     // by the zkVM. To verify if zkVM has produced the proof for the exact serialized journals, canoe verifier
     // verifies the zkVM proof against the commited journals.
     let mut journals: Vec<Journal> = vec![];
     // executes all calls, then combines and commits all journals
+    // journals are committed in the order of canoe inputs, regardless of their groups
     for canoe_input in canoe_inputs.iter() {
+        let group_index = groups
+            .iter()
+            .position(|group| group.contains(canoe_input))
+            .expect("every canoe input belongs to a group");
+        let group = &groups[group_index];
+        let executor = &executors[group_index];
+
         let (returns, anchor_hash, chain_config_hash, anchor_type) =
-            match CertVerifierCall::build(&canoe_input.altda_commitment) {
+            match CertVerifierCall::build(&canoe_input.altda_commitment)
+                .expect("cert should have a cert verifier interface")
+            {
                 CertVerifierCall::LegacyV2Interface(call) => {
                     let call = ContractInput::new_call(
                         canoe_input.verifier_address,
@@ -117,24 +135,25 @@ pub fn main() {
 
         let rlp_bytes = canoe_input.altda_commitment.to_rlp_bytes();
 
-        assert!(anchor_hash == l1_head_block_hash);
+        // anchor hash is checked against the l1 block of the group the canoe input belongs to
+        group
+            .check_anchor_hash(anchor_hash)
+            .expect("anchor hash should match the l1 head block hash of the group");
 
         let journal = Journal {
             certVerifierAddress: canoe_input.verifier_address,
             input: rlp_bytes.into(),
             blockhash: anchor_hash,
             output: returns,
-            l1ChainId: l1_chain_id,
+            l1ChainId: group.l1_chain_id,
             chainConfigHash: chain_config_hash,
         };
         journals.push(journal);
     }
 
-    // use bincode to serialize, such that it can be deserialized to parse the commited content. All the
-    // material are vector, and said to be deterministic.
-    // bincode is also used in op-succinct aggregate program
-    // https://github.com/succinctlabs/op-succinct/blob/c30c5a083fdc7e2da99ece249ca2fffb7d2498e5/programs/aggregation/src/main.rs#L43
-    let journals_bytes = bincode::serialize(&journals).expect("should be able to serialize");
+    // serialize with the function shared with the canoe verifier, such that the verifier rebuilds
+    // byte-identical journals
+    let journals_bytes = serialize_journals(&journals);
 
     // Commit journals altogether
     sp1_zkvm::io::commit_slice(&journals_bytes);
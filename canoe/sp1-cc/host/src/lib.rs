@@ -1,31 +1,39 @@
-use alloy_primitives::{Address, B256};
+use alloy_primitives::{keccak256, Address, B256};
 use alloy_rpc_types::BlockNumberOrTag;
 use alloy_sol_types::{sol_data::Bool, SolType};
 use anyhow::Result;
 use async_trait::async_trait;
-use canoe_bindings::{Journal, StatusCode};
-use canoe_provider::{CanoeInput, CanoeProvider, CertVerifierCall};
-use sp1_cc_client_executor::ContractInput;
+use canoe_bindings::{deserialize_journals, IEigenDACertVerifierBase, Journal, StatusCode};
+use canoe_provider::{
+    dedup_canoe_inputs, group_inputs_by_anchor, CanoeInput, CanoeInputGroup, CanoeProvider,
+    CertVerifierCall, PhaseTimer, ProofTimings, ProvingEstimate,
+};
+use canoe_verifier::CanoeProofBackend;
+use sp1_cc_client_executor::{ClientExecutor, ContractInput};
 use sp1_cc_host_executor::{EvmSketch, Genesis};
 use sp1_sdk::{
-    network::FulfillmentStrategy, Prover, ProverClient, SP1Proof, SP1ProofMode,
-    SP1ProofWithPublicValues, SP1Stdin, SP1_CIRCUIT_VERSION,
-};
-use std::{
-    env,
-    str::FromStr,
-    time::{Duration, Instant},
+    network::FulfillmentStrategy, ExecutionReport, NetworkProver, Prover, ProverClient, SP1Proof,
+    SP1ProofMode, SP1ProofWithPublicValues, SP1PublicValues, SP1Stdin, SP1_CIRCUIT_VERSION,
 };
+use std::{env, str::FromStr, time::Duration};
 use tracing::{info, warn};
 use url::Url;
 
 use rsp_primitives::genesis::genesis_from_json;
 
+pub mod onchain;
+pub use onchain::{CanoeSp1CCOnchainProvider, CanoeSp1CCOnchainVerifier, OnchainProofMode};
+
+pub mod sketch_cache;
+pub use sketch_cache::EvmSketchCache;
+
 /// The ELF we want to execute inside the zkVM.
 pub const ELF: &[u8] = include_bytes!("../../elf/canoe-sp1-cc-client");
 
 const DEFAULT_NETWORK_PRIVATE_KEY: &str =
     "0x0000000000000000000000000000000000000000000000000000000000000001";
+const NETWORK_PRIVATE_KEY_ENV: &str = "NETWORK_PRIVATE_KEY";
+const NETWORK_RPC_URL_ENV: &str = "NETWORK_RPC_URL";
 const SP1_CC_PROOF_STRATEGY_ENV: &str = "SP1_CC_PROOF_STRATEGY";
 
 /// Get the fulfillment strategy from the environment variable
@@ -62,6 +70,14 @@ pub struct CanoeSp1CCProvider {
     pub eth_rpc_url: String,
     /// if true, execute and return a mock proof
     pub mock_mode: bool,
+    /// if set, evm sketches are reused across proofs at the same l1 block, see [EvmSketchCache]
+    pub sketch_cache: Option<EvmSketchCache>,
+    /// rpc endpoint of the sp1 prover network, falls back to the NETWORK_RPC_URL env variable,
+    /// then to the default endpoint of sp1-sdk
+    pub network_rpc_url: Option<String>,
+    /// private key requesting proofs from the sp1 prover network, falls back to the
+    /// NETWORK_PRIVATE_KEY env variable
+    pub private_key: Option<String>,
 }
 
 #[async_trait]
@@ -73,28 +89,72 @@ impl CanoeProvider for CanoeSp1CCProvider {
         &self,
         canoe_inputs: Vec<CanoeInput>,
     ) -> Option<Result<Self::Receipt>> {
+        self.create_certs_validity_proof_with_timings(canoe_inputs)
+            .await
+            .map(|proof| proof.map(|(receipt, _)| receipt))
+    }
+
+    async fn create_certs_validity_proof_with_timings(
+        &self,
+        canoe_inputs: Vec<CanoeInput>,
+    ) -> Option<Result<(Self::Receipt, ProofTimings)>> {
         // if there is nothing to prove against return early
         if canoe_inputs.is_empty() {
             return None;
         }
 
-        Some(get_sp1_cc_proof(canoe_inputs, &self.eth_rpc_url, self.mock_mode).await)
+        Some(
+            get_sp1_cc_proof(
+                canoe_inputs,
+                &self.eth_rpc_url,
+                self.mock_mode,
+                SP1ProofMode::Compressed,
+                self.sketch_cache.as_ref(),
+                &NetworkSettings::resolve(
+                    self.network_rpc_url.as_deref(),
+                    self.private_key.as_deref(),
+                ),
+            )
+            .await,
+        )
     }
 
-    fn get_config_hash(&self, receipt: &Self::Receipt) -> Option<B256> {
-        let journals: Vec<Journal> = bincode::deserialize(receipt.public_values.as_slice())
-            .expect("should be able to deserialize to journals");
-        assert!(!journals.is_empty());
-        let chain_config_hash = journals[0].chainConfigHash;
-        for journal in journals {
-            assert_eq!(chain_config_hash, journal.chainConfigHash);
+    /// The guest is executed without proving regardless of mock_mode, since it is the execution
+    /// done by mock mode which reports the cycles and the prover gas
+    async fn estimate(&self, canoe_inputs: Vec<CanoeInput>) -> Option<Result<ProvingEstimate>> {
+        if canoe_inputs.is_empty() {
+            return None;
         }
-        Some(chain_config_hash)
+
+        Some(
+            estimate_sp1_cc_proof(
+                canoe_inputs,
+                &self.eth_rpc_url,
+                self.sketch_cache.as_ref(),
+                &NetworkSettings::resolve(
+                    self.network_rpc_url.as_deref(),
+                    self.private_key.as_deref(),
+                ),
+            )
+            .await,
+        )
+    }
+
+    fn get_config_hash(&self, receipt: &Self::Receipt) -> Option<B256> {
+        Some(get_journals_config_hash(receipt.public_values.as_slice()))
     }
 
     fn get_recursive_proof(&self, receipt: &Self::Receipt) -> Option<Self::Proof> {
         Some(receipt.clone())
     }
+
+    fn proof_backend(&self) -> Option<CanoeProofBackend> {
+        Some(CanoeProofBackend::Sp1CC)
+    }
+
+    fn resolved_chain_config(&self, l1_chain_id: u64) -> Result<String> {
+        crate::resolved_chain_config(l1_chain_id)
+    }
 }
 
 /// A canoe provider implementation with Sp1 contract call
@@ -108,6 +168,14 @@ pub struct CanoeSp1CCReducedProofProvider {
     pub eth_rpc_url: String,
     /// if true, execute and return a mock proof
     pub mock_mode: bool,
+    /// if set, evm sketches are reused across proofs at the same l1 block, see [EvmSketchCache]
+    pub sketch_cache: Option<EvmSketchCache>,
+    /// rpc endpoint of the sp1 prover network, falls back to the NETWORK_RPC_URL env variable,
+    /// then to the default endpoint of sp1-sdk
+    pub network_rpc_url: Option<String>,
+    /// private key requesting proofs from the sp1 prover network, falls back to the
+    /// NETWORK_PRIVATE_KEY env variable
+    pub private_key: Option<String>,
 }
 
 #[async_trait]
@@ -119,98 +187,386 @@ impl CanoeProvider for CanoeSp1CCReducedProofProvider {
         &self,
         canoe_inputs: Vec<CanoeInput>,
     ) -> Option<Result<Self::Receipt>> {
+        self.create_certs_validity_proof_with_timings(canoe_inputs)
+            .await
+            .map(|proof| proof.map(|(receipt, _)| receipt))
+    }
+
+    async fn create_certs_validity_proof_with_timings(
+        &self,
+        canoe_inputs: Vec<CanoeInput>,
+    ) -> Option<Result<(Self::Receipt, ProofTimings)>> {
         // if there is nothing to prove against return early
         if canoe_inputs.is_empty() {
             return None;
         }
 
-        match get_sp1_cc_proof(canoe_inputs, &self.eth_rpc_url, self.mock_mode).await {
-            Ok(proof) => {
+        match get_sp1_cc_proof(
+            canoe_inputs,
+            &self.eth_rpc_url,
+            self.mock_mode,
+            SP1ProofMode::Compressed,
+            self.sketch_cache.as_ref(),
+            &NetworkSettings::resolve(self.network_rpc_url.as_deref(), self.private_key.as_deref()),
+        )
+        .await
+        {
+            Ok((proof, timings)) => {
                 let journals_bytes = proof.public_values.to_vec();
                 let SP1Proof::Compressed(proof) = proof.proof else {
                     panic!("cannot get Sp1ReducedProof")
                 };
-                Some(Ok((*proof, journals_bytes)))
+                Some(Ok(((*proof, journals_bytes), timings)))
             }
             Err(e) => Some(Err(e)),
         }
     }
 
     fn get_config_hash(&self, receipt: &Self::Receipt) -> Option<B256> {
-        let journals: Vec<Journal> = bincode::deserialize(receipt.1.as_slice())
-            .expect("should be able to deserialize to journals");
-        assert!(!journals.is_empty());
-        let chain_config_hash = journals[0].chainConfigHash;
-        // all chainConfigHash must be identical
-        for journal in journals {
-            assert_eq!(chain_config_hash, journal.chainConfigHash);
-        }
-        Some(chain_config_hash)
+        Some(get_journals_config_hash(receipt.1.as_slice()))
     }
 
     fn get_recursive_proof(&self, receipt: &Self::Receipt) -> Option<Self::Proof> {
         Some(receipt.0.clone())
     }
+
+    fn proof_backend(&self) -> Option<CanoeProofBackend> {
+        Some(CanoeProofBackend::Sp1CC)
+    }
+
+    fn resolved_chain_config(&self, l1_chain_id: u64) -> Result<String> {
+        crate::resolved_chain_config(l1_chain_id)
+    }
+}
+
+/// Returns the chain config hash committed by the serialized journals, all journals must commit
+/// to the same hash
+pub(crate) fn get_journals_config_hash(journals_bytes: &[u8]) -> B256 {
+    let journals: Vec<Journal> =
+        deserialize_journals(journals_bytes).expect("should be able to deserialize to journals");
+    assert!(!journals.is_empty());
+    let chain_config_hash = journals[0].chainConfigHash;
+    // all chainConfigHash must be identical
+    for journal in journals {
+        assert_eq!(chain_config_hash, journal.chainConfigHash);
+    }
+    chain_config_hash
+}
+
+/// Returns the name and the json of the custom genesis shipped for an l1 chain which is not
+/// available in the sp1-cc library
+fn custom_genesis_json(l1_chain_id: u64) -> Option<(&'static str, &'static str)> {
+    match l1_chain_id {
+        17000 => Some(("holesky", HOLESKY_GENESIS)),
+        3151908 => Some(("kurtosis devnet", KURTOSIS_DEVNET_GENESIS)),
+        _ => None,
+    }
+}
+
+/// Returns the genesis of the l1 chain, if genesis is not available in the sp1-cc library, the
+/// code uses custom genesis config
+fn l1_genesis(l1_chain_id: u64) -> Genesis {
+    match Genesis::try_from(l1_chain_id) {
+        Ok(genesis) => genesis,
+        Err(_) => {
+            let Some((_, genesis_json)) = custom_genesis_json(l1_chain_id) else {
+                panic!("chain id {l1_chain_id} is not supported by canoe sp1 cc")
+            };
+            let chain_config = genesis_from_json(genesis_json).expect("genesis from json");
+            Genesis::Custom(chain_config.config)
+        }
+    }
+}
+
+/// Describes the genesis picked by [l1_genesis] for the l1 chain id. A custom genesis is
+/// identified by the keccak256 hash of its json, see [CanoeProvider::resolved_chain_config]
+pub fn resolved_chain_config(l1_chain_id: u64) -> Result<String> {
+    if Genesis::try_from(l1_chain_id).is_ok() {
+        return Ok(format!(
+            "sp1-cc built-in genesis for chain id {l1_chain_id}"
+        ));
+    }
+    match custom_genesis_json(l1_chain_id) {
+        Some((name, genesis_json)) => Ok(format!(
+            "custom {name} genesis for chain id {l1_chain_id}, keccak256 {}",
+            keccak256(genesis_json)
+        )),
+        None => anyhow::bail!("chain id {l1_chain_id} is not supported by canoe sp1 cc"),
+    }
+}
+
+/// Returns the chain config hash sp1-cc commits for the l1 chain at the latest block served by the
+/// rpc, by executing an empty call on a sketch built with [l1_genesis]. It is the hash a
+/// [canoe_sp1_cc_verifier::CanoeSp1CCVerifier] expects, integrators can pin it for an l1 chain
+/// unknown to sp1-cc, see [canoe_sp1_cc_verifier::CanoeSp1CCVerifier::with_custom_chain_config_hashes]
+pub async fn fetch_chain_config_hash(l1_chain_id: u64, eth_rpc_url: &str) -> Result<B256> {
+    let sketch = EvmSketch::builder()
+        .at_block(BlockNumberOrTag::Latest)
+        .with_genesis(l1_genesis(l1_chain_id))
+        .el_rpc_url(Url::from_str(eth_rpc_url)?)
+        .build()
+        .await?;
+
+    // a call to an address without code succeeds with an empty output, only the committed chain
+    // config hash matters
+    let call = IEigenDACertVerifierBase::checkDACertCall {
+        abiEncodedCert: Default::default(),
+    };
+    let contract_input = ContractInput::new_call(Address::ZERO, Address::default(), call);
+    sketch
+        .call_raw(&contract_input)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    let evm_state_sketch = sketch
+        .finalize()
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+    let executor =
+        ClientExecutor::eth(&evm_state_sketch).map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    anyhow::ensure!(
+        executor.chain_spec.chain().id() == l1_chain_id,
+        "rpc serves chain id {} instead of {l1_chain_id}",
+        executor.chain_spec.chain().id(),
+    );
+    let public_vals = executor
+        .execute(contract_input)
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    Ok(public_vals.chainConfigHash)
 }
 
-async fn get_sp1_cc_proof(
+pub(crate) async fn get_sp1_cc_proof(
     canoe_inputs: Vec<CanoeInput>,
     eth_rpc_url: &str,
     mock_mode: bool,
-) -> Result<sp1_sdk::SP1ProofWithPublicValues> {
-    // ensure chain id and l1 block number across all DAcerts are identical
-    let l1_chain_id = canoe_inputs[0].l1_chain_id;
+    proof_mode: SP1ProofMode,
+    sketch_cache: Option<&EvmSketchCache>,
+    network_settings: &NetworkSettings,
+) -> Result<(SP1ProofWithPublicValues, ProofTimings)> {
+    let mut timings = ProofTimings::default();
+    let mut timer = PhaseTimer::start();
+    let stdin = build_sp1_cc_stdin(
+        canoe_inputs,
+        eth_rpc_url,
+        sketch_cache,
+        &mut timings,
+        &mut timer,
+    )
+    .await?;
+
+    let client = network_client(network_settings);
+    let (pk, _vk) = client.setup(ELF);
+
+    let proof = if mock_mode {
+        // Execute the program using the `ProverClient.execute` method, without generating a proof.
+        let (public_values, _) = execute_sp1_cc(&client, &stdin);
+
+        // Create a mock aggregation proof with the public values.
+        SP1ProofWithPublicValues::create_mock_proof(
+            &pk,
+            public_values,
+            proof_mode,
+            SP1_CIRCUIT_VERSION,
+        )
+    } else {
+        let sp1_cc_proof_strategy = env_fulfillment_strategy(SP1_CC_PROOF_STRATEGY_ENV);
+
+        // Generate the proof for the given program and input.
+        let proof = client
+            .prove(&pk, &stdin)
+            .mode(proof_mode)
+            .strategy(sp1_cc_proof_strategy)
+            .skip_simulation(true)
+            .cycle_limit(1_000_000_000_000)
+            .gas_limit(1_000_000_000_000)
+            .timeout(Duration::from_secs(4 * 60 * 60))
+            .run()
+            .expect("sp1-cc should have produced a proof");
+
+        info!("generated sp1-cc {:?} proof in non-mock mode", proof_mode);
+
+        proof
+    };
 
-    let l1_head_block_number = canoe_inputs[0].l1_head_block_number;
+    timings.prove = timer.lap();
+    info!(
+        action = "sp1_cc_proof_generation",
+        status = "completed",
+        preflight = ?timings.preflight,
+        finalize = ?timings.finalize,
+        prove = ?timings.prove,
+        "sp1-cc commited: in elapsed_time {:?}",
+        timings.total(),
+    );
+    Ok((proof, timings))
+}
+
+/// Executes the sp1-cc guest on the canoe inputs without proving, and returns the cost reported
+/// by the execution, see [CanoeProvider::estimate]
+pub(crate) async fn estimate_sp1_cc_proof(
+    canoe_inputs: Vec<CanoeInput>,
+    eth_rpc_url: &str,
+    sketch_cache: Option<&EvmSketchCache>,
+    network_settings: &NetworkSettings,
+) -> Result<ProvingEstimate> {
+    let mut timings = ProofTimings::default();
+    let mut timer = PhaseTimer::start();
+    let stdin = build_sp1_cc_stdin(
+        canoe_inputs,
+        eth_rpc_url,
+        sketch_cache,
+        &mut timings,
+        &mut timer,
+    )
+    .await?;
+
+    let (_, report) = execute_sp1_cc(&network_client(network_settings), &stdin);
+    Ok(proving_estimate(&report))
+}
+
+/// Returns the proving cost reported by an execution of the guest
+fn proving_estimate(report: &ExecutionReport) -> ProvingEstimate {
+    ProvingEstimate {
+        cycles: report.total_instruction_count(),
+        prover_gas: report.gas,
+    }
+}
+
+/// The sp1 prover network a `NetworkProver` is built for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NetworkSettings {
+    /// if None, the default endpoint of sp1-sdk is used
+    rpc_url: Option<String>,
+    private_key: String,
+}
+
+impl NetworkSettings {
+    /// Resolves the settings configured on a provider, an unset field falls back to its env
+    /// variable for compatibility
+    pub(crate) fn resolve(network_rpc_url: Option<&str>, private_key: Option<&str>) -> Self {
+        let rpc_url = network_rpc_url
+            .map(str::to_string)
+            .or_else(|| env::var(NETWORK_RPC_URL_ENV).ok());
+        let private_key = private_key
+            .map(str::to_string)
+            .or_else(|| env::var(NETWORK_PRIVATE_KEY_ENV).ok())
+            .unwrap_or_else(|| {
+                warn!("NETWORK_PRIVATE_KEY is not set, using default network private key");
+                DEFAULT_NETWORK_PRIVATE_KEY.to_string()
+            });
+        Self {
+            rpc_url,
+            private_key,
+        }
+    }
+}
+
+/// Creates a `NetworkProver`
+fn network_client(network_settings: &NetworkSettings) -> NetworkProver {
+    let builder = ProverClient::builder()
+        .network()
+        .private_key(&network_settings.private_key);
+    match &network_settings.rpc_url {
+        Some(rpc_url) => builder.rpc_url(rpc_url).build(),
+        None => builder.build(),
+    }
+}
+
+/// Executes the guest without generating a proof
+fn execute_sp1_cc(client: &NetworkProver, stdin: &SP1Stdin) -> (SP1PublicValues, ExecutionReport) {
+    let (public_values, report) = client
+        .execute(ELF, stdin)
+        .run()
+        .expect("sp1-cc should have executed the ELF");
+    info!(
+        "executed program without proving with {} cycles and {} prover gas",
+        report.total_instruction_count(),
+        report
+            .gas
+            .expect("gas calculation is enabled by default in the executor")
+    );
+    (public_values, report)
+}
+
+/// Builds the guest input for the canoe inputs, i.e. one evm sketch per l1 block followed by the
+/// canoe inputs, after dropping the repeated inputs and rejecting the malformed ones
+async fn build_sp1_cc_stdin(
+    canoe_inputs: Vec<CanoeInput>,
+    eth_rpc_url: &str,
+    sketch_cache: Option<&EvmSketchCache>,
+    timings: &mut ProofTimings,
+    timer: &mut PhaseTimer,
+) -> Result<SP1Stdin> {
+    // a cert occurring several times is proven once, the verifier drops the same occurrences
+    let canoe_inputs = dedup_canoe_inputs(canoe_inputs);
+
+    // reject malformed inputs before spending any effort on proving
     for canoe_input in canoe_inputs.iter() {
-        assert!(canoe_input.l1_chain_id == l1_chain_id);
-        assert!(canoe_input.l1_head_block_number == l1_head_block_number);
+        canoe_input.validate()?;
     }
-    let start = Instant::now();
+
+    // canoe inputs can be anchored at distinct l1 blocks, one evm sketch is built per l1 block
+    let groups = group_inputs_by_anchor(&canoe_inputs)?;
     info!(
-        "begin to generate a sp1-cc proof for {} number of altda commitment at l1 block number {} with chainID {}",
+        "begin to generate a sp1-cc proof for {} number of altda commitment at {} distinct l1 blocks",
         canoe_inputs.len(),
-        l1_head_block_number,
-        l1_chain_id,
+        groups.len(),
     );
 
-    // Which block VerifyDACert eth-calls are executed against.
-    let block_number = BlockNumberOrTag::Number(l1_head_block_number);
-
     let rpc_url = Url::from_str(eth_rpc_url).unwrap();
 
-    let sketch = match Genesis::try_from(l1_chain_id) {
-        Ok(genesis) => {
-            EvmSketch::builder()
-                .at_block(block_number)
-                .with_genesis(genesis)
-                .el_rpc_url(rpc_url)
-                .build()
-                .await?
-        }
-        // if genesis is not available in the sp1-cc library, the code uses custom genesis config
-        Err(_) => {
-            let chain_config = match l1_chain_id {
-                17000 => genesis_from_json(HOLESKY_GENESIS).expect("genesis from json"),
-                3151908 => genesis_from_json(KURTOSIS_DEVNET_GENESIS).expect("genesis from json"),
-                _ => panic!("chain id {l1_chain_id} is not supported by canoe sp1 cc"),
-            };
+    let mut evm_state_sketches_bytes = Vec::with_capacity(groups.len());
+    for group in groups.iter().map(|group| &group.anchor) {
+        let build = build_evm_sketch(group, &canoe_inputs, &rpc_url, timings, timer);
+        let evm_state_sketch_bytes = match sketch_cache {
+            Some(sketch_cache) => {
+                sketch_cache
+                    .get_or_build(group, &canoe_inputs, build)
+                    .await?
+            }
+            None => build.await?,
+        };
+        evm_state_sketches_bytes.push(evm_state_sketch_bytes);
+    }
 
-            let genesis = Genesis::Custom(chain_config.config);
+    // Feed the sketches into the client, ordered identically to the groups derived by the client
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&evm_state_sketches_bytes);
+    stdin.write(&canoe_inputs);
+    Ok(stdin)
+}
 
-            EvmSketch::builder()
-                .at_block(block_number)
-                .with_genesis(genesis)
-                .el_rpc_url(rpc_url)
-                .build()
-                .await
-                .expect("evm sketch builder")
-        }
-    };
+/// Builds the evm sketch at the l1 block of the group, pre-populated with the VerifyDACert
+/// eth-calls of the canoe inputs of the group, and returns it serialized
+async fn build_evm_sketch(
+    group: &CanoeInputGroup,
+    canoe_inputs: &[CanoeInput],
+    rpc_url: &Url,
+    timings: &mut ProofTimings,
+    timer: &mut PhaseTimer,
+) -> Result<Vec<u8>> {
+    info!(
+        "building evm sketch at l1 block number {} with chainID {}",
+        group.l1_head_block_number, group.l1_chain_id,
+    );
+    // Which block VerifyDACert eth-calls of the group are executed against.
+    let sketch = EvmSketch::builder()
+        .at_block(BlockNumberOrTag::Number(group.l1_head_block_number))
+        .with_genesis(l1_genesis(group.l1_chain_id))
+        .el_rpc_url(rpc_url.clone())
+        .build()
+        .await?;
+
+    // the sketch builder resolves the block by number, the block served by the rpc must be the one
+    // the inputs are anchored at, otherwise the guest aborts after the whole proof input is built
+    group.check_anchor_hash(sketch.anchor.header().hash_slow())?;
 
     // pre populate the state
-    for canoe_input in canoe_inputs.iter() {
-        match CertVerifierCall::build(&canoe_input.altda_commitment) {
+    for canoe_input in canoe_inputs
+        .iter()
+        .filter(|canoe_input| group.contains(canoe_input))
+    {
+        match CertVerifierCall::build(&canoe_input.altda_commitment)? {
             CertVerifierCall::LegacyV2Interface(call) => {
                 let contract_input =
                     ContractInput::new_call(canoe_input.verifier_address, Address::default(), call);
@@ -220,9 +576,7 @@ async fn get_sp1_cc_proof(
                     .map_err(|e| anyhow::anyhow!(e.to_string()))?;
 
                 let is_valid = Bool::abi_decode(&returns_bytes).expect("deserialize returns_bytes");
-                if is_valid != canoe_input.claimed_validity {
-                    panic!("in the host executor part, executor arrives to a different answer than the claimed answer. Something inconsistent in the view of eigenda-proxy and zkVM");
-                }
+                check_claimed_validity(canoe_input, is_valid)?;
             }
             CertVerifierCall::ABIEncodeInterface(call) => {
                 let contract_input =
@@ -235,84 +589,209 @@ async fn get_sp1_cc_proof(
                 let returns = <StatusCode as SolType>::abi_decode(&returns_bytes)
                     .expect("deserialize returns_bytes");
                 let is_valid = returns == StatusCode::SUCCESS;
-                if is_valid != canoe_input.claimed_validity {
-                    panic!("in the host executor part, executor arrives to a different answer than the claimed answer. Something inconsistent in the view of eigenda-proxy and zkVM");
-                }
+                check_claimed_validity(canoe_input, is_valid)?;
             }
         };
     }
 
+    timings.preflight += timer.lap();
+
     let evm_state_sketch = sketch
         .finalize()
         .await
-        .map_err(|e| anyhow::anyhow!(e.to_string()))
         .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    timings.finalize += timer.lap();
 
-    // Feed the sketch into the client.
-    let input_bytes = bincode::serialize(&evm_state_sketch)
-        .expect("bincode should have serialized the EVM sketch");
-    let mut stdin = SP1Stdin::new();
-    stdin.write(&input_bytes);
-    stdin.write(&canoe_inputs);
+    Ok(bincode::serialize(&evm_state_sketch)
+        .expect("bincode should have serialized the EVM sketch"))
+}
 
-    // Create a `NetworkProver`.
-    let network_private_key = env::var("NETWORK_PRIVATE_KEY").unwrap_or_else(|_| {
-        warn!("NETWORK_PRIVATE_KEY is not set, using default network private key");
-        DEFAULT_NETWORK_PRIVATE_KEY.to_string()
-    });
-    let client = ProverClient::builder()
-        .network()
-        .private_key(&network_private_key)
-        .build();
-    let (pk, _vk) = client.setup(ELF);
+/// Returns an error if the validity executed by the host differs from the claimed validity of the
+/// canoe input. Unlike the guest which aborts, the host reports the offending commitment so that
+/// the caller can carry on with other work
+fn check_claimed_validity(canoe_input: &CanoeInput, is_valid: bool) -> Result<()> {
+    if is_valid != canoe_input.claimed_validity {
+        anyhow::bail!(
+            "in the host executor part, executor arrives to a different answer {} than the claimed answer {} for altda commitment {}. Something inconsistent in the view of eigenda-proxy and zkVM",
+            is_valid,
+            canoe_input.claimed_validity,
+            canoe_input.altda_commitment.to_digest(),
+        );
+    }
+    Ok(())
+}
 
-    let proof = if mock_mode {
-        // Execute the program using the `ProverClient.execute` method, without generating a proof.
-        let (public_values, report) = client
-            .execute(ELF, &stdin)
-            .run()
-            .expect("sp1-cc should have executed the ELF");
-        info!(
-            "executed program in mock mode with {} cycles and {} prover gas",
-            report.total_instruction_count(),
-            report
-                .gas
-                .expect("gas calculation is enabled by default in the executor")
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use canoe_sp1_cc_verifier::CanoeSp1CCVerifier;
+    use canoe_verifier::CertValidity;
+    use canoe_verifier_address_fetcher::{
+        CanoeVerifierAddressFetcher, CanoeVerifierAddressFetcherDeployedByEigenLabs,
+    };
+    use eigenda_cert::AltDACommitment;
+
+    #[test]
+    fn test_network_settings() {
+        // configured fields take precedence over the env variables
+        let network_settings = NetworkSettings::resolve(
+            Some("https://rpc.sp1-cluster.example"),
+            Some(DEFAULT_NETWORK_PRIVATE_KEY),
         );
+        assert_eq!(
+            network_settings,
+            NetworkSettings {
+                rpc_url: Some("https://rpc.sp1-cluster.example".to_string()),
+                private_key: DEFAULT_NETWORK_PRIVATE_KEY.to_string(),
+            }
+        );
+    }
 
-        // Create a mock aggregation proof with the public values.
-        SP1ProofWithPublicValues::create_mock_proof(
-            &pk,
-            public_values,
-            SP1ProofMode::Compressed,
-            SP1_CIRCUIT_VERSION,
+    #[test]
+    fn test_resolved_chain_config() {
+        let kurtosis = resolved_chain_config(3151908).unwrap();
+        assert!(kurtosis.starts_with("custom kurtosis devnet genesis"));
+        assert!(kurtosis.ends_with(&keccak256(KURTOSIS_DEVNET_GENESIS).to_string()));
+
+        // mainnet genesis is built into sp1-cc
+        assert!(resolved_chain_config(1)
+            .unwrap()
+            .starts_with("sp1-cc built-in genesis"));
+
+        assert!(resolved_chain_config(12345).is_err());
+    }
+
+    /// run with an rpc of each l1 chain built into sp1-cc
+    /// MAINNET_RPC_URL=.. SEPOLIA_RPC_URL=.. cargo test -p canoe-sp1-cc-host -- --ignored
+    #[tokio::test]
+    #[ignore = "needs mainnet and sepolia rpc urls"]
+    async fn test_built_in_chain_config_hashes() {
+        for (l1_chain_id, rpc_url_env) in [(1, "MAINNET_RPC_URL"), (11155111, "SEPOLIA_RPC_URL")] {
+            let eth_rpc_url = env::var(rpc_url_env).expect("rpc url should be set");
+            let chain_config_hash = fetch_chain_config_hash(l1_chain_id, &eth_rpc_url)
+                .await
+                .unwrap();
+
+            // the hash committed by sp1-cc is accepted by the verifier without pinning it
+            let cert_validity = CertValidity {
+                claimed_validity: true,
+                l1_head_block_hash: B256::ZERO,
+                l1_chain_id,
+                chain_config_hash: Some(chain_config_hash),
+                verifier_address: Address::ZERO,
+            };
+            assert_eq!(
+                CanoeSp1CCVerifier::default()
+                    .verified_chain_config_hash(&cert_validity)
+                    .unwrap(),
+                chain_config_hash
+            );
+        }
+    }
+
+    /// run with a sepolia rpc, the v2 cert of the canoe-on-sepolia example is proven in mock mode
+    /// SEPOLIA_RPC_URL=.. cargo test -p canoe-sp1-cc-host -- --ignored
+    #[tokio::test]
+    #[ignore = "needs a sepolia rpc url"]
+    async fn test_proof_timings() {
+        let eth_rpc_url = env::var("SEPOLIA_RPC_URL").expect("rpc url should be set");
+        let altda_commitment = AltDACommitment::try_from(
+            &include_bytes!("../../../../example/canoe-on-sepolia/data/v2_cert_rlp.bin")[..],
         )
-    } else {
-        let sp1_cc_proof_strategy = env_fulfillment_strategy(SP1_CC_PROOF_STRATEGY_ENV);
+        .unwrap();
+        let sketch = EvmSketch::builder()
+            .at_block(BlockNumberOrTag::Latest)
+            .el_rpc_url(Url::from_str(&eth_rpc_url).unwrap())
+            .build()
+            .await
+            .unwrap();
+        let canoe_input = CanoeInput {
+            verifier_address: CanoeVerifierAddressFetcherDeployedByEigenLabs {}
+                .fetch_address(11155111, &altda_commitment.versioned_cert)
+                .unwrap(),
+            altda_commitment,
+            claimed_validity: true,
+            l1_head_block_hash: sketch.anchor.header().hash_slow(),
+            l1_head_block_number: sketch.anchor.header().number,
+            l1_chain_id: 11155111,
+            expected_kzg_commitment: None,
+        };
 
-        // Generate the proof for the given program and input.
-        let proof = client
-            .prove(&pk, &stdin)
-            .compressed()
-            .strategy(sp1_cc_proof_strategy)
-            .skip_simulation(true)
-            .cycle_limit(1_000_000_000_000)
-            .gas_limit(1_000_000_000_000)
-            .timeout(Duration::from_secs(4 * 60 * 60))
-            .run()
-            .expect("sp1-cc should have produced a compressed proof");
+        let provider = CanoeSp1CCProvider {
+            eth_rpc_url,
+            mock_mode: true,
+            sketch_cache: None,
+            network_rpc_url: None,
+            private_key: None,
+        };
+        let (_, timings) = provider
+            .create_certs_validity_proof_with_timings(vec![canoe_input])
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!timings.preflight.is_zero());
+        assert!(!timings.prove.is_zero());
+    }
 
-        info!("generated sp1-cc proof in non-mock mode");
+    /// run with an rpc of the kurtosis devnet
+    /// KURTOSIS_RPC_URL=.. cargo test -p canoe-sp1-cc-host -- --ignored
+    #[tokio::test]
+    #[ignore = "needs a kurtosis devnet rpc url"]
+    async fn test_custom_chain_config_hash() {
+        let l1_chain_id = 3151908;
+        let eth_rpc_url = env::var("KURTOSIS_RPC_URL").expect("rpc url should be set");
+        let chain_config_hash = fetch_chain_config_hash(l1_chain_id, &eth_rpc_url)
+            .await
+            .unwrap();
+
+        let mut cert_validity = CertValidity {
+            claimed_validity: true,
+            l1_head_block_hash: B256::ZERO,
+            l1_chain_id,
+            chain_config_hash: Some(chain_config_hash),
+            verifier_address: Address::ZERO,
+        };
+        // kurtosis devnet is unknown to sp1-cc, the hash committed for its genesis must be pinned
+        assert!(CanoeSp1CCVerifier::default()
+            .verified_chain_config_hash(&cert_validity)
+            .is_err());
+        let verifier = CanoeSp1CCVerifier::with_custom_chain_config_hashes(vec![(
+            l1_chain_id,
+            chain_config_hash,
+        )]);
+        assert_eq!(
+            verifier.verified_chain_config_hash(&cert_validity).unwrap(),
+            chain_config_hash
+        );
 
-        proof
-    };
+        cert_validity.chain_config_hash = Some(B256::repeat_byte(4));
+        assert!(verifier.verified_chain_config_hash(&cert_validity).is_err());
+    }
 
-    let elapsed = start.elapsed();
-    info!(
-        action = "sp1_cc_proof_generation",
-        status = "completed",
-        "sp1-cc commited: in elapsed_time {:?}",
-        elapsed,
-    );
-    Ok(proof)
+    #[test]
+    fn test_proving_estimate() {
+        let report = ExecutionReport {
+            gas: Some(42),
+            ..Default::default()
+        };
+        assert_eq!(
+            proving_estimate(&report),
+            ProvingEstimate {
+                cycles: report.total_instruction_count(),
+                prover_gas: Some(42),
+            }
+        );
+    }
+
+    #[test]
+    fn test_check_claimed_validity() {
+        let canoe_input = crate::sketch_cache::tests::canoe_input(Address::repeat_byte(1));
+        assert!(check_claimed_validity(&canoe_input, true).is_ok());
+
+        // the executor disagrees with a deliberately wrong claim
+        let err = check_claimed_validity(&canoe_input, false).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&canoe_input.altda_commitment.to_digest().to_string()));
+    }
 }
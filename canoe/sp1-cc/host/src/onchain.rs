@@ -0,0 +1,289 @@
+//! Canoe provider and verifier for sp1-cc proofs wrapped into a Groth16 or Plonk proof, which
+//! can be settled directly on L1.
+use crate::{get_journals_config_hash, get_sp1_cc_proof, EvmSketchCache, NetworkSettings, ELF};
+use alloy_primitives::B256;
+use anyhow::Result;
+use async_trait::async_trait;
+use canoe_provider::{CanoeInput, CanoeProvider, ProofTimings};
+use canoe_sp1_cc_verifier::CanoeSp1CCVerifier;
+use canoe_verifier::{
+    CanoeProofBackend, CanoeVerifier, CertValidity, HokuleaCanoeVerificationError,
+};
+use eigenda_cert::AltDACommitment;
+use sp1_sdk::{Prover, ProverClient, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues};
+use tracing::info;
+
+/// The wrapped proof types of sp1 that are verifiable onchain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnchainProofMode {
+    Groth16,
+    Plonk,
+}
+
+impl OnchainProofMode {
+    /// The sp1 proof mode requested from the prover
+    pub fn sp1_proof_mode(&self) -> SP1ProofMode {
+        match self {
+            OnchainProofMode::Groth16 => SP1ProofMode::Groth16,
+            OnchainProofMode::Plonk => SP1ProofMode::Plonk,
+        }
+    }
+
+    /// Returns true if the proof is wrapped in this mode
+    pub fn matches(&self, proof: &SP1Proof) -> bool {
+        matches!(
+            (self, proof),
+            (OnchainProofMode::Groth16, SP1Proof::Groth16(_))
+                | (OnchainProofMode::Plonk, SP1Proof::Plonk(_))
+        )
+    }
+}
+
+/// A canoe provider implementation with Sp1 contract call
+/// CanoeSp1CCOnchainProvider wraps the sp1-cc proof into a Groth16 or Plonk proof, selected by
+/// [OnchainProofMode]. Unlike [crate::CanoeSp1CCProvider], the proof is meant for verification by
+/// the sp1 verifier contract on L1, it cannot be verified within zkVM.
+#[derive(Debug, Clone)]
+pub struct CanoeSp1CCOnchainProvider {
+    /// rpc to l1 geth node
+    pub eth_rpc_url: String,
+    /// if true, execute and return a mock proof
+    pub mock_mode: bool,
+    /// the wrapped proof type to produce
+    pub proof_mode: OnchainProofMode,
+    /// if set, evm sketches are reused across proofs at the same l1 block, see [EvmSketchCache]
+    pub sketch_cache: Option<EvmSketchCache>,
+    /// rpc endpoint of the sp1 prover network, falls back to the NETWORK_RPC_URL env variable,
+    /// then to the default endpoint of sp1-sdk
+    pub network_rpc_url: Option<String>,
+    /// private key requesting proofs from the sp1 prover network, falls back to the
+    /// NETWORK_PRIVATE_KEY env variable
+    pub private_key: Option<String>,
+}
+
+#[async_trait]
+impl CanoeProvider for CanoeSp1CCOnchainProvider {
+    type Proof = SP1ProofWithPublicValues;
+    type Receipt = SP1ProofWithPublicValues;
+
+    async fn create_certs_validity_proof(
+        &self,
+        canoe_inputs: Vec<CanoeInput>,
+    ) -> Option<Result<Self::Receipt>> {
+        self.create_certs_validity_proof_with_timings(canoe_inputs)
+            .await
+            .map(|proof| proof.map(|(receipt, _)| receipt))
+    }
+
+    async fn create_certs_validity_proof_with_timings(
+        &self,
+        canoe_inputs: Vec<CanoeInput>,
+    ) -> Option<Result<(Self::Receipt, ProofTimings)>> {
+        // if there is nothing to prove against return early
+        if canoe_inputs.is_empty() {
+            return None;
+        }
+
+        Some(
+            get_sp1_cc_proof(
+                canoe_inputs,
+                &self.eth_rpc_url,
+                self.mock_mode,
+                self.proof_mode.sp1_proof_mode(),
+                self.sketch_cache.as_ref(),
+                &NetworkSettings::resolve(
+                    self.network_rpc_url.as_deref(),
+                    self.private_key.as_deref(),
+                ),
+            )
+            .await,
+        )
+    }
+
+    fn get_config_hash(&self, receipt: &Self::Receipt) -> Option<B256> {
+        Some(get_journals_config_hash(receipt.public_values.as_slice()))
+    }
+
+    fn get_recursive_proof(&self, receipt: &Self::Receipt) -> Option<Self::Proof> {
+        Some(receipt.clone())
+    }
+
+    fn proof_backend(&self) -> Option<CanoeProofBackend> {
+        Some(CanoeProofBackend::Sp1CC)
+    }
+
+    fn resolved_chain_config(&self, l1_chain_id: u64) -> Result<String> {
+        crate::resolved_chain_config(l1_chain_id)
+    }
+}
+
+/// Verifies a proof produced by [CanoeSp1CCOnchainProvider] in native mode with sp1-sdk.
+/// [CanoeSp1CCVerifier] verifies a serde_json serialized proof outside of zkVM only with its sdk
+/// feature, and never a wrapped proof. The canoe proof bytes are the serde_json serialized
+/// [SP1ProofWithPublicValues] tagged with [CanoeProofBackend::Sp1CC], the same encoding as every
+/// other sp1-cc proof, such that [canoe_provider::prove_and_verify] applies.
+#[derive(Debug, Clone)]
+pub struct CanoeSp1CCOnchainVerifier {
+    /// if true, the proof is expected to be a mock proof
+    pub mock_mode: bool,
+    /// the wrapped proof type expected
+    pub proof_mode: OnchainProofMode,
+}
+
+impl CanoeVerifier for CanoeSp1CCOnchainVerifier {
+    fn validate_cert_receipt(
+        &self,
+        cert_validity_pair: Vec<(AltDACommitment, CertValidity)>,
+        canoe_proof_bytes: Option<Vec<u8>>,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        info!("using CanoeSp1CCOnchainVerifier with {:?}", self.proof_mode);
+
+        // checks the chain config hash of every cert validity
+        let verifier = CanoeSp1CCVerifier::default();
+        for (_, cert_validity) in cert_validity_pair.iter() {
+            verifier.verified_chain_config_hash(cert_validity)?;
+        }
+
+        let journals_bytes = self.to_journals_bytes(cert_validity_pair);
+        self.validate_against_journal_bytes(&journals_bytes, canoe_proof_bytes)
+    }
+
+    fn validate_against_journal_bytes(
+        &self,
+        journals_bytes: &[u8],
+        canoe_proof_bytes: Option<Vec<u8>>,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        let canoe_proof_bytes =
+            canoe_proof_bytes.ok_or(HokuleaCanoeVerificationError::MissingProof)?;
+        let proof_bytes = CanoeProofBackend::Sp1CC.untag(&canoe_proof_bytes)?;
+        let proof: SP1ProofWithPublicValues = serde_json::from_slice(proof_bytes).map_err(|e| {
+            HokuleaCanoeVerificationError::UnableToDeserializeReceipt(e.to_string())
+        })?;
+
+        if !self.proof_mode.matches(&proof.proof) {
+            return Err(HokuleaCanoeVerificationError::InvalidProofAndJournal(
+                format!("proof is not a {:?} proof", self.proof_mode),
+            ));
+        }
+
+        if proof.public_values.as_slice() != journals_bytes {
+            return Err(HokuleaCanoeVerificationError::InconsistentPublicJournal);
+        }
+
+        let verified = if self.mock_mode {
+            let client = ProverClient::builder().mock().build();
+            let (_pk, vk) = client.setup(ELF);
+            client.verify(&proof, &vk)
+        } else {
+            let client = ProverClient::builder().cpu().build();
+            let (_pk, vk) = client.setup(ELF);
+            client.verify(&proof, &vk)
+        };
+        verified.map_err(|e| HokuleaCanoeVerificationError::InvalidProofAndJournal(e.to_string()))
+    }
+
+    fn supported_chain_ids(&self) -> Option<Vec<u64>> {
+        CanoeSp1CCVerifier::default().supported_chain_ids()
+    }
+
+    fn to_journals_bytes(
+        &self,
+        cert_validity_pairs: Vec<(AltDACommitment, CertValidity)>,
+    ) -> Vec<u8> {
+        CanoeSp1CCVerifier::default().to_journals_bytes(cert_validity_pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eigenda_cert::test_utils::v3_altda_commitment;
+    use sp1_sdk::{SP1PublicValues, SP1_CIRCUIT_VERSION};
+
+    #[test]
+    fn test_onchain_proof_mode_selects_mock_proof() {
+        let client = ProverClient::builder().mock().build();
+        let (pk, _vk) = client.setup(ELF);
+
+        for proof_mode in [OnchainProofMode::Groth16, OnchainProofMode::Plonk] {
+            let proof = SP1ProofWithPublicValues::create_mock_proof(
+                &pk,
+                SP1PublicValues::new(),
+                proof_mode.sp1_proof_mode(),
+                SP1_CIRCUIT_VERSION,
+            );
+            assert!(proof_mode.matches(&proof.proof));
+        }
+
+        let compressed = SP1ProofWithPublicValues::create_mock_proof(
+            &pk,
+            SP1PublicValues::new(),
+            SP1ProofMode::Compressed,
+            SP1_CIRCUIT_VERSION,
+        );
+        assert!(!OnchainProofMode::Groth16.matches(&compressed.proof));
+        assert!(!OnchainProofMode::Plonk.matches(&compressed.proof));
+    }
+
+    #[test]
+    fn test_validate_against_journal_bytes() {
+        let client = ProverClient::builder().mock().build();
+        let (pk, _vk) = client.setup(ELF);
+        let journals_bytes = vec![1u8, 2, 3, 4];
+        let proof = SP1ProofWithPublicValues::create_mock_proof(
+            &pk,
+            SP1PublicValues::from(&journals_bytes),
+            SP1ProofMode::Groth16,
+            SP1_CIRCUIT_VERSION,
+        );
+        let proof_bytes = CanoeProofBackend::Sp1CC.tag(serde_json::to_vec(&proof).unwrap());
+
+        let verifier = CanoeSp1CCOnchainVerifier {
+            mock_mode: true,
+            proof_mode: OnchainProofMode::Groth16,
+        };
+        assert!(verifier
+            .validate_against_journal_bytes(&journals_bytes, Some(proof_bytes.clone()))
+            .is_ok());
+        assert!(matches!(
+            verifier.validate_against_journal_bytes(&[1, 2, 3, 5], Some(proof_bytes.clone())),
+            Err(HokuleaCanoeVerificationError::InconsistentPublicJournal)
+        ));
+        assert!(matches!(
+            verifier.validate_against_journal_bytes(&journals_bytes, None),
+            Err(HokuleaCanoeVerificationError::MissingProof)
+        ));
+
+        // a proof wrapped in another mode is rejected
+        let verifier = CanoeSp1CCOnchainVerifier {
+            mock_mode: true,
+            proof_mode: OnchainProofMode::Plonk,
+        };
+        assert!(matches!(
+            verifier.validate_against_journal_bytes(&journals_bytes, Some(proof_bytes)),
+            Err(HokuleaCanoeVerificationError::InvalidProofAndJournal(_))
+        ));
+    }
+
+    #[test]
+    fn test_onchain_verifier_checks_chain_config_hash_first() {
+        let verifier = CanoeSp1CCOnchainVerifier {
+            mock_mode: true,
+            proof_mode: OnchainProofMode::Groth16,
+        };
+        let altda_commitment = v3_altda_commitment();
+        let cert_validity = CertValidity {
+            claimed_validity: true,
+            l1_head_block_hash: B256::repeat_byte(1),
+            l1_chain_id: 1,
+            chain_config_hash: None,
+            verifier_address: Default::default(),
+        };
+
+        // the chain config hash is checked before the proof
+        assert!(matches!(
+            verifier.validate_cert_receipt(vec![(altda_commitment, cert_validity)], None),
+            Err(HokuleaCanoeVerificationError::MissingChainConfigHash)
+        ));
+    }
+}
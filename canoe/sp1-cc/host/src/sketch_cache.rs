@@ -0,0 +1,186 @@
+//! Cache of the evm sketches built by a sp1-cc canoe provider, for workflows proving many batches
+//! of certs against the same l1 block.
+use alloy_primitives::{Address, B256};
+use anyhow::Result;
+use canoe_provider::{CanoeInput, CanoeInputGroup};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use tracing::info;
+
+/// Identifies a VerifyDACert eth-call pre-populated into an evm sketch, by the digest of the altda
+/// commitment, the verifier address and the claimed validity checked by the host
+type SketchCall = (B256, Address, bool);
+
+#[derive(Debug, Clone)]
+struct CachedSketch {
+    l1_head_block_hash: B256,
+    calls: HashSet<SketchCall>,
+    evm_state_sketch_bytes: Vec<u8>,
+}
+
+/// EvmSketchCache keeps the finalized evm sketches built by a provider, keyed by the
+/// (l1_chain_id, l1_head_block_number) each sketch is built at. A cached sketch is reused by a
+/// later proof at the same l1 block only if it was pre-populated with every VerifyDACert call of
+/// that proof, otherwise a sketch is built again and replaces the cached one. Clones share the
+/// same cache.
+#[derive(Debug, Clone, Default)]
+pub struct EvmSketchCache {
+    sketches: Arc<Mutex<HashMap<(u64, u64), CachedSketch>>>,
+    num_builds: Arc<AtomicUsize>,
+}
+
+impl EvmSketchCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of sketches built through the cache, i.e. the number of cache misses
+    pub fn num_builds(&self) -> usize {
+        self.num_builds.load(Ordering::SeqCst)
+    }
+
+    /// Returns the serialized sketch cached for the group if it covers all canoe inputs of the
+    /// group, otherwise awaits build and caches its result
+    pub(crate) async fn get_or_build(
+        &self,
+        group: &CanoeInputGroup,
+        canoe_inputs: &[CanoeInput],
+        build: impl Future<Output = Result<Vec<u8>>>,
+    ) -> Result<Vec<u8>> {
+        let key = (group.l1_chain_id, group.l1_head_block_number);
+        let calls: HashSet<SketchCall> = canoe_inputs
+            .iter()
+            .filter(|canoe_input| group.contains(canoe_input))
+            .map(|canoe_input| {
+                (
+                    canoe_input.altda_commitment.to_digest(),
+                    canoe_input.verifier_address,
+                    canoe_input.claimed_validity,
+                )
+            })
+            .collect();
+
+        if let Some(cached) = self.sketches.lock().unwrap().get(&key) {
+            if cached.l1_head_block_hash == group.l1_head_block_hash
+                && cached.calls.is_superset(&calls)
+            {
+                info!(
+                    "reusing cached evm sketch at l1 block number {} with chainID {}",
+                    group.l1_head_block_number, group.l1_chain_id,
+                );
+                return Ok(cached.evm_state_sketch_bytes.clone());
+            }
+        }
+
+        let evm_state_sketch_bytes = build.await?;
+        self.num_builds.fetch_add(1, Ordering::SeqCst);
+        self.sketches.lock().unwrap().insert(
+            key,
+            CachedSketch {
+                l1_head_block_hash: group.l1_head_block_hash,
+                calls,
+                evm_state_sketch_bytes: evm_state_sketch_bytes.clone(),
+            },
+        );
+        Ok(evm_state_sketch_bytes)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use eigenda_cert::test_utils::v3_altda_commitment;
+
+    pub(crate) fn canoe_input(verifier_address: Address) -> CanoeInput {
+        CanoeInput {
+            altda_commitment: v3_altda_commitment(),
+            claimed_validity: true,
+            l1_head_block_hash: B256::repeat_byte(1),
+            l1_head_block_number: 1,
+            l1_chain_id: 1,
+            verifier_address,
+            expected_kzg_commitment: None,
+        }
+    }
+
+    fn group_of(canoe_input: &CanoeInput) -> CanoeInputGroup {
+        CanoeInputGroup {
+            l1_chain_id: canoe_input.l1_chain_id,
+            l1_head_block_number: canoe_input.l1_head_block_number,
+            l1_head_block_hash: canoe_input.l1_head_block_hash,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evm_sketch_cache() {
+        let cache = EvmSketchCache::new();
+        // counts the builds which are awaited, a reused sketch drops its build unpolled
+        let num_built = AtomicUsize::new(0);
+        let counter = &num_built;
+        let build = |sketch: u8| async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, anyhow::Error>(vec![sketch])
+        };
+
+        let first = canoe_input(Address::repeat_byte(1));
+        let group = group_of(&first);
+        let inputs = vec![first.clone()];
+        assert_eq!(
+            cache.get_or_build(&group, &inputs, build(1)).await.unwrap(),
+            vec![1]
+        );
+        // the second proof at the same block reuses the sketch without building it
+        assert_eq!(
+            cache.get_or_build(&group, &inputs, build(2)).await.unwrap(),
+            vec![1]
+        );
+        assert_eq!(num_built.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.num_builds(), 1);
+        // clones share the cache
+        assert_eq!(
+            cache
+                .clone()
+                .get_or_build(&group, &inputs, build(3))
+                .await
+                .unwrap(),
+            vec![1]
+        );
+        assert_eq!(cache.num_builds(), 1);
+
+        // a cert whose call was not pre-populated into the cached sketch requires a new sketch
+        let inputs = vec![first.clone(), canoe_input(Address::repeat_byte(2))];
+        assert_eq!(
+            cache.get_or_build(&group, &inputs, build(4)).await.unwrap(),
+            vec![4]
+        );
+        // a subset of the pre-populated calls reuses the new sketch
+        assert_eq!(
+            cache
+                .get_or_build(&group, &inputs[1..], build(5))
+                .await
+                .unwrap(),
+            vec![4]
+        );
+        assert_eq!(cache.num_builds(), 2);
+
+        // the same block number with another block hash, i.e. after a reorg, is not reused
+        let mut reorged = first;
+        reorged.l1_head_block_hash = B256::repeat_byte(2);
+        let reorged_group = group_of(&reorged);
+        assert_eq!(
+            cache
+                .get_or_build(&reorged_group, &[reorged], build(6))
+                .await
+                .unwrap(),
+            vec![6]
+        );
+        assert_eq!(num_built.load(Ordering::SeqCst), 3);
+        assert_eq!(cache.num_builds(), 3);
+    }
+}
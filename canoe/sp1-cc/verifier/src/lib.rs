@@ -1,15 +1,24 @@
 //! implement [CanoeVerifier] with sp1-cc
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "sdk")]
+extern crate std;
+
+#[cfg(feature = "sdk")]
+mod native;
 
 use alloc::vec::Vec;
-use canoe_bindings::Journal;
-use canoe_verifier::{CanoeVerifier, CertValidity, HokuleaCanoeVerificationError};
+use alloy_primitives::B256;
+use canoe_bindings::{serialize_journals, Journal};
+use canoe_verifier::{
+    dedup_cert_validity_pairs, CanoeProofBackend, CanoeVerifier, CertValidity,
+    HokuleaCanoeVerificationError,
+};
 use eigenda_cert::AltDACommitment;
 use revm_primitives::hardfork::SpecId;
 use sp1_cc_client_executor::verifiy_chain_config_eth;
 
-use tracing::{info, warn};
+use tracing::info;
 
 /// Any change to sp1-cc client including new sp1 toolchain produces a new ELF to be executed and proved by zkVM
 /// To generate the new ELF (a newer version than 5.2.1 toolchain tag is also fine)
@@ -38,12 +47,63 @@ pub const V_KEY: [u32; 8] = [
 /// must be kept identical to sp1-cc, but it is best to update sp1-cc version.
 pub const L1_ACTIVE_FORK: SpecId = SpecId::PRAGUE;
 
-#[derive(Clone)]
-pub struct CanoeSp1CCVerifier {}
+/// Checks the chain config hash is the one sp1-cc derives for the l1 chain id at [L1_ACTIVE_FORK].
+/// It is the same check the verifier applies to [CertValidity::chain_config_hash], exposed such that
+/// integrators can cross-check a hash before submitting a proof
+pub fn verify_chain_config_hash(
+    chain_config_hash: B256,
+    l1_chain_id: u64,
+) -> Result<(), HokuleaCanoeVerificationError> {
+    verifiy_chain_config_eth(chain_config_hash, l1_chain_id, L1_ACTIVE_FORK)
+        .map_err(|_| HokuleaCanoeVerificationError::InvalidChainConfigHash(l1_chain_id))
+}
+
+/// l1 chain ids whose chain config is built into sp1-cc, such that [verify_chain_config_hash]
+/// can check their chain config hash without a pinned one
+pub const BUILT_IN_L1_CHAIN_IDS: &[u64] = &[1, 11155111];
+
+#[derive(Clone, Default)]
+pub struct CanoeSp1CCVerifier {
+    /// (l1 chain id, chain config hash) pinned by the integrator for l1 chains unknown to sp1-cc, like
+    /// a devnet with a custom genesis. A pinned chain id is checked against its pinned hash only, any
+    /// other chain id is checked by [verify_chain_config_hash]. Hashes are pinned rather than derived
+    /// from a chain spec since sp1-cc does not expose how it hashes a chain config, the sp1-cc host
+    /// reports the hash of a chain through canoe_sp1_cc_host::fetch_chain_config_hash
+    pub custom_chain_config_hashes: Vec<(u64, B256)>,
+    /// if true, the proof verified in native mode is expected to be a mock proof
+    #[cfg(feature = "sdk")]
+    pub mock_mode: bool,
+}
+
+impl CanoeSp1CCVerifier {
+    /// Creates a verifier which accepts the pinned chain config hashes for their l1 chain ids
+    pub fn with_custom_chain_config_hashes(custom_chain_config_hashes: Vec<(u64, B256)>) -> Self {
+        Self {
+            custom_chain_config_hashes,
+            ..Default::default()
+        }
+    }
 
+    /// Returns the chain config hash supplied by the host once it is checked to be correct with respect
+    /// to the l1 chain id and [L1_ACTIVE_FORK], or to the pinned hash of the l1 chain id. The hash is
+    /// committed by the proof, so it must never be used without this check
+    pub fn verified_chain_config_hash(
+        &self,
+        cert_validity: &CertValidity,
+    ) -> Result<B256, HokuleaCanoeVerificationError> {
+        let chain_config_hash = cert_validity
+            .chain_config_hash
+            .ok_or(HokuleaCanoeVerificationError::MissingChainConfigHash)?;
+        self.check_chain_config_hash(cert_validity.l1_chain_id, chain_config_hash)?;
+        Ok(chain_config_hash)
+    }
+}
+
+/// The default [CanoeVerifier::validate_single_cert] applies to sp1-cc as well, the proof must then
+/// commit the journal of the single cert
 impl CanoeVerifier for CanoeSp1CCVerifier {
-    // some variable is unused, because when sp1-cc verifier is not configured in zkVM mode, all tests
-    // are skipped because sp1 cannot take sp1-sdk as dependency
+    // some variable is unused, because when sp1-cc verifier is neither configured in zkVM mode nor
+    // with the sdk feature, the proof verification is skipped
     #[allow(unused_variables)]
     fn validate_cert_receipt(
         &self,
@@ -54,8 +114,31 @@ impl CanoeVerifier for CanoeSp1CCVerifier {
 
         assert!(!cert_validity_pair.is_empty());
 
-        // while transforming to journal bytes, it verifies if chain config hash is correctly set
+        // reject a missing or incorrect chain config hash before any journal is built
+        for (_, cert_validity) in cert_validity_pair.iter() {
+            self.verified_chain_config_hash(cert_validity)?;
+        }
+
         let journals_bytes = self.to_journals_bytes(cert_validity_pair);
+        self.validate_against_journal_bytes(&journals_bytes, canoe_proof_bytes)
+    }
+
+    // see validate_cert_receipt for why some variables are unused
+    #[allow(unused_variables)]
+    fn validate_against_journal_bytes(
+        &self,
+        journals_bytes: &[u8],
+        canoe_proof_bytes: Option<Vec<u8>>,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        // within zkVM the proof itself is supplied via zkVM stdin, but a proof produced by another
+        // backend points to a misconfigured host
+        let canoe_proof_bytes = canoe_proof_bytes
+            .map(|proof_bytes| {
+                CanoeProofBackend::Sp1CC
+                    .untag(&proof_bytes)
+                    .map(|p| p.to_vec())
+            })
+            .transpose()?;
 
         cfg_if::cfg_if! {
             if #[cfg(target_os = "zkvm")] {
@@ -65,35 +148,71 @@ impl CanoeVerifier for CanoeSp1CCVerifier {
                 // if not in dev mode, the receipt should be empty
                 if canoe_proof_bytes.is_some() {
                     // Sp1 doc https://github.com/succinctlabs/sp1/blob/a1d873f10c32f5065de120d555cfb53de4003da3/examples/aggregation/script/src/main.rs#L75
-                    warn!("sp1-cc verification within zkvm requires proof being provided via zkVM stdin");
+                    tracing::warn!("sp1-cc verification within zkvm requires proof being provided via zkVM stdin");
                 }
                 // used within zkVM
                 let public_values_digest = Sha256::digest(journals_bytes);
                 // the function will panic if the proof is incorrect
                 // https://github.com/succinctlabs/sp1/blob/011d2c64808301878e6f0375c3596b3e22e53949/crates/zkvm/lib/src/verify.rs#L3
                 verify_sp1_proof(&V_KEY, &public_values_digest.into());
+            } else if #[cfg(feature = "sdk")] {
+                let canoe_proof_bytes =
+                    canoe_proof_bytes.ok_or(HokuleaCanoeVerificationError::MissingProof)?;
+                native::verify_sp1_cc_proof(&canoe_proof_bytes, journals_bytes, self.mock_mode)?;
             } else {
-                warn!("Skipping sp1CC proof verification in native mode outside of zkVM, because sp1 cannot take sp1-sdk as dependency which is needed for verification in the native mode");
+                tracing::warn!("Skipping sp1CC proof verification in native mode outside of zkVM, because the sdk feature which is needed for verification in the native mode is not enabled");
             }
         }
         Ok(())
     }
 
+    /// Checks the hash against the pinned hash of the l1 chain id if any, otherwise against the
+    /// hash sp1-cc derives for the l1 chain id at [L1_ACTIVE_FORK]
+    fn check_chain_config_hash(
+        &self,
+        l1_chain_id: u64,
+        chain_config_hash: B256,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        match self
+            .custom_chain_config_hashes
+            .iter()
+            .find(|(pinned_l1_chain_id, _)| *pinned_l1_chain_id == l1_chain_id)
+        {
+            Some((_, pinned_chain_config_hash)) => {
+                if *pinned_chain_config_hash != chain_config_hash {
+                    return Err(HokuleaCanoeVerificationError::InvalidChainConfigHash(
+                        l1_chain_id,
+                    ));
+                }
+                Ok(())
+            }
+            None => verify_chain_config_hash(chain_config_hash, l1_chain_id),
+        }
+    }
+
+    /// The built-in l1 chain ids, along with the ones of the pinned chain config hashes
+    fn supported_chain_ids(&self) -> Option<Vec<u64>> {
+        let mut chain_ids = BUILT_IN_L1_CHAIN_IDS.to_vec();
+        for (l1_chain_id, _) in &self.custom_chain_config_hashes {
+            if !chain_ids.contains(l1_chain_id) {
+                chain_ids.push(*l1_chain_id);
+            }
+        }
+        Some(chain_ids)
+    }
+
     fn to_journals_bytes(
         &self,
         cert_validity_pairs: Vec<(AltDACommitment, CertValidity)>,
     ) -> Vec<u8> {
         let mut journals: Vec<Journal> = Vec::new();
-        for (altda_commitment, cert_validity) in &cert_validity_pairs {
+        for (altda_commitment, cert_validity) in &dedup_cert_validity_pairs(cert_validity_pairs) {
             let rlp_bytes = altda_commitment.to_rlp_bytes();
 
-            let chain_config_hash = cert_validity
-                .chain_config_hash
-                .expect("sp1cc verifier expects l1 chain config hash");
-
             // check chain_config_hash supplied by the host is indeed correct with respect to l1 chain id
             // and active fork
-            verifiy_chain_config_eth(chain_config_hash, cert_validity.l1_chain_id, L1_ACTIVE_FORK)
+            let chain_config_hash = self
+                .verified_chain_config_hash(cert_validity)
                 .expect("sp1cc canoe verifies chain config should have succeeded");
 
             let journal = Journal {
@@ -108,6 +227,211 @@ impl CanoeVerifier for CanoeSp1CCVerifier {
             journals.push(journal);
         }
 
-        bincode::serialize(&journals).expect("should be able to serialize")
+        serialize_journals(&journals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use eigenda_cert::test_utils::v3_altda_commitment;
+
+    fn cert_validity_pair(chain_config_hash: Option<B256>) -> (AltDACommitment, CertValidity) {
+        let cert_validity = CertValidity {
+            claimed_validity: true,
+            l1_head_block_hash: B256::repeat_byte(1),
+            l1_chain_id: 1,
+            chain_config_hash,
+            verifier_address: Default::default(),
+        };
+        (v3_altda_commitment(), cert_validity)
+    }
+
+    #[test]
+    fn test_validate_cert_receipt_rejects_missing_chain_config_hash() {
+        let verifier = CanoeSp1CCVerifier::default();
+        let result = verifier.validate_cert_receipt(vec![cert_validity_pair(None)], None);
+        assert!(matches!(
+            result,
+            Err(HokuleaCanoeVerificationError::MissingChainConfigHash)
+        ));
+    }
+
+    #[test]
+    fn test_validate_against_journal_bytes_rejects_steel_proof() {
+        let verifier = CanoeSp1CCVerifier::default();
+        let steel_proof = CanoeProofBackend::Steel.tag(vec![1u8; 32]);
+        let result = verifier.validate_against_journal_bytes(&[1, 2, 3], Some(steel_proof));
+        assert!(matches!(
+            result,
+            Err(HokuleaCanoeVerificationError::UnsupportedProofBackend(0))
+        ));
+    }
+
+    #[test]
+    fn test_validate_cert_receipt_rejects_incorrect_chain_config_hash() {
+        let verifier = CanoeSp1CCVerifier::default();
+        let result = verifier
+            .validate_cert_receipt(vec![cert_validity_pair(Some(B256::repeat_byte(2)))], None);
+        assert!(matches!(
+            result,
+            Err(HokuleaCanoeVerificationError::InvalidChainConfigHash(1))
+        ));
+    }
+
+    #[test]
+    fn test_verify_chain_config_hash_rejects_arbitrary_hash() {
+        // mainnet and sepolia
+        for l1_chain_id in [1, 11155111] {
+            assert!(matches!(
+                verify_chain_config_hash(B256::ZERO, l1_chain_id),
+                Err(HokuleaCanoeVerificationError::InvalidChainConfigHash(id)) if id == l1_chain_id
+            ));
+        }
+    }
+
+    #[test]
+    fn test_custom_chain_config_hashes() {
+        // kurtosis devnet is unknown to sp1-cc
+        let kurtosis_chain_id = 3151908;
+        let pinned_chain_config_hash = B256::repeat_byte(3);
+        let verifier = CanoeSp1CCVerifier::with_custom_chain_config_hashes(vec![(
+            kurtosis_chain_id,
+            pinned_chain_config_hash,
+        )]);
+        assert_eq!(
+            verifier.supported_chain_ids(),
+            Some(vec![1, 11155111, kurtosis_chain_id])
+        );
+        assert_eq!(
+            CanoeSp1CCVerifier::default().supported_chain_ids(),
+            Some(BUILT_IN_L1_CHAIN_IDS.to_vec())
+        );
+
+        let (_, mut cert_validity) = cert_validity_pair(Some(pinned_chain_config_hash));
+        cert_validity.l1_chain_id = kurtosis_chain_id;
+        assert_eq!(
+            verifier.verified_chain_config_hash(&cert_validity).unwrap(),
+            pinned_chain_config_hash
+        );
+
+        cert_validity.chain_config_hash = Some(B256::repeat_byte(4));
+        assert!(matches!(
+            verifier.verified_chain_config_hash(&cert_validity),
+            Err(HokuleaCanoeVerificationError::InvalidChainConfigHash(id)) if id == kurtosis_chain_id
+        ));
+
+        // other chain ids are still checked by sp1-cc
+        let (_, cert_validity) = cert_validity_pair(Some(pinned_chain_config_hash));
+        assert!(matches!(
+            verifier.verified_chain_config_hash(&cert_validity),
+            Err(HokuleaCanoeVerificationError::InvalidChainConfigHash(1))
+        ));
+    }
+
+    #[test]
+    fn test_to_journals_bytes_matches_client_journals() {
+        let chain_config_hash = B256::repeat_byte(3);
+        let verifier =
+            CanoeSp1CCVerifier::with_custom_chain_config_hashes(vec![(1, chain_config_hash)]);
+        let (altda_commitment, cert_validity) = cert_validity_pair(Some(chain_config_hash));
+
+        // journal as built by the sp1-cc client from the canoe input and the execution result
+        let client_journals = vec![Journal {
+            certVerifierAddress: cert_validity.verifier_address,
+            input: altda_commitment.to_rlp_bytes().into(),
+            blockhash: cert_validity.l1_head_block_hash,
+            output: cert_validity.claimed_validity,
+            l1ChainId: cert_validity.l1_chain_id,
+            chainConfigHash: chain_config_hash,
+        }];
+
+        let journals_bytes =
+            verifier.to_journals_bytes(vec![(altda_commitment, cert_validity.clone())]);
+        assert_eq!(journals_bytes, serialize_journals(&client_journals));
+
+        let decoded = canoe_bindings::deserialize_journals(&journals_bytes).unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].blockhash, cert_validity.l1_head_block_hash);
+        assert_eq!(decoded[0].chainConfigHash, chain_config_hash);
+    }
+
+    #[cfg(feature = "sdk")]
+    mod sdk {
+        use super::*;
+        use sp1_sdk::{
+            Prover, ProverClient, SP1ProofMode, SP1ProofWithPublicValues, SP1PublicValues,
+            SP1_CIRCUIT_VERSION,
+        };
+
+        const ELF: &[u8] = include_bytes!("../../elf/canoe-sp1-cc-client");
+
+        fn mock_proof_bytes(public_values: &[u8]) -> Vec<u8> {
+            let client = ProverClient::builder().mock().build();
+            let (pk, _vk) = client.setup(ELF);
+            let proof = SP1ProofWithPublicValues::create_mock_proof(
+                &pk,
+                SP1PublicValues::from(public_values),
+                SP1ProofMode::Compressed,
+                SP1_CIRCUIT_VERSION,
+            );
+            CanoeProofBackend::Sp1CC.tag(serde_json::to_vec(&proof).unwrap())
+        }
+
+        fn mock_verifier() -> CanoeSp1CCVerifier {
+            CanoeSp1CCVerifier {
+                mock_mode: true,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn test_validate_cert_receipt_valid_proof() {
+            let chain_config_hash = B256::repeat_byte(3);
+            let verifier = CanoeSp1CCVerifier {
+                custom_chain_config_hashes: vec![(1, chain_config_hash)],
+                mock_mode: true,
+            };
+            let pair = cert_validity_pair(Some(chain_config_hash));
+            let journals_bytes = verifier.to_journals_bytes(vec![pair.clone()]);
+
+            assert!(verifier
+                .validate_cert_receipt(vec![pair], Some(mock_proof_bytes(&journals_bytes)))
+                .is_ok());
+        }
+
+        #[test]
+        fn test_validate_against_journal_bytes_tampered_proof() {
+            let journals_bytes = vec![1u8, 2, 3, 4];
+            let proof_bytes = mock_proof_bytes(&journals_bytes);
+
+            // public values of the proof do not match the journals
+            assert!(matches!(
+                mock_verifier()
+                    .validate_against_journal_bytes(&[1, 2, 3, 5], Some(proof_bytes.clone())),
+                Err(HokuleaCanoeVerificationError::InconsistentPublicJournal)
+            ));
+
+            // the bytes are not a serialized proof
+            let mut tampered = proof_bytes.clone();
+            tampered.truncate(tampered.len() / 2);
+            assert!(matches!(
+                mock_verifier().validate_against_journal_bytes(&journals_bytes, Some(tampered)),
+                Err(HokuleaCanoeVerificationError::UnableToDeserializeReceipt(_))
+            ));
+
+            assert!(matches!(
+                mock_verifier().validate_against_journal_bytes(&journals_bytes, None),
+                Err(HokuleaCanoeVerificationError::MissingProof)
+            ));
+
+            // a mock proof is rejected by the cpu verifier
+            assert!(matches!(
+                CanoeSp1CCVerifier::default()
+                    .validate_against_journal_bytes(&journals_bytes, Some(proof_bytes)),
+                Err(HokuleaCanoeVerificationError::InvalidProofAndJournal(_))
+            ));
+        }
     }
 }
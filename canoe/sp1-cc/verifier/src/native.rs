@@ -0,0 +1,67 @@
+//! Verification of a sp1-cc proof in native mode with sp1-sdk
+use crate::V_KEY;
+use alloc::string::ToString;
+use canoe_verifier::HokuleaCanoeVerificationError;
+use sp1_sdk::{
+    CpuProver, HashableKey, Prover, ProverClient, SP1ProofWithPublicValues, SP1VerifyingKey,
+};
+use std::sync::OnceLock;
+
+/// The ELF of the sp1-cc client, its verification key must be [V_KEY]
+const ELF: &[u8] = include_bytes!("../../elf/canoe-sp1-cc-client");
+
+/// A prover client along with the verifying key of [ELF]. The setup of the ELF is expensive, so it
+/// is done once per mode, the first time a proof is verified in that mode
+struct ProofVerifier {
+    client: CpuProver,
+    vk: SP1VerifyingKey,
+}
+
+impl ProofVerifier {
+    /// Returns the verifier of the mode, the mock client accepts a mock proof
+    fn get(mock_mode: bool) -> &'static Self {
+        static MOCK: OnceLock<ProofVerifier> = OnceLock::new();
+        static CPU: OnceLock<ProofVerifier> = OnceLock::new();
+        let verifier = if mock_mode { &MOCK } else { &CPU };
+        verifier.get_or_init(|| {
+            let client = if mock_mode {
+                ProverClient::builder().mock().build()
+            } else {
+                ProverClient::builder().cpu().build()
+            };
+            let (_pk, vk) = client.setup(ELF);
+            Self { client, vk }
+        })
+    }
+}
+
+/// Verifies the serde_json serialized [SP1ProofWithPublicValues] (untagged) against [V_KEY], and
+/// checks its public values are the journals bytes
+pub(crate) fn verify_sp1_cc_proof(
+    proof_bytes: &[u8],
+    journals_bytes: &[u8],
+    mock_mode: bool,
+) -> Result<(), HokuleaCanoeVerificationError> {
+    let proof: SP1ProofWithPublicValues = serde_json::from_slice(proof_bytes)
+        .map_err(|e| HokuleaCanoeVerificationError::UnableToDeserializeReceipt(e.to_string()))?;
+
+    if proof.public_values.as_slice() != journals_bytes {
+        return Err(HokuleaCanoeVerificationError::InconsistentPublicJournal);
+    }
+
+    let verifier = ProofVerifier::get(mock_mode);
+    check_v_key(verifier.vk.vk.hash_u32())?;
+    verifier
+        .client
+        .verify(&proof, &verifier.vk)
+        .map_err(|e| HokuleaCanoeVerificationError::InvalidProofAndJournal(e.to_string()))
+}
+
+fn check_v_key(v_key: [u32; 8]) -> Result<(), HokuleaCanoeVerificationError> {
+    if v_key != V_KEY {
+        return Err(HokuleaCanoeVerificationError::InvalidProofAndJournal(
+            "sp1-cc ELF does not match V_KEY".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -1,14 +1,14 @@
 //! This is a crate for generating a steel proof for an eigenda blob.
 use std::str::FromStr;
-use std::time::Instant;
 
 use alloy_primitives::B256;
 use canoe_bindings::StatusCode;
 
+use futures::{stream, Future, StreamExt};
 use risc0_steel::{
     ethereum::{EthEvmEnv, ETH_HOLESKY_CHAIN_SPEC, ETH_MAINNET_CHAIN_SPEC, ETH_SEPOLIA_CHAIN_SPEC},
     host::BlockNumberOrTag,
-    Contract,
+    Contract, EvmBlockHeader,
 };
 use tokio::task;
 
@@ -20,8 +20,12 @@ use anyhow::{Context, Result};
 use async_trait::async_trait;
 use url::Url;
 
-use canoe_provider::{CanoeInput, CanoeProvider, CertVerifierCall};
-use risc0_steel::alloy::providers::ProviderBuilder;
+use canoe_provider::{
+    anchor_canoe_inputs, dedup_canoe_inputs, group_inputs_by_anchor, CanoeInput, CanoeProvider,
+    CertVerifierCall, PhaseTimer, ProofTimings,
+};
+use canoe_verifier::CanoeProofBackend;
+use risc0_steel::alloy::providers::{layers::CacheLayer, ProviderBuilder};
 use risc0_steel::ethereum::EthChainSpec;
 use risc0_zkvm;
 
@@ -44,11 +48,52 @@ impl CanoeProvider for CanoeSteelProvider {
         &self,
         canoe_inputs: Vec<CanoeInput>,
     ) -> Option<Result<Self::Receipt>> {
+        self.create_certs_validity_proof_with_timings(canoe_inputs)
+            .await
+            .map(|proof| proof.map(|(receipt, _)| receipt))
+    }
+
+    async fn create_certs_validity_proof_with_timings(
+        &self,
+        canoe_inputs: Vec<CanoeInput>,
+    ) -> Option<Result<(Self::Receipt, ProofTimings)>> {
         if canoe_inputs.is_empty() {
             return None;
         }
 
-        Some(get_steel_proof(canoe_inputs, &self.eth_rpc_url).await)
+        // a cert occurring several times is proven once, the verifier drops the same occurrences
+        Some(
+            get_steel_proof(
+                dedup_canoe_inputs(canoe_inputs),
+                &self.eth_rpc_url,
+                SteelAnchor::BlockNumber,
+            )
+            .await,
+        )
+    }
+
+    /// The l1 state is loaded by the supplied block hash, the rpc is only asked to serve the block
+    /// number the inputs are anchored at
+    async fn create_certs_validity_proof_at_anchor(
+        &self,
+        canoe_inputs: Vec<CanoeInput>,
+        l1_head_block_hash: B256,
+        l1_head_block_number: u64,
+    ) -> Option<Result<Self::Receipt>> {
+        if canoe_inputs.is_empty() {
+            return None;
+        }
+
+        let canoe_inputs = anchor_canoe_inputs(
+            dedup_canoe_inputs(canoe_inputs),
+            l1_head_block_hash,
+            l1_head_block_number,
+        );
+        Some(
+            get_steel_proof(canoe_inputs, &self.eth_rpc_url, SteelAnchor::BlockHash)
+                .await
+                .map(|(receipt, _)| receipt),
+        )
     }
 
     // steel does not require config hash to pin l1 chain config
@@ -59,20 +104,103 @@ impl CanoeProvider for CanoeSteelProvider {
     fn get_recursive_proof(&self, receipt: &Self::Receipt) -> Option<Self::Proof> {
         Some(receipt.clone())
     }
+
+    fn proof_backend(&self) -> Option<CanoeProofBackend> {
+        Some(CanoeProofBackend::Steel)
+    }
+
+    fn resolved_chain_config(&self, l1_chain_id: u64) -> Result<String> {
+        let (name, _) = l1_chain_spec(l1_chain_id);
+        Ok(format!(
+            "steel {name} chain spec for chain id {l1_chain_id}"
+        ))
+    }
+}
+
+/// Returns the chain spec steel proves against for the l1 chain id, along with its name. A chain
+/// without built-in spec uses a single spec with default fork configuration
+fn l1_chain_spec(l1_chain_id: u64) -> (&'static str, EthChainSpec) {
+    match l1_chain_id {
+        1 => ("built-in mainnet", ETH_MAINNET_CHAIN_SPEC.clone()),
+        11155111 => ("built-in sepolia", ETH_SEPOLIA_CHAIN_SPEC.clone()),
+        17000 => ("built-in holesky", ETH_HOLESKY_CHAIN_SPEC.clone()),
+        _ => (
+            "default single fork",
+            EthChainSpec::new_single(l1_chain_id, Default::default()),
+        ),
+    }
+}
+
+/// Maximal number of warm up preflights running at the same time, see [get_steel_proof]
+const MAX_CONCURRENT_PREFLIGHTS: usize = 8;
+
+/// Maximal number of rpc responses cached for a steel proof
+const PREFLIGHT_CACHE_MAX_ITEMS: u32 = 10_000;
+
+/// Preflights the VerifyDACert call of the canoe input on the env, and evaluates to the validity
+/// returned by the cert verifier. It is a macro since the env type depends on the provider
+macro_rules! preflight_cert {
+    ($env:expr, $canoe_input:expr) => {{
+        let canoe_input: &CanoeInput = $canoe_input;
+        let mut contract = Contract::preflight(canoe_input.verifier_address, &mut $env);
+        match CertVerifierCall::build(&canoe_input.altda_commitment)? {
+            CertVerifierCall::LegacyV2Interface(call) => {
+                contract.call_builder(&call).call().await?
+            }
+            CertVerifierCall::ABIEncodeInterface(call) => {
+                let status = contract.call_builder(&call).call().await?;
+                status == StatusCode::SUCCESS as u8
+            }
+        }
+    }};
+}
+
+/// Runs f on every item, with at most limit futures in flight at once, and returns the outputs in
+/// the order of the items
+async fn run_bounded_in_order<'a, T, F, Fut>(items: &'a [T], limit: usize, f: F) -> Vec<Fut::Output>
+where
+    F: FnMut(&'a T) -> Fut,
+    Fut: Future,
+{
+    stream::iter(items.iter().map(f))
+        .buffered(limit.max(1))
+        .collect()
+        .await
+}
+
+/// How the l1 block the steel env is built at is requested from the rpc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SteelAnchor {
+    /// by the l1_head_block_number of the canoe inputs
+    BlockNumber,
+    /// by the l1_head_block_hash of the canoe inputs
+    BlockHash,
 }
 
 async fn get_steel_proof(
     canoe_inputs: Vec<CanoeInput>,
     eth_rpc_url: &str,
-) -> Result<risc0_zkvm::Receipt> {
-    // ensure chain id and l1 block number across all DAcerts are identical
-    let l1_chain_id = canoe_inputs[0].l1_chain_id;
-    let l1_head_block_number = canoe_inputs[0].l1_head_block_number;
+    steel_anchor: SteelAnchor,
+) -> Result<(risc0_zkvm::Receipt, ProofTimings)> {
+    // reject malformed inputs before spending any effort on proving
     for canoe_input in canoe_inputs.iter() {
-        assert!(canoe_input.l1_chain_id == l1_chain_id);
-        assert!(canoe_input.l1_head_block_number == l1_head_block_number);
+        canoe_input.validate()?;
     }
-    let start = Instant::now();
+
+    // ensure chain id, l1 block number and l1 block hash across all DAcerts are identical, a steel
+    // proof is anchored at a single l1 block
+    let groups = group_inputs_by_anchor(&canoe_inputs)?;
+    if groups.len() != 1 {
+        anyhow::bail!(
+            "a steel proof is anchored at a single l1 block, canoe inputs are anchored at {} l1 blocks",
+            groups.len()
+        );
+    }
+    let anchor = &groups[0].anchor;
+    let l1_chain_id = anchor.l1_chain_id;
+    let l1_head_block_number = anchor.l1_head_block_number;
+    let mut timings = ProofTimings::default();
+    let mut timer = PhaseTimer::start();
     info!(
         "begin to generate a steel proof for {} number of altda commitment at l1 block number {} with chainID {}",
         canoe_inputs.len(),
@@ -82,38 +210,80 @@ async fn get_steel_proof(
 
     let eth_rpc_url = Url::from_str(eth_rpc_url)?;
 
-    // Create an alloy provider for that private key and URL.
-    let provider = ProviderBuilder::new().connect_http(eth_rpc_url);
+    // Create an alloy provider for that private key and URL. The responses at a fixed block are
+    // cached, such that the state fetched by the concurrent warm up preflights is served again
+    // to the env the proof input is built from
+    let provider = ProviderBuilder::new()
+        .layer(CacheLayer::new(PREFLIGHT_CACHE_MAX_ITEMS))
+        .connect_http(eth_rpc_url);
 
-    let chain_spec = match l1_chain_id {
-        1 => ETH_MAINNET_CHAIN_SPEC.clone(),
-        11155111 => ETH_SEPOLIA_CHAIN_SPEC.clone(),
-        17000 => ETH_HOLESKY_CHAIN_SPEC.clone(),
-        _ => EthChainSpec::new_single(l1_chain_id, Default::default()),
-    };
+    let (_, chain_spec) = l1_chain_spec(l1_chain_id);
 
-    let mut env = EthEvmEnv::builder()
+    let builder = EthEvmEnv::builder()
         .chain_spec(&chain_spec)
-        .provider(provider.clone())
-        .block_number_or_tag(BlockNumberOrTag::Number(l1_head_block_number))
-        .build()
-        .await?;
+        .provider(provider.clone());
+    let mut env = match steel_anchor {
+        SteelAnchor::BlockNumber => {
+            builder
+                .block_number_or_tag(BlockNumberOrTag::Number(l1_head_block_number))
+                .build()
+                .await?
+        }
+        SteelAnchor::BlockHash => {
+            builder
+                .block_hash(anchor.l1_head_block_hash)
+                .build()
+                .await?
+        }
+    };
 
-    for canoe_input in canoe_inputs.iter() {
+    // the state served by the rpc must be the one the inputs are anchored at, otherwise
+    // the guest aborts after the whole preflight has been done
+    anchor.check_anchor_hash(env.header().seal())?;
+    let rpc_block_number = env.header().inner().number();
+    if rpc_block_number != l1_head_block_number {
+        anyhow::bail!(
+            "rpc block number {} at l1 block hash {} differs from canoe input anchor {}",
+            rpc_block_number,
+            anchor.l1_head_block_hash,
+            l1_head_block_number,
+        );
+    }
+
+    // Contract::preflight borrows the EvmEnv mutably, because every storage slot and account fetched
+    // over rpc is recorded in the env database to be later turned into the guest input, so the env
+    // cannot be shared by concurrent preflights, and all calls must be proven against a single env.
+    // The rpc portion is parallelized instead: every cert is first preflighted concurrently on a
+    // throwaway env at the same block, which fetches the state it touches into the provider cache,
+    // then the preflights on the env of the proof run one after another from the cache
+    let warm_up_validities =
+        run_bounded_in_order(&canoe_inputs, MAX_CONCURRENT_PREFLIGHTS, |canoe_input| {
+            let provider = provider.clone();
+            let chain_spec = &chain_spec;
+            async move {
+                let mut env = EthEvmEnv::builder()
+                    .chain_spec(chain_spec)
+                    .provider(provider)
+                    .block_hash(anchor.l1_head_block_hash)
+                    .build()
+                    .await?;
+                Ok::<_, anyhow::Error>(preflight_cert!(env, canoe_input))
+            }
+        })
+        .await;
+
+    for (canoe_input, warm_up_validity) in canoe_inputs.iter().zip(warm_up_validities) {
         // Preflight the call to prepare the input that is required to execute the function in
         // the guest without RPC access. It also returns the result of the call.
-        let mut contract = Contract::preflight(canoe_input.verifier_address, &mut env);
+        let is_valid = preflight_cert!(env, canoe_input);
 
-        // calls the function
-        let is_valid = match CertVerifierCall::build(&canoe_input.altda_commitment) {
-            CertVerifierCall::LegacyV2Interface(call) => {
-                contract.call_builder(&call).call().await?
-            }
-            CertVerifierCall::ABIEncodeInterface(call) => {
-                let status = contract.call_builder(&call).call().await?;
-                status == StatusCode::SUCCESS as u8
-            }
-        };
+        // both preflights run the same call against the same block
+        if warm_up_validity? != is_valid {
+            anyhow::bail!(
+                "preflights of altda commitment {} disagree on its validity",
+                canoe_input.altda_commitment.to_digest()
+            );
+        }
 
         // sanity check about the validity, abort early if not
         if canoe_input.claimed_validity != is_valid {
@@ -123,9 +293,12 @@ async fn get_steel_proof(
             );
         }
     }
+    timings.preflight = timer.lap();
+
     // Finally, construct the input from the environment.
     let evm_input: risc0_steel::EvmInput<risc0_steel::ethereum::EthEvmFactory> =
         env.into_input().await?;
+    timings.finalize = timer.lap();
 
     // Create the steel proof.
     let prove_info = task::spawn_blocking(move || {
@@ -145,8 +318,147 @@ async fn get_steel_proof(
     .await?
     .context("failed to create proof")?;
     let receipt = prove_info.receipt;
-    let elapsed = start.elapsed();
-    info!("finish a steel proof generation spent {:?}", elapsed);
+    timings.prove = timer.lap();
+    info!(
+        preflight = ?timings.preflight,
+        finalize = ?timings.finalize,
+        prove = ?timings.prove,
+        "finish a steel proof generation spent {:?}",
+        timings.total()
+    );
+
+    Ok((receipt, timings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, Bytes, U256};
+    use alloy_transport::mock::Asserter;
+    use risc0_steel::alloy::providers::Provider;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_run_bounded_in_order() {
+        // a fake preflight answering with the parity of the cert, slower for earlier certs
+        let preflight = |i: &u64| {
+            let i = *i;
+            async move {
+                tokio::time::sleep(Duration::from_millis(50 - 2 * i)).await;
+                i % 2 == 0
+            }
+        };
+        let certs: Vec<u64> = (0..20).collect();
+
+        let mut sequential = vec![];
+        for cert in certs.iter() {
+            sequential.push(preflight(cert).await);
+        }
+
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        let concurrent = run_bounded_in_order(&certs, 4, |cert| {
+            let in_flight = &in_flight;
+            let max_in_flight = &max_in_flight;
+            let validity = preflight(cert);
+            async move {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                let validity = validity.await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                validity
+            }
+        })
+        .await;
+
+        assert_eq!(concurrent, sequential);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_preflights_match_sequential() {
+        // each cert reads its own slot at the anchor, the mocked rpc answers requests in the order
+        // they are sent, such that a preflight served the answer of another cert gets its validity
+        let anchor = B256::repeat_byte(1);
+        let verifier_address = Address::repeat_byte(2);
+        let certs: Vec<u64> = (0..3 * MAX_CONCURRENT_PREFLIGHTS as u64).collect();
+        let mocked_provider = || {
+            let asserter = Asserter::new();
+            for cert in certs.iter() {
+                asserter.push_success(&U256::from(cert * 3));
+            }
+            ProviderBuilder::new()
+                .layer(CacheLayer::new(PREFLIGHT_CACHE_MAX_ITEMS))
+                .connect_mocked_client(asserter)
+        };
+        // a fake preflight of the cert, finishing out of order with the earlier certs being slower
+        async fn preflight(
+            provider: impl Provider,
+            verifier_address: Address,
+            anchor: B256,
+            cert: u64,
+        ) -> Result<bool> {
+            let slot = provider
+                .get_storage_at(verifier_address, U256::from(cert))
+                .block_id(anchor.into())
+                .await?;
+            tokio::time::sleep(Duration::from_millis(50 - cert)).await;
+            Ok(slot.bit(0))
+        }
+
+        let provider = mocked_provider();
+        let mut sequential = vec![];
+        for cert in certs.iter() {
+            sequential.push(
+                preflight(provider.clone(), verifier_address, anchor, *cert)
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let provider = mocked_provider();
+        let concurrent = run_bounded_in_order(&certs, MAX_CONCURRENT_PREFLIGHTS, |cert| {
+            preflight(provider.clone(), verifier_address, anchor, *cert)
+        })
+        .await;
+        let concurrent: Vec<bool> = concurrent.into_iter().map(Result::unwrap).collect();
 
-    Ok(receipt)
+        assert_eq!(concurrent, sequential);
+        assert!(sequential.contains(&true) && sequential.contains(&false));
+    }
+
+    #[tokio::test]
+    async fn test_preflight_provider_caches_state_at_anchor() {
+        // the mocked rpc answers each request once, so the repeated requests of the preflights on
+        // the env of the proof only succeed if they are served by the cache
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new()
+            .layer(CacheLayer::new(PREFLIGHT_CACHE_MAX_ITEMS))
+            .connect_mocked_client(asserter.clone());
+        let anchor = B256::repeat_byte(1);
+        let address = Address::repeat_byte(2);
+        let code = Bytes::from_static(&[0x60, 0x00]);
+        let slot = U256::from(7);
+        asserter.push_success(&code);
+        asserter.push_success(&slot);
+
+        // the warm up preflight, then the preflight on the env of the proof
+        for _ in 0..2 {
+            let fetched_code = provider.get_code_at(address).block_id(anchor.into()).await;
+            assert_eq!(fetched_code.unwrap(), code);
+            let fetched_slot = provider
+                .get_storage_at(address, U256::ZERO)
+                .block_id(anchor.into())
+                .await;
+            assert_eq!(fetched_slot.unwrap(), slot);
+        }
+
+        // the rpc was only requested twice, a request missing the cache finds no response left
+        let uncached = provider
+            .get_code_at(Address::repeat_byte(3))
+            .block_id(anchor.into())
+            .await;
+        assert!(uncached.is_err());
+    }
 }
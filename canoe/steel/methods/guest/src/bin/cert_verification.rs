@@ -22,11 +22,10 @@ use risc0_steel::{
 use risc0_steel::ethereum::EthChainSpec;
 use risc0_zkvm::guest::env;
 use canoe_bindings::{
-    Journal, StatusCode
+    serialize_journals, Journal, StatusCode
 };
-use canoe_provider::{CanoeInput, CertVerifierCall};
+use canoe_provider::{group_inputs_by_anchor, CanoeInput, CertVerifierCall};
 use alloy_primitives::B256;
-use bincode;
 
 risc0_zkvm::guest::entry!(main);
 
@@ -35,15 +34,15 @@ fn main() {
     let input: EthEvmInput = env::read();
     let canoe_inputs: Vec<CanoeInput> = env::read();
 
-    assert!(!canoe_inputs.is_empty());
-    let l1_chain_id = canoe_inputs[0].l1_chain_id;
-    let l1_head_block_number = canoe_inputs[0].l1_head_block_number;
-    let l1_head_block_hash = canoe_inputs[0].l1_head_block_hash;
-    // require all canoe input share a common l1_chain_id
+    // require all canoe input share a common l1_chain_id, l1_head_block_number and l1_head_block_hash
+    let groups = group_inputs_by_anchor(&canoe_inputs).expect("canoe inputs should be groupable");
+    assert_eq!(groups.len(), 1);
+    let l1_chain_id = groups[0].anchor.l1_chain_id;
+    let l1_head_block_number = groups[0].anchor.l1_head_block_number;
+    let l1_head_block_hash = groups[0].anchor.l1_head_block_hash;
+    // the cert must carry the kzg commitment of the blob checked by batch_verify, if provided
     for canoe_input in canoe_inputs.iter() {
-        assert!(canoe_input.l1_chain_id == l1_chain_id);
-        assert!(canoe_input.l1_head_block_number == l1_head_block_number);
-        assert!(canoe_input.l1_head_block_hash == l1_head_block_hash);
+        canoe_input.check_kzg_commitment().expect("cert kzg commitment should match the expected one");
     }
     
     // Converts the input into a `EvmEnv` for execution. The `with_chain_spec` method is used
@@ -64,7 +63,9 @@ fn main() {
     let mut journals: Vec<Journal> = vec![];
     for canoe_input in canoe_inputs.iter() {
         // Prepare the function call and call the function
-        let is_valid = match CertVerifierCall::build(&canoe_input.altda_commitment) {
+        let is_valid = match CertVerifierCall::build(&canoe_input.altda_commitment)
+            .expect("cert should have a cert verifier interface")
+        {
             CertVerifierCall::LegacyV2Interface(call) => Contract::new(canoe_input.verifier_address, &env).call_builder(&call).call(),
             CertVerifierCall::ABIEncodeInterface(call) => {
                 let status = Contract::new(canoe_input.verifier_address, &env).call_builder(&call).call();
@@ -88,7 +89,7 @@ fn main() {
         journals.push(journal);
     }
 
-    let journal_bytes = bincode::serialize(&journals).expect("should be able to serialize");
+    let journal_bytes = serialize_journals(&journals);
 
     env::commit_slice(&journal_bytes);
 }
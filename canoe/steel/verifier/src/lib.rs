@@ -9,9 +9,12 @@ use eigenda_cert::AltDACommitment;
 
 use risc0_zkvm::Receipt;
 
-use canoe_bindings::Journal;
+use canoe_bindings::{serialize_journals, Journal};
 use canoe_steel_methods::CERT_VERIFICATION_ID;
-use canoe_verifier::{CanoeVerifier, CertValidity, HokuleaCanoeVerificationError};
+use canoe_verifier::{
+    dedup_cert_validity_pairs, CanoeProofBackend, CanoeVerifier, CertValidity,
+    HokuleaCanoeVerificationError,
+};
 use tracing::info;
 
 #[derive(Clone)]
@@ -23,6 +26,8 @@ pub struct CanoeSteelVerifier {}
 ///     CertValidity provides the output and blockhash which comes from boot info
 ///     VERIFIER_ADDRESS is currently burned inside the client
 ///     eigenda_cert contains all the inputs
+/// A lone cert can be checked with [CanoeVerifier::validate_single_cert], against a receipt
+/// proving that cert only
 impl CanoeVerifier for CanoeSteelVerifier {
     fn validate_cert_receipt(
         &self,
@@ -34,6 +39,23 @@ impl CanoeVerifier for CanoeSteelVerifier {
         // use default to_journals_bytes implementation
         let journals_bytes = self.to_journals_bytes(cert_validity_pair);
 
+        self.validate_against_journal_bytes(&journals_bytes, canoe_proof_bytes)
+    }
+
+    fn validate_against_journal_bytes(
+        &self,
+        journals_bytes: &[u8],
+        canoe_proof_bytes: Option<Vec<u8>>,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        // reject a proof produced by another backend before attempting to deserialize it
+        let canoe_proof_bytes = canoe_proof_bytes
+            .map(|proof_bytes| {
+                CanoeProofBackend::Steel
+                    .untag(&proof_bytes)
+                    .map(|p| p.to_vec())
+            })
+            .transpose()?;
+
         cfg_if::cfg_if! {
             if #[cfg(target_os = "zkvm")] {
                 use risc0_zkvm::guest::env;
@@ -45,7 +67,7 @@ impl CanoeVerifier for CanoeSteelVerifier {
                         is provided from other ways which is not verified within zkVM");
                 }
 
-                env::verify(CERT_VERIFICATION_ID, &journals_bytes).map_err(|e| HokuleaCanoeVerificationError::InvalidProofAndJournal(e.to_string()))?;
+                env::verify(CERT_VERIFICATION_ID, journals_bytes).map_err(|e| HokuleaCanoeVerificationError::InvalidProofAndJournal(e.to_string()))?;
             } else {
                 if canoe_proof_bytes.is_none() {
                     return Err(HokuleaCanoeVerificationError::MissingProof);
@@ -63,12 +85,21 @@ impl CanoeVerifier for CanoeSteelVerifier {
         Ok(())
     }
 
+    /// A steel proof commits no chain config hash, the host must not populate any
+    fn check_chain_config_hash(
+        &self,
+        _l1_chain_id: u64,
+        _chain_config_hash: B256,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        Err(HokuleaCanoeVerificationError::UnexpectedChainConfigHash)
+    }
+
     fn to_journals_bytes(
         &self,
         cert_validity_pairs: Vec<(AltDACommitment, CertValidity)>,
     ) -> Vec<u8> {
         let mut journals: Vec<Journal> = Vec::new();
-        for (altda_commitment, cert_validity) in &cert_validity_pairs {
+        for (altda_commitment, cert_validity) in &dedup_cert_validity_pairs(cert_validity_pairs) {
             let rlp_bytes = altda_commitment.to_rlp_bytes();
             assert!(cert_validity.chain_config_hash.is_none());
 
@@ -84,6 +115,6 @@ impl CanoeVerifier for CanoeSteelVerifier {
             journals.push(journal);
         }
 
-        bincode::serialize(&journals).expect("should be able to serialize")
+        serialize_journals(&journals)
     }
 }
@@ -4,6 +4,9 @@
 //! rollup has the option to deployed their own CertVerifier or router, if the rollup has security
 //! constraint.
 #![no_std]
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
 use alloy_primitives::{address, Address};
 use eigenda_cert::EigenDAVersionedCert;
 
@@ -15,6 +18,9 @@ pub enum CanoeVerifierAddressFetcherError {
     /// Invalid Cert validity response
     #[error("Unable to fetch contract address with chain id {0} for legacy interface for V2 certificate")]
     UnknownChainIDForLegacyInterface(u64),
+    /// No cert verifier exists for the cert version
+    #[error("No cert verifier is available for {0} certificate")]
+    UnsupportedCertVersion(&'static str),
 }
 
 pub trait CanoeVerifierAddressFetcher: Clone + Send + 'static {
@@ -56,6 +62,43 @@ impl CanoeVerifierAddressFetcher for CanoeVerifierAddressFetcherDeployedByEigenL
     }
 }
 
+/// A [CanoeVerifierAddressFetcher] looking up addresses from a user supplied map keyed by chain id.
+/// It is meant for rollups that deploy their own CertVerifier or router. The same address is used
+/// for all cert versions on a chain, hence if V2 certs are present, it must be a contract exposing
+/// the legacy interface. A chain id missing from the map is reported as
+/// [CanoeVerifierAddressFetcherError::UnknownChainIDForABIEncodeInterface] for every cert version.
+#[derive(Debug, Clone, Default)]
+pub struct CanoeVerifierAddressFetcherFromMap {
+    addresses: BTreeMap<u64, Address>,
+}
+
+impl CanoeVerifierAddressFetcherFromMap {
+    /// Constructs a new fetcher from (chain id, cert verifier address) entries
+    pub fn new(entries: impl IntoIterator<Item = (u64, Address)>) -> Self {
+        Self {
+            addresses: entries.into_iter().collect(),
+        }
+    }
+}
+
+impl CanoeVerifierAddressFetcher for CanoeVerifierAddressFetcherFromMap {
+    fn fetch_address(
+        &self,
+        chain_id: u64,
+        versioned_cert: &EigenDAVersionedCert,
+    ) -> Result<Address, CanoeVerifierAddressFetcherError> {
+        if let EigenDAVersionedCert::V1(_) = versioned_cert {
+            return Err(CanoeVerifierAddressFetcherError::UnsupportedCertVersion(
+                "V1",
+            ));
+        }
+        self.addresses
+            .get(&chain_id)
+            .copied()
+            .ok_or(CanoeVerifierAddressFetcherError::UnknownChainIDForABIEncodeInterface(chain_id))
+    }
+}
+
 /// get cert verifier address based on chain id, and cert version from altda commitment
 /// V3 cert uses router address
 fn cert_verifier_address(
@@ -63,6 +106,10 @@ fn cert_verifier_address(
     versioned_cert: &EigenDAVersionedCert,
 ) -> Result<Address, CanoeVerifierAddressFetcherError> {
     match &versioned_cert {
+        // validity of V1 cert cannot be proven by canoe
+        EigenDAVersionedCert::V1(_) => Err(
+            CanoeVerifierAddressFetcherError::UnsupportedCertVersion("V1"),
+        ),
         EigenDAVersionedCert::V2(_) => cert_verifier_legacy_v2_interface(chain_id),
         EigenDAVersionedCert::V3(_) => cert_verifier_address_abi_encode_interface(chain_id),
     }
@@ -119,3 +166,52 @@ fn cert_verifier_legacy_v2_interface(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eigenda_cert::test_utils::v3_altda_commitment;
+    use eigenda_cert::EigenDACertV2;
+
+    fn v2_and_v3_certs() -> (EigenDAVersionedCert, EigenDAVersionedCert) {
+        let altda_commitment = v3_altda_commitment();
+        let EigenDAVersionedCert::V3(v3_cert) = altda_commitment.versioned_cert else {
+            panic!("fixture should be a V3 cert");
+        };
+        let v2_cert = EigenDACertV2 {
+            blob_inclusion_info: v3_cert.blob_inclusion_info.clone(),
+            batch_header_v2: v3_cert.batch_header_v2.clone(),
+            nonsigner_stake_and_signature: v3_cert.nonsigner_stake_and_signature.clone(),
+            signed_quorum_numbers: v3_cert.signed_quorum_numbers.clone(),
+        };
+        (
+            EigenDAVersionedCert::V2(v2_cert),
+            EigenDAVersionedCert::V3(v3_cert),
+        )
+    }
+
+    #[test]
+    fn test_fetch_address_from_map() {
+        let (v2_cert, v3_cert) = v2_and_v3_certs();
+        let verifier_address = Address::repeat_byte(1);
+        let fetcher = CanoeVerifierAddressFetcherFromMap::new([(1, verifier_address)]);
+
+        assert_eq!(
+            fetcher.fetch_address(1, &v2_cert).unwrap(),
+            verifier_address
+        );
+        assert_eq!(
+            fetcher.fetch_address(1, &v3_cert).unwrap(),
+            verifier_address
+        );
+
+        assert!(matches!(
+            fetcher.fetch_address(2, &v2_cert),
+            Err(CanoeVerifierAddressFetcherError::UnknownChainIDForABIEncodeInterface(2))
+        ));
+        assert!(matches!(
+            fetcher.fetch_address(2, &v3_cert),
+            Err(CanoeVerifierAddressFetcherError::UnknownChainIDForABIEncodeInterface(2))
+        ));
+    }
+}
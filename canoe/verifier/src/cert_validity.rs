@@ -1,4 +1,6 @@
+use alloc::vec::Vec;
 use alloy_primitives::{Address, B256};
+use eigenda_cert::AltDACommitment;
 use serde::{Deserialize, Serialize};
 
 /// The l1_head from the kona_cfg is chosen to anchor the view call.
@@ -7,7 +9,7 @@ use serde::{Deserialize, Serialize};
 /// The l1_head from kona_cfg is recent enough. But using the
 /// reference block number from eigenda cert can be too old, such that
 /// the proving software cannot reach to that state
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CertValidity {
     /// the claim about if the cert is valid
     pub claimed_validity: bool,
@@ -19,8 +21,34 @@ pub struct CertValidity {
     /// will contain the same block hash
     pub l1_chain_id: u64,
     /// chain config hash that not only includes l1_chain_id but other information like
-    /// fork activation time
+    /// fork activation time. It is populated by the host from the canoe receipt, and is never
+    /// trusted as is: a verifier that commits it (sp1-cc) checks it against l1_chain_id and its
+    /// active fork, a verifier that does not (steel) requires it to be None
     pub chain_config_hash: Option<B256>,
     /// verfier address
     pub verifier_address: Address,
 }
+
+/// Drops the repeated occurrences of a cert validity pair, keeping the first one, such that a cert
+/// appearing several times in a derivation window is only proven once. Pairs are identified by
+/// the digest of the altda commitment along with the cert validity, a cert occurring with
+/// different validities is kept, and all of them have to be proven. Canoe providers drop the
+/// same canoe inputs, see canoe_provider::dedup_canoe_inputs, such that the journals built by a
+/// verifier from all occurrences match the proven ones
+pub fn dedup_cert_validity_pairs(
+    cert_validity_pairs: Vec<(AltDACommitment, CertValidity)>,
+) -> Vec<(AltDACommitment, CertValidity)> {
+    let mut seen: Vec<(B256, CertValidity)> = Vec::new();
+    cert_validity_pairs
+        .into_iter()
+        .filter(|(altda_commitment, cert_validity)| {
+            let key = (altda_commitment.to_digest(), cert_validity.clone());
+            if seen.contains(&key) {
+                false
+            } else {
+                seen.push(key);
+                true
+            }
+        })
+        .collect()
+}
@@ -3,7 +3,13 @@
 extern crate alloc;
 
 pub mod cert_validity;
-pub use cert_validity::CertValidity;
+pub use cert_validity::{dedup_cert_validity_pairs, CertValidity};
+
+pub mod proof_backend;
+pub use proof_backend::CanoeProofBackend;
 
 pub mod verifier;
 pub use verifier::{CanoeNoOpVerifier, CanoeVerifier, HokuleaCanoeVerificationError};
+
+pub mod mock;
+pub use mock::{mock_journal, CanoeMockVerifier};
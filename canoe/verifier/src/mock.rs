@@ -0,0 +1,67 @@
+//! A mock [CanoeVerifier] paired with the mock provider of canoe-provider, such that integration
+//! tests can run the witgen to verification loop without an rpc or a prover.
+use crate::{
+    dedup_cert_validity_pairs, CanoeProofBackend, CanoeVerifier, CertValidity,
+    HokuleaCanoeVerificationError,
+};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use canoe_bindings::{serialize_journals, Journal};
+use eigenda_cert::AltDACommitment;
+
+/// mock_journal returns the journal a canoe proof commits for the cert, with the claimed validity
+/// as output since the mock never evaluates it
+pub fn mock_journal(altda_commitment: &AltDACommitment, cert_validity: &CertValidity) -> Journal {
+    Journal {
+        blockhash: cert_validity.l1_head_block_hash,
+        certVerifierAddress: cert_validity.verifier_address,
+        input: altda_commitment.to_rlp_bytes().into(),
+        output: cert_validity.claimed_validity,
+        l1ChainId: cert_validity.l1_chain_id,
+        chainConfigHash: cert_validity.chain_config_hash.unwrap_or_default(),
+    }
+}
+
+/// CanoeMockVerifier accepts a mock proof only if the journals embedded in it are the journals
+/// rebuilt from the cert validity pairs. It proves nothing about the validity of a cert, and must
+/// never be used outside of tests
+#[derive(Clone, Debug, Default)]
+pub struct CanoeMockVerifier {}
+
+impl CanoeVerifier for CanoeMockVerifier {
+    fn validate_cert_receipt(
+        &self,
+        cert_validity_pair: Vec<(AltDACommitment, CertValidity)>,
+        canoe_proof: Option<Vec<u8>>,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        let journals_bytes = self.to_journals_bytes(cert_validity_pair);
+        self.validate_against_journal_bytes(&journals_bytes, canoe_proof)
+    }
+
+    fn validate_against_journal_bytes(
+        &self,
+        journal_bytes: &[u8],
+        canoe_proof: Option<Vec<u8>>,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        let canoe_proof = canoe_proof.ok_or(HokuleaCanoeVerificationError::MissingProof)?;
+        let proof_bytes = CanoeProofBackend::Mock.untag(&canoe_proof)?;
+        let embedded_journal_bytes: Vec<u8> = serde_json::from_slice(proof_bytes).map_err(|e| {
+            HokuleaCanoeVerificationError::UnableToDeserializeReceipt(e.to_string())
+        })?;
+        if embedded_journal_bytes != journal_bytes {
+            return Err(HokuleaCanoeVerificationError::InconsistentPublicJournal);
+        }
+        Ok(())
+    }
+
+    fn to_journals_bytes(
+        &self,
+        cert_validity_pairs: Vec<(AltDACommitment, CertValidity)>,
+    ) -> Vec<u8> {
+        let journals: Vec<Journal> = dedup_cert_validity_pairs(cert_validity_pairs)
+            .iter()
+            .map(|(altda_commitment, cert_validity)| mock_journal(altda_commitment, cert_validity))
+            .collect();
+        serialize_journals(&journals)
+    }
+}
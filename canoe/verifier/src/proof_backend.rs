@@ -0,0 +1,64 @@
+use crate::HokuleaCanoeVerificationError;
+use alloc::vec::Vec;
+
+/// The zk backend which produced a serialized canoe proof. Each backend serializes its proof
+/// differently, so the serialized proof is prefixed with a one byte tag. It allows a verifier to
+/// cleanly reject a proof from another backend, instead of failing to deserialize it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CanoeProofBackend {
+    /// risc0 steel, the proof is a json serialized receipt
+    Steel = 0,
+    /// sp1 contract call
+    Sp1CC = 1,
+    /// mock backend for tests, the proof is the json serialized journals, see
+    /// [crate::CanoeMockVerifier]
+    Mock = 2,
+}
+
+impl CanoeProofBackend {
+    /// Prefixes the serialized proof with the backend tag
+    pub fn tag(self, proof_bytes: Vec<u8>) -> Vec<u8> {
+        let mut tagged = Vec::with_capacity(proof_bytes.len() + 1);
+        tagged.push(self as u8);
+        tagged.extend_from_slice(&proof_bytes);
+        tagged
+    }
+
+    /// Checks the tag of the canoe proof bytes is the one of this backend, and returns the
+    /// serialized proof without the tag
+    pub fn untag(self, canoe_proof_bytes: &[u8]) -> Result<&[u8], HokuleaCanoeVerificationError> {
+        let (tag, proof_bytes) = canoe_proof_bytes
+            .split_first()
+            .ok_or(HokuleaCanoeVerificationError::MissingProof)?;
+        if *tag != self as u8 {
+            return Err(HokuleaCanoeVerificationError::UnsupportedProofBackend(*tag));
+        }
+        Ok(proof_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_tag_and_untag() {
+        let proof_bytes = vec![7u8; 4];
+        let tagged = CanoeProofBackend::Sp1CC.tag(proof_bytes.clone());
+        assert_eq!(tagged[0], 1);
+        assert_eq!(
+            CanoeProofBackend::Sp1CC.untag(&tagged).unwrap(),
+            proof_bytes.as_slice()
+        );
+        assert!(matches!(
+            CanoeProofBackend::Steel.untag(&tagged),
+            Err(HokuleaCanoeVerificationError::UnsupportedProofBackend(1))
+        ));
+        assert!(matches!(
+            CanoeProofBackend::Steel.untag(&[]),
+            Err(HokuleaCanoeVerificationError::MissingProof)
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::cert_validity::CertValidity;
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
+use alloy_primitives::B256;
 
 use eigenda_cert::AltDACommitment;
 
@@ -21,6 +22,21 @@ pub enum HokuleaCanoeVerificationError {
     /// unable to deserialize receipt
     #[error("Non zkvm environment: unable to deserialize receipt: {0}")]
     UnableToDeserializeReceipt(String),
+    /// Sp1: the host did not populate the l1 chain config hash
+    #[error("Sp1: cert validity is missing the l1 chain config hash")]
+    MissingChainConfigHash,
+    /// Sp1: the l1 chain config hash does not match the l1 chain id and active fork
+    #[error("Sp1: l1 chain config hash is inconsistent with l1 chain id {0}")]
+    InvalidChainConfigHash(u64),
+    /// Steel: the host populated an l1 chain config hash, which a steel proof never commits
+    #[error("Steel: cert validity carries an l1 chain config hash, which steel does not commit")]
+    UnexpectedChainConfigHash,
+    /// the canoe proof is tagged with a backend other than the one of the verifier
+    #[error("canoe proof is tagged with unsupported proof backend {0}")]
+    UnsupportedProofBackend(u8),
+    /// the verifier cannot verify a canoe proof against supplied journal bytes
+    #[error("verifier does not support verifying a canoe proof against journal bytes")]
+    UnsupportedJournalBytesVerification,
 }
 
 pub trait CanoeVerifier: Clone + Send + 'static {
@@ -30,6 +46,66 @@ pub trait CanoeVerifier: Clone + Send + 'static {
         _canoe_proof: Option<Vec<u8>>,
     ) -> Result<(), HokuleaCanoeVerificationError>;
 
+    /// Same as [CanoeVerifier::validate_cert_receipt], but returns the validity attested by the
+    /// proof for every altda commitment once the verification succeeds. A single proof covers all
+    /// certs, so either every claimed validity is attested, or the verification fails as a whole
+    fn validate_cert_receipt_attested(
+        &self,
+        cert_validity_pairs: Vec<(AltDACommitment, CertValidity)>,
+        canoe_proof: Option<Vec<u8>>,
+    ) -> Result<Vec<(AltDACommitment, bool)>, HokuleaCanoeVerificationError> {
+        let attested_validities = cert_validity_pairs
+            .iter()
+            .map(|(altda_commitment, cert_validity)| {
+                (altda_commitment.clone(), cert_validity.claimed_validity)
+            })
+            .collect();
+        self.validate_cert_receipt(cert_validity_pairs, canoe_proof)?;
+        Ok(attested_validities)
+    }
+
+    /// Same as [CanoeVerifier::validate_cert_receipt] for a canoe proof covering one cert only,
+    /// for integrations which never batch certs
+    fn validate_single_cert(
+        &self,
+        altda_commitment: AltDACommitment,
+        cert_validity: CertValidity,
+        canoe_proof: Option<Vec<u8>>,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        self.validate_cert_receipt(vec![(altda_commitment, cert_validity)], canoe_proof)
+    }
+
+    /// Verifies the canoe proof commits to exactly the supplied journal bytes. Unlike
+    /// [CanoeVerifier::validate_cert_receipt], the journals are not rebuilt from cert validity
+    /// pairs, so proofs produced by other tooling can be checked against their committed bytes.
+    /// Verifiers which do not override it return
+    /// [HokuleaCanoeVerificationError::UnsupportedJournalBytesVerification]
+    fn validate_against_journal_bytes(
+        &self,
+        _journal_bytes: &[u8],
+        _canoe_proof: Option<Vec<u8>>,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        Err(HokuleaCanoeVerificationError::UnsupportedJournalBytesVerification)
+    }
+
+    /// Returns the l1 chain ids for which the verifier can verify a canoe proof, or None if it
+    /// accepts any l1 chain id. It allows rejecting an unsupported l1 chain before any proof is
+    /// verified
+    fn supported_chain_ids(&self) -> Option<Vec<u64>> {
+        None
+    }
+
+    /// Checks the chain config hash supplied by the host in a cert validity against the one the
+    /// verifier expects for the l1 chain id, such that a wrong hash is reported as an error before
+    /// any proof is verified. Any hash is accepted by default, for verifiers ignoring it
+    fn check_chain_config_hash(
+        &self,
+        _l1_chain_id: u64,
+        _chain_config_hash: B256,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        Ok(())
+    }
+
     /// The function converts validity and altda commitment into journals.
     /// Journals are concatenated in a serialized byte array. The output of
     /// the serialization must be identical to one committed by zkVM.
@@ -51,6 +127,14 @@ impl CanoeVerifier for CanoeNoOpVerifier {
         Ok(())
     }
 
+    fn validate_against_journal_bytes(
+        &self,
+        _journal_bytes: &[u8],
+        _canoe_proof: Option<Vec<u8>>,
+    ) -> Result<(), HokuleaCanoeVerificationError> {
+        Ok(())
+    }
+
     fn to_journals_bytes(
         &self,
         _cert_validity_pairs: Vec<(AltDACommitment, CertValidity)>,
@@ -58,3 +142,108 @@ impl CanoeVerifier for CanoeNoOpVerifier {
         Vec::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CanoeMockVerifier, CanoeProofBackend};
+    use eigenda_cert::test_utils::v3_altda_commitment;
+
+    #[test]
+    fn test_validate_cert_receipt_attested() {
+        let altda_commitment = v3_altda_commitment();
+        let valid = CertValidity {
+            claimed_validity: true,
+            ..Default::default()
+        };
+        let cert_validity_pairs = vec![
+            (altda_commitment.clone(), valid),
+            (altda_commitment.clone(), CertValidity::default()),
+        ];
+
+        let attested = CanoeNoOpVerifier {}
+            .validate_cert_receipt_attested(cert_validity_pairs.clone(), None)
+            .unwrap();
+        assert_eq!(
+            attested,
+            vec![(altda_commitment.clone(), true), (altda_commitment, false)]
+        );
+
+        // nothing is attested when the verification fails
+        assert!(matches!(
+            CanoeMockVerifier::default().validate_cert_receipt_attested(cert_validity_pairs, None),
+            Err(HokuleaCanoeVerificationError::MissingProof)
+        ));
+    }
+
+    #[test]
+    fn test_validate_single_cert() {
+        let verifier = CanoeMockVerifier::default();
+        let altda_commitment = v3_altda_commitment();
+        let valid = CertValidity {
+            claimed_validity: true,
+            ..Default::default()
+        };
+        let journals_bytes =
+            verifier.to_journals_bytes(vec![(altda_commitment.clone(), valid.clone())]);
+        let canoe_proof = CanoeProofBackend::Mock.tag(serde_json::to_vec(&journals_bytes).unwrap());
+
+        assert!(verifier
+            .validate_single_cert(
+                altda_commitment.clone(),
+                valid.clone(),
+                Some(canoe_proof.clone())
+            )
+            .is_ok());
+        assert!(verifier
+            .validate_cert_receipt(
+                vec![(altda_commitment.clone(), valid)],
+                Some(canoe_proof.clone())
+            )
+            .is_ok());
+
+        // both paths reject a claim the proof does not commit to
+        let invalid = CertValidity::default();
+        assert!(matches!(
+            verifier.validate_single_cert(
+                altda_commitment.clone(),
+                invalid.clone(),
+                Some(canoe_proof.clone())
+            ),
+            Err(HokuleaCanoeVerificationError::InconsistentPublicJournal)
+        ));
+        assert!(matches!(
+            verifier.validate_cert_receipt(vec![(altda_commitment, invalid)], Some(canoe_proof)),
+            Err(HokuleaCanoeVerificationError::InconsistentPublicJournal)
+        ));
+    }
+
+    #[test]
+    fn test_validate_against_journal_bytes_unsupported_by_default() {
+        // a verifier implementing only the required methods
+        #[derive(Clone)]
+        struct MinimalVerifier {}
+
+        impl CanoeVerifier for MinimalVerifier {
+            fn validate_cert_receipt(
+                &self,
+                _cert_validity_pair: Vec<(AltDACommitment, CertValidity)>,
+                _canoe_proof: Option<Vec<u8>>,
+            ) -> Result<(), HokuleaCanoeVerificationError> {
+                Ok(())
+            }
+
+            fn to_journals_bytes(
+                &self,
+                _cert_validity_pairs: Vec<(AltDACommitment, CertValidity)>,
+            ) -> Vec<u8> {
+                Vec::new()
+            }
+        }
+
+        assert!(matches!(
+            MinimalVerifier {}.validate_against_journal_bytes(&[1, 2, 3], Some(vec![1])),
+            Err(HokuleaCanoeVerificationError::UnsupportedJournalBytesVerification)
+        ));
+    }
+}
@@ -0,0 +1,291 @@
+//! This tool verifies a canoe proof saved by a previous run against a cert, without generating
+//! any proof. The cert validity is rebuilt from the l1 block the proof is anchored at, which is
+//! queried from the l1 rpc, and from the cert verifier deployed by EigenLabs on the l1 chain.
+//! The verifier is selected by feature, steel or sp1-cc, and defaults to the mock verifier
+//! cargo run --bin canoe-verify-bin --release --features steel -- --cert-path <cert> \
+//!   --proof-path <proof> --l1-chain-id 11155111 --l1-head-block-number <number> --eth-rpc-url <url>
+use alloy_primitives::B256;
+use alloy_provider::{Provider, ProviderBuilder};
+use anyhow::{anyhow, bail, Result};
+use canoe_verifier::{CanoeVerifier, CertValidity};
+use canoe_verifier_address_fetcher::{
+    CanoeVerifierAddressFetcher, CanoeVerifierAddressFetcherDeployedByEigenLabs,
+};
+use clap::Parser;
+use eigenda_cert::AltDACommitment;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use url::Url;
+
+#[derive(Parser, Debug)]
+struct Args {
+    /// Path to the bytes of the altda commitment holding the cert
+    #[arg(long)]
+    cert_path: PathBuf,
+    /// Path to the canoe proof, tagged with its proof backend like the canoe proof of an eigenda
+    /// witness
+    #[arg(long)]
+    proof_path: PathBuf,
+    /// l1 chain id the canoe proof is verified against
+    #[arg(long)]
+    l1_chain_id: u64,
+    /// l1 block number the canoe proof is anchored at
+    #[arg(long)]
+    l1_head_block_number: u64,
+    /// Validity of the cert attested by the canoe proof
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    claimed_validity: bool,
+    /// Chain config hash committed by a sp1-cc proof, a steel proof does not commit any
+    #[arg(long)]
+    chain_config_hash: Option<B256>,
+    /// if true, the sp1-cc proof is expected to be a mock proof
+    #[cfg(all(feature = "sp1-cc", not(feature = "steel")))]
+    #[arg(long, env = "OP_SUCCINCT_MOCK")]
+    mock_mode: bool,
+    /// Ethereum RPC endpoint URL
+    #[arg(long, env = "ETH_RPC_URL")]
+    eth_rpc_url: String,
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() -> Result<()> {
+    let args = Args::try_parse()?;
+
+    let (altda_commitment, canoe_proof) = read_inputs(&args.cert_path, &args.proof_path)?;
+    let l1_head_block_hash = fetch_block_hash(
+        &args.eth_rpc_url,
+        args.l1_chain_id,
+        args.l1_head_block_number,
+    )
+    .await?;
+    let cert_validity = rebuild_cert_validity(
+        &altda_commitment,
+        &args,
+        l1_head_block_hash,
+        CanoeVerifierAddressFetcherDeployedByEigenLabs {},
+    )?;
+
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "steel")] {
+            let canoe_verifier = canoe_steel_verifier::CanoeSteelVerifier {};
+        } else if #[cfg(feature = "sp1-cc")] {
+            let canoe_verifier = canoe_sp1_cc_verifier::CanoeSp1CCVerifier {
+                mock_mode: args.mock_mode,
+                ..Default::default()
+            };
+        } else {
+            let canoe_verifier = canoe_verifier::CanoeMockVerifier::default();
+        }
+    }
+
+    match canoe_verifier.validate_single_cert(altda_commitment, cert_validity, Some(canoe_proof)) {
+        Ok(()) => {
+            println!("canoe proof verification pass");
+            Ok(())
+        }
+        Err(e) => {
+            println!("canoe proof verification fail: {e}");
+            std::process::exit(1)
+        }
+    }
+}
+
+/// Reads the altda commitment and the canoe proof from their files
+fn read_inputs(cert_path: &Path, proof_path: &Path) -> Result<(AltDACommitment, Vec<u8>)> {
+    let cert_bytes = std::fs::read(cert_path)?;
+    let altda_commitment = AltDACommitment::try_from(cert_bytes.as_slice())
+        .map_err(|e| anyhow!("cannot parse altda commitment: {e}"))?;
+    let canoe_proof = std::fs::read(proof_path)?;
+    Ok((altda_commitment, canoe_proof))
+}
+
+/// Returns the hash of the l1 block at l1_head_block_number, after checking the rpc serves the
+/// l1 chain id
+async fn fetch_block_hash(
+    eth_rpc_url: &str,
+    l1_chain_id: u64,
+    l1_head_block_number: u64,
+) -> Result<B256> {
+    let provider = ProviderBuilder::new().connect_http(Url::from_str(eth_rpc_url)?);
+
+    let provider_chain_id = provider.get_chain_id().await?;
+    if provider_chain_id != l1_chain_id {
+        bail!("the provided rpc serves chain id {provider_chain_id}, expected {l1_chain_id}");
+    }
+
+    let block = provider
+        .get_block_by_number(l1_head_block_number.into())
+        .await?
+        .ok_or_else(|| anyhow!("block {l1_head_block_number} not found"))?;
+    Ok(block.header.into_consensus().hash_slow())
+}
+
+/// Rebuilds the cert validity the canoe proof attests for the altda commitment
+fn rebuild_cert_validity(
+    altda_commitment: &AltDACommitment,
+    args: &Args,
+    l1_head_block_hash: B256,
+    canoe_address_fetcher: impl CanoeVerifierAddressFetcher,
+) -> Result<CertValidity> {
+    Ok(CertValidity {
+        claimed_validity: args.claimed_validity,
+        l1_head_block_hash,
+        l1_chain_id: args.l1_chain_id,
+        chain_config_hash: args.chain_config_hash,
+        verifier_address: canoe_address_fetcher
+            .fetch_address(args.l1_chain_id, &altda_commitment.versioned_cert)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use canoe_verifier::{CanoeMockVerifier, CanoeProofBackend};
+    use eigenda_cert::test_utils::{v3_altda_commitment, v3_altda_commitment_calldata};
+
+    #[test]
+    fn test_verify_saved_proof() {
+        let args = Args::parse_from([
+            "canoe-verify-bin",
+            "--cert-path",
+            "cert.bin",
+            "--proof-path",
+            "proof.bin",
+            "--l1-chain-id",
+            "11155111",
+            "--l1-head-block-number",
+            "1",
+            "--eth-rpc-url",
+            "http://127.0.0.1:8545",
+        ]);
+        let dir = std::env::temp_dir().join(format!("canoe-verify-bin-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert_path = dir.join(&args.cert_path);
+        let proof_path = dir.join(&args.proof_path);
+
+        // a known-good proof, saved along with its cert
+        let cert_bytes = v3_altda_commitment_calldata();
+        let altda_commitment = v3_altda_commitment();
+        let l1_head_block_hash = B256::repeat_byte(1);
+        let cert_validity = rebuild_cert_validity(
+            &altda_commitment,
+            &args,
+            l1_head_block_hash,
+            CanoeVerifierAddressFetcherDeployedByEigenLabs {},
+        )
+        .unwrap();
+        let canoe_verifier = CanoeMockVerifier::default();
+        let journals_bytes =
+            canoe_verifier.to_journals_bytes(vec![(altda_commitment, cert_validity)]);
+        let canoe_proof = CanoeProofBackend::Mock.tag(serde_json::to_vec(&journals_bytes).unwrap());
+        std::fs::write(&cert_path, &cert_bytes).unwrap();
+        std::fs::write(&proof_path, &canoe_proof).unwrap();
+
+        let verify = || {
+            let (altda_commitment, canoe_proof) = read_inputs(&cert_path, &proof_path).unwrap();
+            let cert_validity = rebuild_cert_validity(
+                &altda_commitment,
+                &args,
+                l1_head_block_hash,
+                CanoeVerifierAddressFetcherDeployedByEigenLabs {},
+            )
+            .unwrap();
+            canoe_verifier.validate_single_cert(altda_commitment, cert_validity, Some(canoe_proof))
+        };
+        assert!(verify().is_ok());
+
+        // a corrupted proof fails
+        let mut corrupted = canoe_proof;
+        *corrupted.last_mut().unwrap() ^= 1;
+        std::fs::write(&proof_path, &corrupted).unwrap();
+        assert!(verify().is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(all(feature = "sp1-cc", not(feature = "steel")))]
+    #[test]
+    fn test_verify_saved_sp1_cc_mock_proof() {
+        use canoe_sp1_cc_verifier::CanoeSp1CCVerifier;
+        use sp1_sdk::{
+            Prover, ProverClient, SP1ProofMode, SP1ProofWithPublicValues, SP1PublicValues,
+            SP1_CIRCUIT_VERSION,
+        };
+
+        let chain_config_hash = B256::repeat_byte(3);
+        let chain_config_hash_arg = chain_config_hash.to_string();
+        let args = Args::parse_from([
+            "canoe-verify-bin",
+            "--cert-path",
+            "cert.bin",
+            "--proof-path",
+            "proof.bin",
+            "--l1-chain-id",
+            "11155111",
+            "--l1-head-block-number",
+            "1",
+            "--chain-config-hash",
+            chain_config_hash_arg.as_str(),
+            "--mock-mode",
+            "--eth-rpc-url",
+            "http://127.0.0.1:8545",
+        ]);
+        assert!(args.mock_mode);
+        let dir =
+            std::env::temp_dir().join(format!("canoe-verify-bin-sp1-cc-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert_path = dir.join(&args.cert_path);
+        let proof_path = dir.join(&args.proof_path);
+
+        // the chain config hash of sepolia is pinned, such that the mock proof can commit any
+        let canoe_verifier = CanoeSp1CCVerifier {
+            custom_chain_config_hashes: vec![(args.l1_chain_id, chain_config_hash)],
+            mock_mode: args.mock_mode,
+        };
+
+        // a mock sp1-cc proof committing the journal of the cert, saved along with its cert
+        let altda_commitment = v3_altda_commitment();
+        let l1_head_block_hash = B256::repeat_byte(1);
+        let cert_validity = rebuild_cert_validity(
+            &altda_commitment,
+            &args,
+            l1_head_block_hash,
+            CanoeVerifierAddressFetcherDeployedByEigenLabs {},
+        )
+        .unwrap();
+        let journals_bytes =
+            canoe_verifier.to_journals_bytes(vec![(altda_commitment, cert_validity)]);
+        let client = ProverClient::builder().mock().build();
+        let (pk, _vk) = client.setup(include_bytes!("../../sp1-cc/elf/canoe-sp1-cc-client"));
+        let proof = SP1ProofWithPublicValues::create_mock_proof(
+            &pk,
+            SP1PublicValues::from(&journals_bytes),
+            SP1ProofMode::Compressed,
+            SP1_CIRCUIT_VERSION,
+        );
+        let canoe_proof = CanoeProofBackend::Sp1CC.tag(serde_json::to_vec(&proof).unwrap());
+        std::fs::write(&cert_path, v3_altda_commitment_calldata()).unwrap();
+        std::fs::write(&proof_path, &canoe_proof).unwrap();
+
+        let verify = |claimed_validity: bool| {
+            let (altda_commitment, canoe_proof) = read_inputs(&cert_path, &proof_path).unwrap();
+            let mut cert_validity = rebuild_cert_validity(
+                &altda_commitment,
+                &args,
+                l1_head_block_hash,
+                CanoeVerifierAddressFetcherDeployedByEigenLabs {},
+            )
+            .unwrap();
+            cert_validity.claimed_validity = claimed_validity;
+            canoe_verifier.validate_single_cert(altda_commitment, cert_validity, Some(canoe_proof))
+        };
+        assert!(verify(true).is_ok());
+
+        // the proof does not attest the opposite claim
+        assert!(verify(false).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,2 +1,3 @@
 #![no_std]
 pub mod fp_client;
+pub mod replay;
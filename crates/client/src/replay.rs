@@ -0,0 +1,65 @@
+//! Replays the eigenda derivation of a single l1 block, without running the derivation pipeline
+//! or the executor.
+extern crate alloc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+use alloy_primitives::Bytes;
+use tracing::info;
+
+use hokulea_eigenda::{EigenDADataSource, EigenDAPreimageProvider, EigenDAPreimageSource};
+
+use kona_derive::{
+    BlobProvider, ChainProvider, EthereumDataSource, PipelineErrorKind, PipelineResult,
+};
+use kona_preimage::CommsClient;
+use kona_proof::{l1::OracleL1ChainProvider, BootInfo, FlushableCache};
+
+/// Derives all the items of the l1 block at `l1_block_number`, reading every preimage from the
+/// oracle. The block must be an ancestor of the l1 head in the boot info. The batcher address is
+/// taken from the genesis system config of the rollup.
+pub async fn replay_l1_block<
+    O: CommsClient + FlushableCache + Send + Sync + Debug,
+    B: BlobProvider + Send + Sync + Debug + Clone,
+    E: EigenDAPreimageProvider + Send + Sync + Debug + Clone,
+>(
+    oracle: Arc<O>,
+    beacon: B,
+    eigenda: E,
+    l1_block_number: u64,
+) -> PipelineResult<Vec<Bytes>>
+where
+    <B as BlobProvider>::Error: Debug,
+    <E as EigenDAPreimageProvider>::Error: Debug,
+{
+    let boot = BootInfo::load(oracle.as_ref())
+        .await
+        .map_err(Into::<PipelineErrorKind>::into)?;
+    let rollup_config = boot.rollup_config;
+    let batcher_address = rollup_config
+        .genesis
+        .system_config
+        .as_ref()
+        .map(|system_config| system_config.batcher_address)
+        .unwrap_or_default();
+
+    let mut l1_provider = OracleL1ChainProvider::new(boot.l1_head, oracle);
+    let block_info = l1_provider
+        .block_info_by_number(l1_block_number)
+        .await
+        .map_err(Into::<PipelineErrorKind>::into)?;
+
+    let dap = EthereumDataSource::new_from_parts(l1_provider, beacon, &rollup_config);
+    let eigenda_preimage_source = EigenDAPreimageSource::new(eigenda);
+    let mut dap = EigenDADataSource::new(dap, eigenda_preimage_source);
+
+    let items = dap.drain_block(&block_info, batcher_address).await?;
+    info!(
+        target: "client",
+        "Replayed l1 block #{number}, derived {num} items",
+        number = block_info.number,
+        num = items.len()
+    );
+    Ok(items)
+}
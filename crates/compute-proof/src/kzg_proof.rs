@@ -1,7 +1,9 @@
 //! This is a crate for generating a kzg proof for an eigenda blob. In the future,
 //! such proof is carried inside the blob header. Then it can be removed. This crate access filesystem,
 //! cannot be used in any fault proof or zk vm.
-use alloy_primitives::Bytes;
+use alloy_primitives::{Bytes, FixedBytes, U256};
+use eigenda_cert::G1Point;
+use hokulea_eigenda::EncodedPayload;
 use num::BigUint;
 use rust_kzg_bn254_primitives::blob::Blob;
 use rust_kzg_bn254_primitives::errors::KzgError;
@@ -9,16 +11,37 @@ use rust_kzg_bn254_prover::kzg::KZG;
 use rust_kzg_bn254_prover::srs::SRS;
 use spin::Lazy;
 
+/// The order of the eigenda srs
+pub const SRS_ORDER: u32 = 268435456;
+
+/// The number of g1 points needed to commit the largest eigenda blob, 16MiB of field elements
+pub const MAX_BLOB_SRS_POINTS: u32 = 524288;
+
 /// load srs points
 pub static G1_SRS: Lazy<SRS> = Lazy::new(load_g1_srs);
 
 fn load_g1_srs() -> SRS {
     let srs_file_path = "resources/g1.point";
     // In the future, it might make sense to let the proxy to return kzg proof, instead of local computation
-    SRS::new(srs_file_path, 268435456, 524288)
+    load_g1_srs_from_file(srs_file_path, MAX_BLOB_SRS_POINTS)
         .unwrap_or_else(|err| panic!("Failed to load SRS file {srs_file_path}: {err}"))
 }
 
+/// Loads the first num_points g1 points of the srs from a file of compressed big endian points,
+/// the format of resources/g1.point. Unlike [G1_SRS], it allows proving with an srs file located
+/// elsewhere. It returns an error if the file holds fewer points than num_points, which is
+/// [MAX_BLOB_SRS_POINTS] for an srs able to prove any eigenda blob
+pub fn load_g1_srs_from_file(srs_file_path: &str, num_points: u32) -> Result<SRS, KzgError> {
+    let srs = SRS::new(srs_file_path, SRS_ORDER, num_points)?;
+    if srs.g1.len() != num_points as usize {
+        return Err(KzgError::GenericError(format!(
+            "srs file {srs_file_path} contains {} g1 points, expected {num_points}",
+            srs.g1.len()
+        )));
+    }
+    Ok(srs)
+}
+
 /// This function computes a KZG proof for a eigenDA blob
 /// nitro code <https://github.com/Layr-Labs/nitro/blob/14f09745b74321f91d1f702c3e7bb5eb7d0e49ce/arbitrator/prover/src/kzgbn254.rs#L141>
 /// could refactor in the future, such that both host and client can compute the proof
@@ -34,6 +57,37 @@ pub fn compute_kzg_proof_with_srs(encoded_payload: &[u8], srs: &SRS) -> Result<B
     kzg.calculate_and_store_roots_of_unity(encoded_payload.len() as u64)
         .unwrap();
 
+    compute_kzg_proof_with_kzg(&mut kzg, encoded_payload, srs)
+}
+
+/// This function computes the KZG proofs of many eigenDA blobs, returned in the same order.
+/// Unlike calling [compute_kzg_proof_with_srs] per blob, a single [KZG] is reused, and its roots
+/// of unity are only recomputed when the blob length changes from the previous blob
+pub fn compute_kzg_proofs_batch(
+    blobs: &[Vec<u8>],
+    srs: &SRS,
+) -> Result<Vec<FixedBytes<64>>, KzgError> {
+    let mut kzg = KZG::new();
+    let mut roots_of_unity_len = None;
+    let mut proofs = Vec::with_capacity(blobs.len());
+    for encoded_payload in blobs {
+        let len = encoded_payload.len() as u64;
+        if roots_of_unity_len != Some(len) {
+            kzg.calculate_and_store_roots_of_unity(len)?;
+            roots_of_unity_len = Some(len);
+        }
+        let proof = compute_kzg_proof_with_kzg(&mut kzg, encoded_payload, srs)?;
+        proofs.push(FixedBytes::from_slice(proof.as_ref()));
+    }
+    Ok(proofs)
+}
+
+/// Computes a KZG proof with a [KZG] whose roots of unity are already set for the blob length
+fn compute_kzg_proof_with_kzg(
+    kzg: &mut KZG,
+    encoded_payload: &[u8],
+    srs: &SRS,
+) -> Result<Bytes, KzgError> {
     // The encoded payload is a polynomial presented in its evaluation form
     let blob = Blob::new(encoded_payload).expect("should be able to construct a blob");
     let input_poly = blob.to_polynomial_eval_form();
@@ -54,6 +108,34 @@ pub fn compute_kzg_proof_with_srs(encoded_payload: &[u8], srs: &SRS) -> Result<B
     Ok(proof_bytes.into())
 }
 
+/// This function commits the encoded payload and compares the commitment with the expected one,
+/// usually the kzg commitment of the cert returned by get_kzg_commitment. It allows validating a
+/// downloaded blob against a cert without a kzg proof. An encoded payload which cannot be
+/// committed, for instance one longer than the srs, never matches
+pub fn verify_commitment(encoded_payload: &EncodedPayload, expected: &G1Point, srs: &SRS) -> bool {
+    let Ok(blob) = Blob::new(encoded_payload.serialize()) else {
+        return false;
+    };
+    let mut kzg = KZG::new();
+    if kzg
+        .calculate_and_store_roots_of_unity(blob.len() as u64)
+        .is_err()
+    {
+        return false;
+    }
+    let Ok(commitment) = kzg.commit_eval_form(&blob.to_polynomial_eval_form(), srs) else {
+        return false;
+    };
+
+    let commitment_x_bigint: BigUint = commitment.x.into();
+    let commitment_y_bigint: BigUint = commitment.y.into();
+    let commitment = G1Point {
+        x: U256::from_be_bytes(convert_biguint_to_be_32_bytes(&commitment_x_bigint)),
+        y: U256::from_be_bytes(convert_biguint_to_be_32_bytes(&commitment_y_bigint)),
+    };
+    commitment == *expected
+}
+
 /// This function convert a BigUint into 32Bytes vector in big endian format
 //pub fn append_left_padded_biguint_be(vec: &mut Vec<u8>, biguint: &BigUint) {
 pub fn convert_biguint_to_be_32_bytes(biguint: &BigUint) -> [u8; 32] {
@@ -67,6 +149,20 @@ pub fn convert_biguint_to_be_32_bytes(biguint: &BigUint) -> [u8; 32] {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_primitives::hex;
+    use rust_kzg_bn254_primitives::helpers::read_g1_point_from_bytes_be;
+
+    // the first 4 g1 points of the srs, enough for blobs of up to 4 field elements
+    const G1_POINTS_BYTE: &str = "8000000000000000000000000000000000000000000000000000000000000001cbfc87ecbdcdc23ef5481bb179aaada7f42c22d2dfd52b4655a18c2879c54eea9fb27cc0e2465b3e57a42a051dbfbd8d0b62eec80cd07c46401781deab36ca27c44ab250113840f37622eb001cfbcb1dec55f15e6ea48333ddb63e9d2befecab";
+
+    fn get_g1_points() -> SRS {
+        let g1_points_bytes = hex::decode(G1_POINTS_BYTE).unwrap();
+        let g1 = g1_points_bytes
+            .chunks(32)
+            .map(|b| read_g1_point_from_bytes_be(b).unwrap())
+            .collect();
+        SRS { g1, order: 4 }
+    }
 
     #[test]
     fn test_convert_biguint_to_be_32_bytes() {
@@ -87,4 +183,79 @@ mod tests {
         let expected = a;
         assert_eq!(&out_a[..], &expected[..]);
     }
+
+    #[test]
+    fn test_compute_kzg_proofs_batch_matches_single_proof() {
+        let srs = get_g1_points();
+        // every field element starts with a zero byte to stay within the modulus
+        let blob_1 = [vec![0u8; 33], vec![1u8; 31]].concat();
+        let blob_2 = [vec![0u8], vec![2u8; 31], vec![0u8; 65], vec![3u8; 31]].concat();
+        // the repeated blob reuses the roots of unity, the second one forces a recomputation
+        let blobs = vec![blob_1.clone(), blob_1, blob_2];
+
+        let batch_proofs = compute_kzg_proofs_batch(&blobs, &srs).unwrap();
+        assert_eq!(batch_proofs.len(), blobs.len());
+        for (blob, batch_proof) in blobs.iter().zip(batch_proofs.iter()) {
+            let proof = compute_kzg_proof_with_srs(blob, &srs).unwrap();
+            assert_eq!(batch_proof.as_slice(), proof.as_ref());
+        }
+
+        assert!(compute_kzg_proofs_batch(&[], &srs).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verify_commitment() {
+        let srs = get_g1_points();
+        let encoded_payload = EncodedPayload {
+            encoded_payload: [vec![0u8; 33], vec![1u8; 31]].concat().into(),
+        };
+
+        // the commitment computed the same way the kzg proof commits the blob
+        let blob = Blob::new(encoded_payload.serialize()).unwrap();
+        let mut kzg = KZG::new();
+        kzg.calculate_and_store_roots_of_unity(blob.len() as u64)
+            .unwrap();
+        let commitment = kzg
+            .commit_eval_form(&blob.to_polynomial_eval_form(), &srs)
+            .unwrap();
+        let commitment_x_bigint: BigUint = commitment.x.into();
+        let commitment_y_bigint: BigUint = commitment.y.into();
+        let expected = G1Point {
+            x: U256::from_be_bytes(convert_biguint_to_be_32_bytes(&commitment_x_bigint)),
+            y: U256::from_be_bytes(convert_biguint_to_be_32_bytes(&commitment_y_bigint)),
+        };
+        assert!(verify_commitment(&encoded_payload, &expected, &srs));
+
+        let mismatched = G1Point {
+            x: expected.x,
+            y: expected.y + U256::from(1),
+        };
+        assert!(!verify_commitment(&encoded_payload, &mismatched, &srs));
+
+        // another blob does not match the commitment
+        let other_encoded_payload = EncodedPayload {
+            encoded_payload: [vec![0u8; 33], vec![2u8; 31]].concat().into(),
+        };
+        assert!(!verify_commitment(&other_encoded_payload, &expected, &srs));
+    }
+
+    #[test]
+    fn test_load_g1_srs_from_file() {
+        let srs_file_path = std::env::temp_dir().join("hokulea_test_g1.point");
+        std::fs::write(&srs_file_path, hex::decode(G1_POINTS_BYTE).unwrap()).unwrap();
+        let srs_file_path = srs_file_path.to_str().unwrap();
+
+        let srs = load_g1_srs_from_file(srs_file_path, 4).unwrap();
+        assert_eq!(srs.g1, get_g1_points().g1);
+
+        // the proof computed with the loaded srs matches the one of the embedded points
+        let blob = [vec![0u8; 33], vec![1u8; 31]].concat();
+        assert_eq!(
+            compute_kzg_proof_with_srs(&blob, &srs).unwrap(),
+            compute_kzg_proof_with_srs(&blob, &get_g1_points()).unwrap()
+        );
+
+        // the file holds fewer points than required
+        assert!(load_g1_srs_from_file(srs_file_path, 5).is_err());
+    }
 }
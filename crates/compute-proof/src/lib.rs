@@ -11,5 +11,8 @@
 
 pub mod kzg_proof;
 pub use kzg_proof::{
-    compute_kzg_proof, compute_kzg_proof_with_srs, convert_biguint_to_be_32_bytes, G1_SRS,
+    compute_kzg_proof, compute_kzg_proof_with_srs, compute_kzg_proofs_batch,
+    convert_biguint_to_be_32_bytes, load_g1_srs_from_file, verify_commitment, G1_SRS,
+    MAX_BLOB_SRS_POINTS, SRS_ORDER,
 };
+pub use rust_kzg_bn254_prover::srs::SRS;
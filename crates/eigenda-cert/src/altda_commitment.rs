@@ -1,7 +1,8 @@
-use crate::{EigenDACertV2, EigenDACertV3, G1Point};
+use crate::{BlobInfoV1, EigenDACertV2, EigenDACertV3, G1Point};
+use alloc::string::String;
 use alloc::vec::Vec;
 use alloy_primitives::keccak256;
-use alloy_primitives::B256;
+use alloy_primitives::{Bytes, B256, U256};
 use alloy_rlp::Decodable;
 use alloy_rlp::Encodable;
 use alloy_rlp::Error;
@@ -11,12 +12,49 @@ use serde::{Deserialize, Serialize};
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum EigenDAVersionedCert {
+    /// V1, only supported for parsing and digesting historical certs
+    V1(BlobInfoV1),
     /// V2
     V2(EigenDACertV2),
     /// V3
     V3(EigenDACertV3),
 }
 
+impl EigenDAVersionedCert {
+    /// on-wire version byte of a V1 cert
+    pub const V1_VERSION_BYTE: u8 = 0;
+    /// on-wire version byte of a V2 cert
+    pub const V2_VERSION_BYTE: u8 = 1;
+    /// on-wire version byte of a V3 cert
+    pub const V3_VERSION_BYTE: u8 = 2;
+
+    /// version_byte returns the byte that precedes the rlp encoded cert in an altda commitment.
+    /// It is the byte consumed by the parsing of an [AltDACommitment]
+    pub fn version_byte(&self) -> u8 {
+        match self {
+            EigenDAVersionedCert::V1(_) => Self::V1_VERSION_BYTE,
+            EigenDAVersionedCert::V2(_) => Self::V2_VERSION_BYTE,
+            EigenDAVersionedCert::V3(_) => Self::V3_VERSION_BYTE,
+        }
+    }
+
+    /// validate checks the invariants of the cert not enforced by its rlp decoding, such that
+    /// converting it to its solidity type cannot panic
+    pub fn validate(&self) -> Result<(), AltDACommitmentParseError> {
+        match self {
+            EigenDAVersionedCert::V1(c) => c.blob_header.commitment.validate(),
+            EigenDAVersionedCert::V2(c) => {
+                c.blob_inclusion_info.validate()?;
+                c.nonsigner_stake_and_signature.validate()
+            }
+            EigenDAVersionedCert::V3(c) => {
+                c.blob_inclusion_info.validate()?;
+                c.nonsigner_stake_and_signature.validate()
+            }
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error, Clone, Copy, PartialEq, Eq)]
 pub enum AltDACommitmentParseError {
     #[error("Insufficient altda commitment data")]
@@ -29,6 +67,10 @@ pub enum AltDACommitmentParseError {
     UnsupportedCertVersionType(u8),
     #[error("Unable to decode rlp cert: {0}")]
     InvalidRlpCert(Error),
+    #[error("Malformed G2 point with {x_len} x and {y_len} y elements, expected 2 each")]
+    MalformedG2Point { x_len: usize, y_len: usize },
+    #[error("Malformed V1 commitment with {x_len} bytes x and {y_len} bytes y, expected 32 each")]
+    MalformedV1Commitment { x_len: usize, y_len: usize },
 }
 
 /// AltDACommitment contains EigenDA cert, and is used as a part of key to uniquely
@@ -44,9 +86,74 @@ pub struct AltDACommitment {
     pub versioned_cert: EigenDAVersionedCert,
 }
 
+/// The index where INTERFACE_BYTE is located
+/// More see <https://github.com/Layr-Labs/hokulea/tree/master/docs#reserved-addresses-for-da-certificates>
+pub const RESERVED_EIGENDA_API_BYTE_INDEX: usize = 32;
+
+/// In the address space of preimage oracle, which interface type a validity query is addressed at
+/// More see <https://github.com/Layr-Labs/hokulea/tree/master/docs#reserved-addresses-for-da-certificates>
+pub const RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY: u8 = 1;
+
+/// In the address space of preimage oracle, which interface type a recency query is addressed at
+/// More see <https://github.com/Layr-Labs/hokulea/tree/master/docs#reserved-addresses-for-da-certificates>
+pub const RESERVED_EIGENDA_API_BYTE_FOR_RECENCY: u8 = 2;
+
+/// Number of hex characters kept by [short_digest_hex]
+pub const SHORT_DIGEST_HEX_LEN: usize = 8;
+
+/// Returns the first [SHORT_DIGEST_HEX_LEN] hex characters of a digest of an altda commitment,
+/// see [AltDACommitment::digest_hex_short]
+pub fn short_digest_hex(digest: &B256) -> String {
+    alloy_primitives::hex::encode(&digest[..SHORT_DIGEST_HEX_LEN / 2])
+}
+
+/// BlobKey is the [AltDACommitment::digest_template] of an altda commitment, from which the key of
+/// every field element of its encoded payload is derived
+///  |  32 bytes | 0 .. 0 | 8 bytes             |
+///  |cert digest| 0 .. 0 | field element index |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobKey([u8; BlobKey::LEN]);
+
+impl BlobKey {
+    /// Number of bytes of a key
+    pub const LEN: usize = 80;
+    /// Offset of the big endian field element index within a key
+    pub const INDEX_OFFSET: usize = 72;
+
+    /// Returns the key template of the altda commitment, holding the digest of the cert
+    pub fn from_commitment(altda_commitment: &AltDACommitment) -> Self {
+        let mut key = [0u8; Self::LEN];
+        key[..32].copy_from_slice(altda_commitment.to_digest().as_slice());
+        Self(key)
+    }
+
+    /// Returns the key addressing the field element at index of the encoded payload
+    pub fn with_index(&self, index: u64) -> [u8; Self::LEN] {
+        let mut key = self.0;
+        key[Self::INDEX_OFFSET..].copy_from_slice(&index.to_be_bytes());
+        key
+    }
+}
+
+/// da layer bytes accepted when parsing an altda commitment without an explicit allow-list,
+/// 0 is eigenda
+pub const DEFAULT_DA_LAYER_BYTES: &[u8] = &[0];
+
 impl TryFrom<&[u8]> for AltDACommitment {
     type Error = AltDACommitmentParseError;
     fn try_from(value: &[u8]) -> Result<AltDACommitment, Self::Error> {
+        AltDACommitment::try_from_with_da_layer_bytes(value, DEFAULT_DA_LAYER_BYTES)
+    }
+}
+
+impl AltDACommitment {
+    /// Parses an altda commitment like its TryFrom<&[u8]> impl, but accepts any da layer byte of
+    /// allowed_da_layer_bytes. The da layer byte is kept in the commitment, such that the caller
+    /// can route it, and it round trips through [AltDACommitment::to_rlp_bytes]
+    pub fn try_from_with_da_layer_bytes(
+        value: &[u8],
+        allowed_da_layer_bytes: &[u8],
+    ) -> Result<AltDACommitment, AltDACommitmentParseError> {
         // at least 3 bytes to indicate the type
         if value.len() < 4 {
             return Err(AltDACommitmentParseError::InsufficientData);
@@ -61,39 +168,67 @@ impl TryFrom<&[u8]> for AltDACommitment {
 
         // da_layer_byte, eigenda is 0
         let da_layer_byte = value[1];
-        if da_layer_byte != 0 {
+        if !allowed_da_layer_bytes.contains(&da_layer_byte) {
             return Err(AltDACommitmentParseError::UnsupportedDaLayerType);
         }
 
         let versioned_cert = match value[2] {
-            // V2 cert
-            1 => {
-                let v2_cert =
-                    EigenDACertV2::decode(&mut &value[3..]).map_err(Self::Error::InvalidRlpCert)?;
+            EigenDAVersionedCert::V1_VERSION_BYTE => {
+                let v1_cert = BlobInfoV1::decode(&mut &value[3..])
+                    .map_err(AltDACommitmentParseError::InvalidRlpCert)?;
+                EigenDAVersionedCert::V1(v1_cert)
+            }
+            EigenDAVersionedCert::V2_VERSION_BYTE => {
+                let v2_cert = EigenDACertV2::decode(&mut &value[3..])
+                    .map_err(AltDACommitmentParseError::InvalidRlpCert)?;
                 EigenDAVersionedCert::V2(v2_cert)
             }
-            // V3 cert
-            2 => {
-                let v3_cert =
-                    EigenDACertV3::decode(&mut &value[3..]).map_err(Self::Error::InvalidRlpCert)?;
+            EigenDAVersionedCert::V3_VERSION_BYTE => {
+                let v3_cert = EigenDACertV3::decode(&mut &value[3..])
+                    .map_err(AltDACommitmentParseError::InvalidRlpCert)?;
                 EigenDAVersionedCert::V3(v3_cert)
             }
             _ => {
-                // also filter out non v2 cert since no logics have been implemented
+                // also filter out unknown cert version since no logics have been implemented
                 return Err(AltDACommitmentParseError::UnsupportedCertVersionType(
                     value[2],
                 ));
             }
         };
+        versioned_cert.validate()?;
+        Ok(AltDACommitment {
+            commitment_type,
+            da_layer_byte,
+            versioned_cert,
+        })
+    }
+
+    /// Constructs an AltDACommitment from an already decoded cert. It enforces the same
+    /// commitment type and da layer invariants as [AltDACommitment::try_from_with_da_layer_bytes]
+    /// with the same allowed_da_layer_bytes, such that callers cannot build a commitment which
+    /// could not be parsed back from [AltDACommitment::to_rlp_bytes]. Pass
+    /// [DEFAULT_DA_LAYER_BYTES] for the da layer bytes accepted by the TryFrom<&[u8]> impl
+    pub fn new(
+        commitment_type: u8,
+        da_layer_byte: u8,
+        versioned_cert: EigenDAVersionedCert,
+        allowed_da_layer_bytes: &[u8],
+    ) -> Result<Self, AltDACommitmentParseError> {
+        // da-service commitment
+        if commitment_type != 1 {
+            return Err(AltDACommitmentParseError::UnsupportedCommitmentType);
+        }
+        if !allowed_da_layer_bytes.contains(&da_layer_byte) {
+            return Err(AltDACommitmentParseError::UnsupportedDaLayerType);
+        }
+        versioned_cert.validate()?;
         Ok(AltDACommitment {
             commitment_type,
             da_layer_byte,
             versioned_cert,
         })
     }
-}
 
-impl AltDACommitment {
     /// This function preprare a holder for a key used to fetch field elements for
     /// eigenda encoded payload. The analogous code for eth blob can be found
     /// <https://github.com/op-rs/kona/blob/08064c4f464b016dc98671f2b3ea60223cfa11a9/crates/proof/proof/src/l1/blob_provider.rs#L57C9-L57C70>
@@ -112,15 +247,38 @@ impl AltDACommitment {
     ///
     /// By hashing the entire cert, such problem is avoided entirely
     pub fn digest_template(&self) -> [u8; 80] {
-        let mut field_element_key = [0u8; 80];
-        let digest = self.to_digest();
-        field_element_key[..32].copy_from_slice(digest.as_slice());
-        field_element_key
+        BlobKey::from_commitment(self).0
+    }
+
+    /// Returns the keccak keys of all preimages the client queries for the altda commitment, in
+    /// the order of the derivation: recency, validity, then every field element of the encoded
+    /// payload. The oracle addresses each of them as a global generic preimage key.
+    ///
+    /// There is no kzg proof key: the client never reads a kzg proof from the preimage oracle. The
+    /// blob is checked against the kzg commitment of the cert by the proof computed from the
+    /// fetched field elements at witness generation, see compute_witness_kzg_proofs in
+    /// hokulea-witgen, so a kv store holding the keys above serves every query of the client
+    pub fn preimage_keys(&self) -> Vec<B256> {
+        let mut recency_address = self.digest_template();
+        recency_address[RESERVED_EIGENDA_API_BYTE_INDEX] = RESERVED_EIGENDA_API_BYTE_FOR_RECENCY;
+        let mut validity_address = self.digest_template();
+        validity_address[RESERVED_EIGENDA_API_BYTE_INDEX] = RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY;
+
+        let blob_length_fe = self.get_num_field_element();
+        let mut preimage_keys = Vec::with_capacity(blob_length_fe + 2);
+        preimage_keys.push(keccak256(recency_address));
+        preimage_keys.push(keccak256(validity_address));
+        let blob_key = BlobKey::from_commitment(self);
+        for idx_fe in 0..blob_length_fe as u64 {
+            preimage_keys.push(keccak256(blob_key.with_index(idx_fe)));
+        }
+        preimage_keys
     }
 
     /// get number of field element for a cert
     pub fn get_num_field_element(&self) -> usize {
         match &self.versioned_cert {
+            EigenDAVersionedCert::V1(c) => c.blob_header.data_length as usize,
             EigenDAVersionedCert::V2(c) => {
                 c.blob_inclusion_info
                     .blob_certificate
@@ -141,14 +299,73 @@ impl AltDACommitment {
     /// get reference block number
     pub fn get_rbn(&self) -> u64 {
         match &self.versioned_cert {
+            EigenDAVersionedCert::V1(c) => {
+                c.blob_verification_proof
+                    .batch_metadata
+                    .batch_header
+                    .reference_block_number as u64
+            }
             EigenDAVersionedCert::V2(c) => c.batch_header_v2.reference_block_number as u64,
             EigenDAVersionedCert::V3(c) => c.batch_header_v2.reference_block_number as u64,
         }
     }
 
+    /// get quorum numbers the blob is dispersed to. None for V1 cert, whose quorums are only available
+    /// as blob quorum params
+    pub fn get_quorum_numbers(&self) -> Option<&Bytes> {
+        match &self.versioned_cert {
+            EigenDAVersionedCert::V1(_) => None,
+            EigenDAVersionedCert::V2(c) => Some(
+                &c.blob_inclusion_info
+                    .blob_certificate
+                    .blob_header
+                    .quorum_numbers,
+            ),
+            EigenDAVersionedCert::V3(c) => Some(
+                &c.blob_inclusion_info
+                    .blob_certificate
+                    .blob_header
+                    .quorum_numbers,
+            ),
+        }
+    }
+
+    /// get relay keys of the relays holding the blob. Empty for V1 cert, which predates relays
+    pub fn get_relay_keys(&self) -> &[u32] {
+        match &self.versioned_cert {
+            EigenDAVersionedCert::V1(_) => &[],
+            EigenDAVersionedCert::V2(c) => &c.blob_inclusion_info.blob_certificate.relay_keys,
+            EigenDAVersionedCert::V3(c) => &c.blob_inclusion_info.blob_certificate.relay_keys,
+        }
+    }
+
+    /// get payment header hash of the blob header, used for payment accounting. None for V1 cert,
+    /// which has no payment header
+    pub fn get_payment_header_hash(&self) -> Option<B256> {
+        match &self.versioned_cert {
+            EigenDAVersionedCert::V1(_) => None,
+            EigenDAVersionedCert::V2(c) => Some(B256::from(
+                c.blob_inclusion_info
+                    .blob_certificate
+                    .blob_header
+                    .payment_header_hash,
+            )),
+            EigenDAVersionedCert::V3(c) => Some(B256::from(
+                c.blob_inclusion_info
+                    .blob_certificate
+                    .blob_header
+                    .payment_header_hash,
+            )),
+        }
+    }
+
     /// get kzg commitment g1 point, first U256 is x coordinate, second is y
     pub fn get_kzg_commitment(&self) -> G1Point {
         match &self.versioned_cert {
+            EigenDAVersionedCert::V1(c) => G1Point {
+                x: U256::from_be_slice(&c.blob_header.commitment.x),
+                y: U256::from_be_slice(&c.blob_header.commitment.y),
+            },
             EigenDAVersionedCert::V2(c) => G1Point {
                 x: c.blob_inclusion_info
                     .blob_certificate
@@ -180,6 +397,13 @@ impl AltDACommitment {
         }
     }
 
+    /// Returns true if both commitments reference the same blob, i.e. they carry the same kzg
+    /// commitment. Unlike `==`, the rest of the cert is ignored, so two certs of a re-posted
+    /// blob with different metadata, like the reference block number, are the same blob
+    pub fn same_blob(&self, other: &AltDACommitment) -> bool {
+        self.get_kzg_commitment() == other.get_kzg_commitment()
+    }
+
     /// Convert AltdaCommitment into bytes in the same form downloaded from
     /// Ethereum block. The bytes form is used as the key to send http query
     /// to the eigenda proxy
@@ -187,18 +411,12 @@ impl AltDACommitment {
         let mut bytes = Vec::new();
         bytes.push(self.commitment_type.to_be());
         bytes.push(self.da_layer_byte.to_be());
+        bytes.push(self.versioned_cert.version_byte());
         let mut cert_rlp_bytes = Vec::<u8>::new();
         match &self.versioned_cert {
-            EigenDAVersionedCert::V2(c) => {
-                // V2 cert has version byte 1
-                bytes.push(1);
-                c.encode(&mut cert_rlp_bytes);
-            }
-            EigenDAVersionedCert::V3(c) => {
-                // V3 cert has version byte 2
-                bytes.push(2);
-                c.encode(&mut cert_rlp_bytes);
-            }
+            EigenDAVersionedCert::V1(c) => c.encode(&mut cert_rlp_bytes),
+            EigenDAVersionedCert::V2(c) => c.encode(&mut cert_rlp_bytes),
+            EigenDAVersionedCert::V3(c) => c.encode(&mut cert_rlp_bytes),
         }
         bytes.extend_from_slice(&cert_rlp_bytes);
         bytes
@@ -210,9 +428,16 @@ impl AltDACommitment {
         keccak256(&rlp_bytes)
     }
 
+    /// Returns the first [SHORT_DIGEST_HEX_LEN] hex characters of the digest, which tell certs
+    /// apart in logs and panic messages without printing the full digest
+    pub fn digest_hex_short(&self) -> String {
+        short_digest_hex(&self.to_digest())
+    }
+
     /// Get Cert Version string
     pub fn cert_version_str(&self) -> &'static str {
         match self.versioned_cert {
+            EigenDAVersionedCert::V1(_) => "V1",
             EigenDAVersionedCert::V2(_) => "V2",
             EigenDAVersionedCert::V3(_) => "V3",
         }
@@ -222,21 +447,155 @@ impl AltDACommitment {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::v3_altda_commitment;
+    use crate::test_utils::{v3_altda_commitment, v3_altda_commitment_calldata};
+    use crate::v1_cert::{
+        BatchHeaderV1, BatchMetadataV1, BlobHeaderV1, BlobQuorumParamV1, BlobVerificationProofV1,
+        G1CommitmentV1,
+    };
     use alloc::vec;
-    use alloy_primitives::Bytes;
+
+    fn v1_altda_commitment() -> AltDACommitment {
+        let blob_info = BlobInfoV1 {
+            blob_header: BlobHeaderV1 {
+                commitment: G1CommitmentV1 {
+                    x: vec![1u8; 32].into(),
+                    y: vec![2u8; 32].into(),
+                },
+                data_length: 16,
+                blob_quorum_params: vec![
+                    BlobQuorumParamV1 {
+                        quorum_number: 0,
+                        adversary_threshold_percentage: 33,
+                        confirmation_threshold_percentage: 55,
+                        chunk_length: 1,
+                    },
+                    BlobQuorumParamV1 {
+                        quorum_number: 1,
+                        adversary_threshold_percentage: 33,
+                        confirmation_threshold_percentage: 55,
+                        chunk_length: 1,
+                    },
+                ],
+            },
+            blob_verification_proof: BlobVerificationProofV1 {
+                batch_id: 7,
+                blob_index: 3,
+                batch_metadata: BatchMetadataV1 {
+                    batch_header: BatchHeaderV1 {
+                        batch_root: vec![3u8; 32].into(),
+                        quorum_numbers: vec![0, 1].into(),
+                        quorum_signed_percentages: vec![100, 98].into(),
+                        reference_block_number: 1234,
+                    },
+                    signatory_record_hash: vec![4u8; 32].into(),
+                    fee: vec![0].into(),
+                    confirmation_block_number: 1300,
+                    batch_header_hash: vec![5u8; 32].into(),
+                },
+                inclusion_proof: vec![6u8; 64].into(),
+                quorum_indexes: vec![0, 1].into(),
+            },
+        };
+        AltDACommitment {
+            commitment_type: 1,
+            da_layer_byte: 0,
+            versioned_cert: EigenDAVersionedCert::V1(blob_info),
+        }
+    }
+
+    #[test]
+    fn test_v1_altda_commitment_roundtrip() {
+        let altda_commitment = v1_altda_commitment();
+        let calldata = altda_commitment.to_rlp_bytes();
+        assert_eq!(calldata[..3], [1, 0, 0]);
+
+        let parsed: AltDACommitment = calldata[..].try_into().unwrap();
+        assert_eq!(parsed, altda_commitment);
+        assert_eq!(parsed.to_rlp_bytes(), calldata);
+        assert_eq!(parsed.to_digest(), keccak256(&calldata));
+        assert_eq!(parsed.cert_version_str(), "V1");
+        assert_eq!(parsed.get_num_field_element(), 16);
+        assert_eq!(parsed.get_rbn(), 1234);
+        assert_eq!(
+            parsed.get_kzg_commitment(),
+            G1Point {
+                x: U256::from_be_bytes([1u8; 32]),
+                y: U256::from_be_bytes([2u8; 32]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_new_altda_commitment() {
+        let altda_commitment = v1_altda_commitment();
+        let versioned_cert = altda_commitment.versioned_cert.clone();
+
+        assert_eq!(
+            AltDACommitment::new(1, 0, versioned_cert.clone(), DEFAULT_DA_LAYER_BYTES),
+            Ok(altda_commitment.clone())
+        );
+        // keccak commitment
+        assert_eq!(
+            AltDACommitment::new(0, 0, versioned_cert.clone(), DEFAULT_DA_LAYER_BYTES),
+            Err(AltDACommitmentParseError::UnsupportedCommitmentType)
+        );
+        // not eigenda
+        assert_eq!(
+            AltDACommitment::new(1, 1, versioned_cert.clone(), DEFAULT_DA_LAYER_BYTES),
+            Err(AltDACommitmentParseError::UnsupportedDaLayerType)
+        );
+        // accepted like try_from_with_da_layer_bytes does with the same allow-list
+        let allowed = AltDACommitment::new(1, 1, versioned_cert, &[0, 1]).unwrap();
+        assert_eq!(allowed.da_layer_byte, 1);
+        assert_eq!(
+            AltDACommitment::try_from_with_da_layer_bytes(&allowed.to_rlp_bytes(), &[0, 1]),
+            Ok(allowed)
+        );
+    }
+
+    #[test]
+    fn test_same_blob() {
+        let altda_commitment = v1_altda_commitment();
+        let mut reposted = altda_commitment.clone();
+        if let EigenDAVersionedCert::V1(c) = &mut reposted.versioned_cert {
+            c.blob_verification_proof
+                .batch_metadata
+                .batch_header
+                .reference_block_number += 10;
+        }
+        assert!(altda_commitment.same_blob(&reposted));
+        assert_ne!(altda_commitment, reposted);
+
+        let mut other_blob = altda_commitment.clone();
+        if let EigenDAVersionedCert::V1(c) = &mut other_blob.versioned_cert {
+            c.blob_header.commitment.x = vec![9u8; 32].into();
+        }
+        assert!(!altda_commitment.same_blob(&other_blob));
+    }
 
     #[test]
     fn test_try_into_altda_commitment_and_to_rlp_bytes_ok() {
-        let calldata: Bytes = alloy_primitives::hex::decode("0x010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001").unwrap().into();
+        let calldata = v3_altda_commitment_calldata();
         let altda_commitment: AltDACommitment = calldata[..].try_into().unwrap();
         let calldata_serialized = altda_commitment.to_rlp_bytes();
         assert_eq!(calldata, calldata_serialized);
     }
 
+    #[test]
+    fn test_get_quorum_numbers() {
+        let altda_commitment = v3_altda_commitment();
+        assert_eq!(
+            altda_commitment.get_quorum_numbers(),
+            Some(&Bytes::from(vec![0u8, 1]))
+        );
+
+        assert_eq!(v1_altda_commitment().get_quorum_numbers(), None);
+    }
+
     #[test]
     fn test_try_into_altda_commitment() {
-        let calldata: Bytes = alloy_primitives::hex::decode("0x010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001").unwrap().into();
-        let altda_commitment: AltDACommitment = calldata[..].try_into().unwrap();
+        let altda_commitment = v3_altda_commitment();
 
         struct Case {
             input: Bytes,
@@ -286,4 +645,162 @@ mod tests {
             assert_eq!(result, case.result);
         }
     }
+
+    #[test]
+    fn test_version_byte() {
+        assert_eq!(
+            v3_altda_commitment().versioned_cert.version_byte(),
+            EigenDAVersionedCert::V3_VERSION_BYTE
+        );
+
+        for altda_commitment in [v1_altda_commitment(), v3_altda_commitment()] {
+            let calldata = altda_commitment.to_rlp_bytes();
+            // the version byte is the one consumed when parsing the altda commitment
+            assert_eq!(calldata[2], altda_commitment.versioned_cert.version_byte());
+            let parsed: AltDACommitment = calldata[..].try_into().unwrap();
+            assert_eq!(
+                parsed.versioned_cert.version_byte(),
+                altda_commitment.versioned_cert.version_byte()
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_from_with_da_layer_bytes() {
+        let mut altda_commitment = v1_altda_commitment();
+        altda_commitment.da_layer_byte = 1;
+        let calldata = altda_commitment.to_rlp_bytes();
+
+        // the default allow-list only accepts eigenda
+        assert_eq!(
+            AltDACommitment::try_from(&calldata[..]),
+            Err(AltDACommitmentParseError::UnsupportedDaLayerType)
+        );
+
+        let parsed = AltDACommitment::try_from_with_da_layer_bytes(&calldata, &[0, 1]).unwrap();
+        assert_eq!(parsed.da_layer_byte, 1);
+        assert_eq!(parsed, altda_commitment);
+        assert_eq!(parsed.to_rlp_bytes(), calldata);
+
+        let eigenda_calldata = v1_altda_commitment().to_rlp_bytes();
+        assert_eq!(
+            AltDACommitment::try_from_with_da_layer_bytes(&eigenda_calldata, &[0, 1]),
+            Ok(v1_altda_commitment())
+        );
+
+        assert_eq!(
+            AltDACommitment::try_from_with_da_layer_bytes(&calldata, &[0, 2]),
+            Err(AltDACommitmentParseError::UnsupportedDaLayerType)
+        );
+    }
+
+    #[test]
+    fn test_try_into_altda_commitment_malformed_g2_point() {
+        let mut altda_commitment = v3_altda_commitment();
+        let EigenDAVersionedCert::V3(c) = &mut altda_commitment.versioned_cert else {
+            panic!("expected a v3 cert");
+        };
+        c.blob_inclusion_info
+            .blob_certificate
+            .blob_header
+            .commitment
+            .length_proof
+            .y
+            .truncate(1);
+        let calldata = altda_commitment.to_rlp_bytes();
+
+        assert_eq!(
+            AltDACommitment::try_from(&calldata[..]),
+            Err(AltDACommitmentParseError::MalformedG2Point { x_len: 2, y_len: 1 })
+        );
+        assert_eq!(
+            AltDACommitment::new(
+                1,
+                0,
+                altda_commitment.versioned_cert,
+                DEFAULT_DA_LAYER_BYTES
+            ),
+            Err(AltDACommitmentParseError::MalformedG2Point { x_len: 2, y_len: 1 })
+        );
+    }
+
+    #[test]
+    fn test_get_relay_keys() {
+        let altda_commitment = v3_altda_commitment();
+        let EigenDAVersionedCert::V3(c) = &altda_commitment.versioned_cert else {
+            panic!("fixture is a V3 cert");
+        };
+        assert_eq!(
+            altda_commitment.get_relay_keys(),
+            &c.blob_inclusion_info.blob_certificate.relay_keys[..]
+        );
+        assert_eq!(altda_commitment.get_relay_keys(), &[0]);
+
+        assert!(v1_altda_commitment().get_relay_keys().is_empty());
+    }
+
+    #[test]
+    fn test_get_payment_header_hash() {
+        let altda_commitment = v3_altda_commitment();
+        let expected = alloy_primitives::b256!(
+            "731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037"
+        );
+        assert_eq!(altda_commitment.get_payment_header_hash(), Some(expected));
+
+        assert_eq!(v1_altda_commitment().get_payment_header_hash(), None);
+    }
+
+    #[test]
+    fn test_digest_hex_short() {
+        let altda_commitment = v3_altda_commitment();
+
+        let short = altda_commitment.digest_hex_short();
+        assert_eq!(short.len(), SHORT_DIGEST_HEX_LEN);
+        let digest = alloy_primitives::hex::encode(altda_commitment.to_digest());
+        assert!(digest.starts_with(&short));
+        assert_eq!(short, short_digest_hex(&altda_commitment.to_digest()));
+    }
+
+    #[test]
+    fn test_blob_key_layout() {
+        let altda_commitment = v1_altda_commitment();
+        let blob_key = BlobKey::from_commitment(&altda_commitment);
+        assert_eq!(blob_key.with_index(0), altda_commitment.digest_template());
+
+        let key = blob_key.with_index(0x0102030405060708);
+        assert_eq!(&key[..32], altda_commitment.to_digest().as_slice());
+        assert_eq!(key[32..72], [0u8; 40]);
+        assert_eq!(key[72..], [1, 2, 3, 4, 5, 6, 7, 8]);
+        // the template is left untouched
+        assert_eq!(blob_key.with_index(1)[72..], [0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_malformed_v1_commitment() {
+        let mut altda_commitment = v1_altda_commitment();
+        if let EigenDAVersionedCert::V1(c) = &mut altda_commitment.versioned_cert {
+            c.blob_header.commitment.x = vec![1u8; 31].into();
+        }
+        let calldata = altda_commitment.to_rlp_bytes();
+        assert_eq!(
+            AltDACommitment::try_from(&calldata[..]),
+            Err(AltDACommitmentParseError::MalformedV1Commitment {
+                x_len: 31,
+                y_len: 32
+            })
+        );
+
+        // a coordinate longer than 32 bytes cannot be converted to U256 either
+        if let EigenDAVersionedCert::V1(c) = &mut altda_commitment.versioned_cert {
+            c.blob_header.commitment.x = vec![1u8; 32].into();
+            c.blob_header.commitment.y = vec![2u8; 33].into();
+        }
+        assert_eq!(
+            altda_commitment.versioned_cert.validate(),
+            Err(AltDACommitmentParseError::MalformedV1Commitment {
+                x_len: 32,
+                y_len: 33
+            })
+        );
+    }
 }
@@ -1,7 +1,7 @@
 #![no_std]
 use alloy_primitives::Bytes;
 use alloy_primitives::{FixedBytes, U256};
-use alloy_rlp::{RlpDecodable, RlpEncodable};
+use alloy_rlp::{Decodable, RlpDecodable, RlpEncodable};
 use canoe_bindings as sol_struct;
 use serde::{Deserialize, Serialize};
 
@@ -9,9 +9,18 @@ extern crate alloc;
 use alloc::vec::Vec;
 
 pub mod altda_commitment;
+pub mod v1_cert;
 pub mod v2_cert;
 pub mod v3_cert;
-pub use altda_commitment::{AltDACommitment, AltDACommitmentParseError, EigenDAVersionedCert};
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub use altda_commitment::{
+    short_digest_hex, AltDACommitment, AltDACommitmentParseError, BlobKey, EigenDAVersionedCert,
+    DEFAULT_DA_LAYER_BYTES, RESERVED_EIGENDA_API_BYTE_FOR_RECENCY,
+    RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY, RESERVED_EIGENDA_API_BYTE_INDEX, SHORT_DIGEST_HEX_LEN,
+};
+pub use v1_cert::BlobInfoV1;
 pub use v2_cert::EigenDACertV2;
 pub use v3_cert::EigenDACertV3;
 
@@ -30,6 +39,24 @@ impl G1Point {
             Y: self.y,
         }
     }
+
+    /// Returns the big endian bytes of the x and y coordinates
+    pub fn to_be_bytes(&self) -> ([u8; 32], [u8; 32]) {
+        (self.x.to_be_bytes(), self.y.to_be_bytes())
+    }
+
+    /// Converts the point into an ark-bn254 affine point, with the coordinates reduced modulo the
+    /// base field. Panics if the point is not on the curve
+    #[cfg(feature = "ark")]
+    pub fn to_g1_affine(&self) -> ark_bn254::G1Affine {
+        use ark_ff::PrimeField;
+
+        let (x, y) = self.to_be_bytes();
+        ark_bn254::G1Affine::new(
+            ark_bn254::Fq::from_be_bytes_mod_order(&x),
+            ark_bn254::Fq::from_be_bytes_mod_order(&y),
+        )
+    }
 }
 
 // G2Point represents a point on the BN254 G2 curve
@@ -40,6 +67,20 @@ pub struct G2Point {
 }
 
 impl G2Point {
+    /// validate ensures both coordinates hold exactly two elements, which [G2Point::to_sol]
+    /// relies on. The rlp encoding does not constrain the length, so a malformed cert can hold
+    /// any number of elements
+    pub fn validate(&self) -> Result<(), AltDACommitmentParseError> {
+        if self.x.len() != 2 || self.y.len() != 2 {
+            return Err(AltDACommitmentParseError::MalformedG2Point {
+                x_len: self.x.len(),
+                y_len: self.y.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// to_sol panics unless the point passed [G2Point::validate]
     pub fn to_sol(&self) -> sol_struct::G2Point {
         let mut x = [U256::default(); 2];
         x[0] = self.x[0];
@@ -157,6 +198,13 @@ impl BatchHeaderV2 {
 }
 
 impl BlobInclusionInfo {
+    /// validate checks the G2 points of the blob commitment, see [G2Point::validate]
+    pub fn validate(&self) -> Result<(), AltDACommitmentParseError> {
+        let commitment = &self.blob_certificate.blob_header.commitment;
+        commitment.length_commitment.validate()?;
+        commitment.length_proof.validate()
+    }
+
     pub fn to_sol(&self) -> sol_struct::BlobInclusionInfo {
         sol_struct::BlobInclusionInfo {
             blobIndex: self.blob_index,
@@ -167,6 +215,11 @@ impl BlobInclusionInfo {
 }
 
 impl NonSignerStakesAndSignature {
+    /// validate checks the G2 point of the aggregated public key, see [G2Point::validate]
+    pub fn validate(&self) -> Result<(), AltDACommitmentParseError> {
+        self.apk_g2.validate()
+    }
+
     pub fn to_sol(&self) -> sol_struct::NonSignerStakesAndSignature {
         sol_struct::NonSignerStakesAndSignature {
             nonSignerQuorumBitmapIndices: self.non_signer_quorum_bitmap_indices.clone(),
@@ -184,3 +237,185 @@ impl NonSignerStakesAndSignature {
         }
     }
 }
+
+/// Decodes an RLP encoded [BatchHeaderV2] into its solidity type. The bytes can come from an
+/// untrusted source, so a decoding failure is returned rather than panicking
+pub fn parse_batch_header(mut bytes: &[u8]) -> Result<sol_struct::BatchHeaderV2, alloy_rlp::Error> {
+    Ok(BatchHeaderV2::decode(&mut bytes)?.to_sol())
+}
+
+/// Decodes an RLP encoded [NonSignerStakesAndSignature] into its solidity type
+pub fn parse_non_signer(
+    mut bytes: &[u8],
+) -> Result<sol_struct::NonSignerStakesAndSignature, alloy_rlp::Error> {
+    let non_signer = NonSignerStakesAndSignature::decode(&mut bytes)?;
+    non_signer
+        .validate()
+        .map_err(|_| alloy_rlp::Error::Custom("malformed g2 point"))?;
+    Ok(non_signer.to_sol())
+}
+
+/// Decodes an RLP encoded [BlobInclusionInfo] into its solidity type
+pub fn parse_blob_inclusion(
+    mut bytes: &[u8],
+) -> Result<sol_struct::BlobInclusionInfo, alloy_rlp::Error> {
+    let blob_inclusion = BlobInclusionInfo::decode(&mut bytes)?;
+    blob_inclusion
+        .validate()
+        .map_err(|_| alloy_rlp::Error::Custom("malformed g2 point"))?;
+    Ok(blob_inclusion.to_sol())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloy_rlp::Encodable;
+
+    fn g1_point() -> G1Point {
+        G1Point {
+            x: U256::from(1),
+            y: U256::from(2),
+        }
+    }
+
+    fn g2_point() -> G2Point {
+        G2Point {
+            x: vec![U256::from(3), U256::from(4)],
+            y: vec![U256::from(5), U256::from(6)],
+        }
+    }
+
+    fn rlp_bytes<T: Encodable>(value: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        value.encode(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn test_g1_point_to_be_bytes() {
+        let (x, y) = g1_point().to_be_bytes();
+        let mut expected_y = [0u8; 32];
+        expected_y[31] = 2;
+        assert_eq!(y, expected_y);
+        assert_eq!(
+            G1Point {
+                x: U256::from_be_bytes(x),
+                y: U256::from_be_bytes(y),
+            },
+            g1_point()
+        );
+    }
+
+    #[cfg(feature = "ark")]
+    #[test]
+    fn test_g1_point_to_g1_affine() {
+        use ark_ff::{BigInteger, PrimeField};
+
+        // (1, 2) is the generator of the BN254 G1 curve
+        let point = g1_point();
+        let affine = point.to_g1_affine();
+        let (x, y) = point.to_be_bytes();
+        assert_eq!(affine.x.into_bigint().to_bytes_be(), x);
+        assert_eq!(affine.y.into_bigint().to_bytes_be(), y);
+    }
+
+    #[test]
+    fn test_parse_batch_header() {
+        let batch_header = BatchHeaderV2 {
+            batch_root: [7; 32],
+            reference_block_number: 100,
+        };
+        let bytes = rlp_bytes(&batch_header);
+
+        let parsed = parse_batch_header(&bytes).unwrap();
+        assert_eq!(parsed.batchRoot, FixedBytes::<32>([7; 32]));
+        assert_eq!(parsed.referenceBlockNumber, 100);
+
+        assert!(parse_batch_header(&bytes[..bytes.len() - 1]).is_err());
+        assert!(parse_batch_header(&[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_non_signer() {
+        let non_signer = NonSignerStakesAndSignature {
+            non_signer_quorum_bitmap_indices: vec![1],
+            non_signer_pubkeys: vec![g1_point()],
+            quorum_apks: vec![g1_point()],
+            apk_g2: g2_point(),
+            sigma: g1_point(),
+            quorum_apk_indices: vec![2],
+            total_stake_indices: vec![3],
+            non_signer_stake_indices: vec![vec![4]],
+        };
+        let bytes = rlp_bytes(&non_signer);
+
+        let parsed = parse_non_signer(&bytes).unwrap();
+        assert_eq!(parsed.nonSignerQuorumBitmapIndices, vec![1]);
+        assert_eq!(parsed.nonSignerStakeIndices, vec![vec![4]]);
+
+        assert!(parse_non_signer(&bytes[..bytes.len() - 1]).is_err());
+        assert!(parse_non_signer(&[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_blob_inclusion() {
+        let blob_inclusion = BlobInclusionInfo {
+            blob_certificate: BlobCertificate {
+                blob_header: BlobHeaderV2 {
+                    version: 0,
+                    quorum_numbers: Bytes::from(vec![0, 1]),
+                    commitment: BlobCommitment {
+                        commitment: g1_point(),
+                        length_commitment: g2_point(),
+                        length_proof: g2_point(),
+                        length: 16,
+                    },
+                    payment_header_hash: [8; 32],
+                },
+                signature: Bytes::from(vec![9; 65]),
+                relay_keys: vec![0],
+            },
+            blob_index: 5,
+            inclusion_proof: Bytes::from(vec![10; 32]),
+        };
+        let bytes = rlp_bytes(&blob_inclusion);
+
+        let parsed = parse_blob_inclusion(&bytes).unwrap();
+        assert_eq!(parsed.blobIndex, 5);
+        assert_eq!(parsed.blobCertificate.blobHeader.commitment.length, 16);
+
+        assert!(parse_blob_inclusion(&bytes[..bytes.len() - 1]).is_err());
+        assert!(parse_blob_inclusion(&[]).is_err());
+    }
+
+    #[test]
+    fn test_malformed_g2_point() {
+        assert_eq!(g2_point().validate(), Ok(()));
+
+        let malformed = G2Point {
+            x: vec![U256::from(3)],
+            y: vec![U256::from(5), U256::from(6)],
+        };
+        assert_eq!(
+            malformed.validate(),
+            Err(AltDACommitmentParseError::MalformedG2Point { x_len: 1, y_len: 2 })
+        );
+
+        // a malformed point decoded from untrusted bytes is an error rather than a panic
+        let non_signer = NonSignerStakesAndSignature {
+            non_signer_quorum_bitmap_indices: vec![],
+            non_signer_pubkeys: vec![],
+            quorum_apks: vec![],
+            apk_g2: malformed,
+            sigma: g1_point(),
+            quorum_apk_indices: vec![],
+            total_stake_indices: vec![],
+            non_signer_stake_indices: vec![],
+        };
+        assert_eq!(
+            parse_non_signer(&rlp_bytes(&non_signer)),
+            Err(alloy_rlp::Error::Custom("malformed g2 point"))
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use alloy_primitives::Bytes;
+use alloy_primitives::{keccak256, B256};
+use alloy_rlp::{Encodable, RlpDecodable, RlpEncodable};
+use serde::{Deserialize, Serialize};
+
+use crate::AltDACommitmentParseError;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+/// G1Commitment of a V1 blob. Coordinates are kept as raw big endian bytes, which is how
+/// the disperser protobuf, hence the RLP encoded cert, carries them
+#[derive(Debug, Clone, RlpEncodable, RlpDecodable, PartialEq, Serialize, Deserialize)]
+pub struct G1CommitmentV1 {
+    pub x: Bytes,
+    pub y: Bytes,
+}
+
+impl G1CommitmentV1 {
+    /// validate checks both coordinates are 32 bytes, such that they convert to a [crate::G1Point]
+    pub fn validate(&self) -> Result<(), AltDACommitmentParseError> {
+        if self.x.len() != 32 || self.y.len() != 32 {
+            return Err(AltDACommitmentParseError::MalformedV1Commitment {
+                x_len: self.x.len(),
+                y_len: self.y.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Security parameters of a quorum a V1 blob is dispersed to
+#[derive(Debug, Clone, RlpEncodable, RlpDecodable, PartialEq, Serialize, Deserialize)]
+pub struct BlobQuorumParamV1 {
+    pub quorum_number: u32,
+    pub adversary_threshold_percentage: u32,
+    pub confirmation_threshold_percentage: u32,
+    pub chunk_length: u32,
+}
+
+/// BlobHeaderV1 is the blob header of the EigenDA V1 protocol
+#[derive(Debug, Clone, RlpEncodable, RlpDecodable, PartialEq, Serialize, Deserialize)]
+pub struct BlobHeaderV1 {
+    pub commitment: G1CommitmentV1,
+    /// length of the blob in number of field elements
+    pub data_length: u32,
+    pub blob_quorum_params: Vec<BlobQuorumParamV1>,
+}
+
+/// BatchHeaderV1 is the batch header of the EigenDA V1 protocol
+#[derive(Debug, Clone, RlpEncodable, RlpDecodable, PartialEq, Serialize, Deserialize)]
+pub struct BatchHeaderV1 {
+    pub batch_root: Bytes,
+    pub quorum_numbers: Bytes,
+    pub quorum_signed_percentages: Bytes,
+    pub reference_block_number: u32,
+}
+
+/// BatchMetadataV1 contains the batch header and the confirmation of the batch on L1
+#[derive(Debug, Clone, RlpEncodable, RlpDecodable, PartialEq, Serialize, Deserialize)]
+pub struct BatchMetadataV1 {
+    pub batch_header: BatchHeaderV1,
+    pub signatory_record_hash: Bytes,
+    pub fee: Bytes,
+    pub confirmation_block_number: u32,
+    pub batch_header_hash: Bytes,
+}
+
+/// BlobVerificationProofV1 contains the inclusion proof of a blob within a confirmed batch
+#[derive(Debug, Clone, RlpEncodable, RlpDecodable, PartialEq, Serialize, Deserialize)]
+pub struct BlobVerificationProofV1 {
+    pub batch_id: u32,
+    pub blob_index: u32,
+    pub batch_metadata: BatchMetadataV1,
+    pub inclusion_proof: Bytes,
+    pub quorum_indexes: Bytes,
+}
+
+/// EigenDA V1 cert, known as BlobInfo in the V1 disperser API.
+/// Only parsing and digesting are supported, there is no validity proof for V1 certs
+#[derive(Debug, Clone, RlpEncodable, RlpDecodable, PartialEq, Serialize, Deserialize)]
+pub struct BlobInfoV1 {
+    pub blob_header: BlobHeaderV1,
+    pub blob_verification_proof: BlobVerificationProofV1,
+}
+
+impl BlobInfoV1 {
+    pub fn to_digest(&self) -> B256 {
+        let mut cert_rlp_bytes = Vec::<u8>::new();
+        // rlp encode of cert
+        self.encode(&mut cert_rlp_bytes);
+        keccak256(&cert_rlp_bytes)
+    }
+}
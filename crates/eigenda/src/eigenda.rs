@@ -5,10 +5,11 @@ use crate::{eigenda_preimage::EigenDAPreimageSource, HokuleaErrorKind, ALTDA_DER
 use kona_derive::PipelineErrorKind;
 
 use crate::eigenda_data::EncodedPayload;
-use alloc::vec::Vec;
-use alloc::{boxed::Box, fmt::Debug};
+use alloc::{boxed::Box, fmt::Debug, sync::Arc};
+use alloc::{vec, vec::Vec};
 use alloy_primitives::{Address, Bytes};
 use async_trait::async_trait;
+use eigenda_cert::AltDACommitment;
 use kona_derive::{
     BlobProvider, ChainProvider, DataAvailabilityProvider, EthereumDataSource, PipelineError,
     PipelineResult,
@@ -18,10 +19,22 @@ use tracing::warn;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum EigenDAOrCalldata {
-    EigenDA(EncodedPayload),
+    /// an encoded payload, along with the altda commitment it was retrieved for
+    EigenDA(AltDACommitment, EncodedPayload),
     Calldata(Bytes),
 }
 
+/// A callback invoked with an altda commitment and the reason it was discarded from the
+/// derivation, for instance to surface discarded certs to monitoring
+#[derive(Clone)]
+pub struct DiscardCallback(pub Arc<dyn Fn(&AltDACommitment, &str) + Send + Sync>);
+
+impl Debug for DiscardCallback {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("DiscardCallback")
+    }
+}
+
 /// A factory for creating an EigenDADataSource iterator. The internal behavior is that
 /// data is fetched from eigenda or stays as it is if Eth calldata is desired. Those data
 /// are cached. When next() is called it just returns the next cached encoded payload.
@@ -42,6 +55,31 @@ where
     pub open: bool,
     /// eigenda encoded payload or ethereum calldata that does not use eigenda in failover mode
     pub data: Vec<EigenDAOrCalldata>,
+    /// batcher addresses whose l1 data is loaded in addition to the batcher address of the system
+    /// config, for rollups rotating batchers or posting from several of them. Empty by default,
+    /// such that only the batcher of the system config is accepted
+    pub batcher_addresses: Vec<Address>,
+    /// if set, an altda commitment whose preimage keeps failing with a temporary error, for
+    /// instance because eigenda proxy is down, is discarded at the given number of consecutive
+    /// attempts, counting the first one. Meant for a degraded mode relying on a later honest
+    /// proposer to re-include the data. None by default, which retries forever.
+    ///
+    /// WARNING: it must NEVER be enabled in a fault proof or zkVM client. Whether data is discarded
+    /// then depends on the availability of eigenda proxy during the run rather than on l1 and
+    /// eigenda state, such that two runs over the same l1 data can derive different l2 chains, and
+    /// an honest proof can be made to fail. It is only compiled with the degraded-mode feature,
+    /// which must only be enabled by hosts or witness generation running a derivation off chain
+    #[cfg(feature = "degraded-mode")]
+    pub discard_after_temporary_errors: Option<u64>,
+    /// if set, invoked on each discarded altda commitment with the reason of the discard. The
+    /// discard is logged either way. l1 data which cannot be parsed as an altda commitment is only
+    /// logged. None by default
+    pub on_discard: Option<DiscardCallback>,
+    /// number of consecutive attempts to load l1 data which failed with a temporary error
+    #[cfg(feature = "degraded-mode")]
+    temporary_error_count: u64,
+    /// number of times the source pulled l1 data, a retry after a temporary error pulls again
+    reload_count: u64,
 }
 
 impl<C, B, A> EigenDADataSource<C, B, A>
@@ -60,8 +98,46 @@ where
             eigenda_source,
             open: false,
             data: Vec::new(),
+            batcher_addresses: Vec::new(),
+            #[cfg(feature = "degraded-mode")]
+            discard_after_temporary_errors: None,
+            on_discard: None,
+            #[cfg(feature = "degraded-mode")]
+            temporary_error_count: 0,
+            reload_count: 0,
         }
     }
+
+    /// Accepts the l1 data of batcher_addresses besides the batcher of the system config, see
+    /// [EigenDADataSource::batcher_addresses]
+    pub fn with_batcher_addresses(mut self, batcher_addresses: Vec<Address>) -> Self {
+        self.batcher_addresses = batcher_addresses;
+        self
+    }
+
+    /// Discards an altda commitment after max_attempts consecutive temporary errors, see
+    /// [EigenDADataSource::discard_after_temporary_errors]. Never use it in a fault proof or zkVM
+    /// client
+    #[cfg(feature = "degraded-mode")]
+    pub fn with_discard_after_temporary_errors(mut self, max_attempts: u64) -> Self {
+        self.discard_after_temporary_errors = Some(max_attempts);
+        self
+    }
+
+    /// Invokes on_discard on each discarded altda commitment, see [EigenDADataSource::on_discard]
+    pub fn with_on_discard(
+        mut self,
+        on_discard: impl Fn(&AltDACommitment, &str) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_discard = Some(DiscardCallback(Arc::new(on_discard)));
+        self
+    }
+
+    /// Returns the number of times the source pulled l1 data instead of serving the loaded
+    /// data. A count growing much faster than the number of l1 blocks points to a retry storm
+    pub const fn reload_count(&self) -> u64 {
+        self.reload_count
+    }
 }
 
 #[async_trait]
@@ -86,14 +162,24 @@ where
         self.load_eigenda_or_calldata(block_ref, batcher_addr)
             .await?;
 
-        match self.next_data()? {
-            EigenDAOrCalldata::Calldata(c) => return Ok(c),
-            EigenDAOrCalldata::EigenDA(encoded_payload) => {
-                match encoded_payload.decode() {
-                    Ok(c) => return Ok(c),
-                    // if encodoed payload cannot be decoded, try next data, since load_encoded_payload
-                    // has openned the stage already, it won't load the l1 block again
-                    Err(_) => self.next(block_ref, batcher_addr).await,
+        // if an encoded payload cannot be decoded, try the next data. Since load_eigenda_or_calldata
+        // has opened the stage already, it won't load the l1 block again. Once all data is consumed,
+        // next_data returns PipelineError::Eof
+        loop {
+            match self.next_data()? {
+                EigenDAOrCalldata::Calldata(c) => return Ok(c),
+                EigenDAOrCalldata::EigenDA(altda_commitment, encoded_payload) => {
+                    match encoded_payload.decode() {
+                        Ok(c) => return Ok(c),
+                        Err(e) => {
+                            warn!(
+                                "skipping undecodable payload of cert {} at l1 block {}: {}",
+                                altda_commitment.digest_hex_short(),
+                                block_ref.number,
+                                e
+                            );
+                        }
+                    }
                 }
             }
         }
@@ -124,23 +210,38 @@ where
         if self.open {
             return Ok(());
         }
+        self.reload_count += 1;
+
+        let mut batcher_addrs = vec![batcher_addr];
+        for addr in &self.batcher_addresses {
+            if !batcher_addrs.contains(addr) {
+                batcher_addrs.push(*addr);
+            }
+        }
 
         let mut calldata_list: Vec<Bytes> = Vec::new();
-        // drain all the ethereum calldata from the l1 block
-        loop {
-            match self.ethereum_source.next(block_ref, batcher_addr).await {
-                Ok(d) => calldata_list.push(d),
-                Err(e) => {
-                    // break out the loop after having all batcher calldata for that block number
-                    // OP has different struct for handling pre and post ecotone. But both returns PipelineError::Eof
-                    // post ecotone https://github.com/op-rs/kona/blob/1133800fcb23c4515ed919407742a22f222d88b1/crates/protocol/derive/src/sources/blobs.rs#L175
-                    // pre ecotone https://github.com/op-rs/kona/blob/1133800fcb23c4515ed919407742a22f222d88b1/crates/protocol/derive/src/sources/calldata.rs#L86
-                    if let PipelineErrorKind::Temporary(PipelineError::Eof) = e {
-                        break;
+        for (i, addr) in batcher_addrs.into_iter().enumerate() {
+            // the ethereum source stays open once drained for a batcher, it has to pull the l1
+            // block again for the next one
+            if i > 0 {
+                self.ethereum_source.clear();
+            }
+            // drain all the ethereum calldata from the l1 block
+            loop {
+                match self.ethereum_source.next(block_ref, addr).await {
+                    Ok(d) => calldata_list.push(d),
+                    Err(e) => {
+                        // break out the loop after having all batcher calldata for that block number
+                        // OP has different struct for handling pre and post ecotone. But both returns PipelineError::Eof
+                        // post ecotone https://github.com/op-rs/kona/blob/1133800fcb23c4515ed919407742a22f222d88b1/crates/protocol/derive/src/sources/blobs.rs#L175
+                        // pre ecotone https://github.com/op-rs/kona/blob/1133800fcb23c4515ed919407742a22f222d88b1/crates/protocol/derive/src/sources/calldata.rs#L86
+                        if let PipelineErrorKind::Temporary(PipelineError::Eof) = e {
+                            break;
+                        }
+                        return Err(e);
                     }
-                    return Err(e);
-                }
-            };
+                };
+            }
         }
 
         // all data returnable to l1 retriever, including both eigenda encoded payload and Derivation version 0
@@ -153,9 +254,26 @@ where
                     Err(e) => match e {
                         HokuleaErrorKind::Discard(e) => {
                             warn!("Hokulea derivation discard {}", e);
+                            self.report_discard(data, &e);
                             continue;
                         }
                         HokuleaErrorKind::Temporary(e) => {
+                            #[cfg(feature = "degraded-mode")]
+                            {
+                                self.temporary_error_count += 1;
+                                if self
+                                    .discard_after_temporary_errors
+                                    .is_some_and(|max| self.temporary_error_count >= max)
+                                {
+                                    warn!(
+                                        "Hokulea derivation discard after {} temporary errors {}",
+                                        self.temporary_error_count, e
+                                    );
+                                    self.temporary_error_count = 0;
+                                    self.report_discard(data, &e);
+                                    continue;
+                                }
+                            }
                             // we need to clear the ethereum source, because when the op driver retries after this error,
                             // load_eigenda_or_calldata needs to pull the ethereum data again. If we don't clear, the ethereum
                             // source would keep state, and not giving the calldata that produces the error.
@@ -168,8 +286,11 @@ where
                             return Err(PipelineError::Provider(e).crit());
                         }
                     },
-                    Ok(encoded_payload) => {
-                        self_contained_data.push(EigenDAOrCalldata::EigenDA(encoded_payload));
+                    Ok((altda_commitment, encoded_payload)) => {
+                        self_contained_data.push(EigenDAOrCalldata::EigenDA(
+                            altda_commitment,
+                            encoded_payload,
+                        ));
                     }
                 }
             } else {
@@ -187,9 +308,42 @@ where
 
         self.data = self_contained_data;
         self.open = true;
+        #[cfg(feature = "degraded-mode")]
+        {
+            self.temporary_error_count = 0;
+        }
         Ok(())
     }
 
+    /// Invokes the on_discard callback, if any, with the altda commitment of the discarded data
+    fn report_discard(&self, data: &Bytes, reason: &str) {
+        if let Some(on_discard) = &self.on_discard {
+            if let Ok(altda_commitment) = AltDACommitment::try_from(&data[1..]) {
+                (on_discard.0)(&altda_commitment, reason);
+            }
+        }
+    }
+
+    /// Derives every item an l1 block contributes, in order, and clears the source afterwards so
+    /// it can be reused for another block. This is meant for replaying a single l1 block outside
+    /// of the derivation pipeline, hence a temporary error is returned as is instead of retried.
+    pub async fn drain_block(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_addr: Address,
+    ) -> PipelineResult<Vec<Bytes>> {
+        let mut items = Vec::new();
+        loop {
+            match self.next(block_ref, batcher_addr).await {
+                Ok(d) => items.push(d),
+                Err(PipelineErrorKind::Temporary(PipelineError::Eof)) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        self.clear();
+        Ok(items)
+    }
+
     #[allow(clippy::result_large_err)]
     fn next_data(&mut self) -> Result<EigenDAOrCalldata, PipelineErrorKind> {
         // if all eigenda encoded payload are processed, send signal to driver to advance
@@ -202,16 +356,22 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::test_utils::{self, TestEigenDAPreimageProvider};
+    use crate::test_utils::{
+        self, valid_encoded_payload_with_altda_commitment, TestEigenDAPreimageProvider,
+    };
 
     use super::*;
-    use alloc::{collections::VecDeque, vec};
-    use alloy_consensus::TxEnvelope;
-    use alloy_rlp::Decodable;
-    use eigenda_cert::AltDACommitment;
+    use alloc::collections::VecDeque;
+    use alloc::string::{String, ToString};
+    use alloy_consensus::{transaction::SignerRecoverable, Signed, TxEip1559, TxEnvelope};
+    use alloy_primitives::{b256, TxKind, U256};
+    use eigenda_cert::test_utils::v3_altda_commitment_calldata;
+    // the callback of a test records the discards behind a lock, which is not in alloc
+    extern crate std;
     use kona_derive::test_utils::{TestBlobProvider, TestChainProvider};
     use kona_derive::{BlobSource, CalldataSource};
     use kona_genesis::{HardForkConfig, RollupConfig};
+    use std::sync::Mutex;
 
     const L1_INBOX_ADDRESS: Address =
         alloy_primitives::address!("0x000faef0a3d9711c3e9bbc4f3e2730dd75167da3");
@@ -224,28 +384,39 @@ mod tests {
         EigenDAPreimageSource::new(preimage_provider)
     }
 
-    // the altda commitmenta and encoded payload corresponds to eip1559 tx
-    // 0x02f904f583aa36a78212f2843b9aca0084b2d05e008301057294000faef0a3d9711c3e9bbc4f3e2730dd75167da380b9048301010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001c001a0445ab87abefec130d63733b3bcafc7ee0c0f8367e61b580be4f0cf0c3d21a03aa02d054c857c76e9dbf47d63d0b70b58200e14e9f9ba2eb47343c3b67faab93a72
-    pub(crate) fn valid_encoded_payload_with_altda_commitment() -> (AltDACommitment, EncodedPayload)
-    {
-        let calldata: Bytes = alloy_primitives::hex::decode("0x010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001").unwrap().into();
-        let altda_commitment = calldata[..].try_into().unwrap();
-        // the encoded payload corresponding to the altda commitment
-        let raw_eigenda_blob = alloy_primitives::hex::decode("00000000009100000000000000000000000000000000000000000000000000000000ab80c99f814a3541886f8f4a65f61b67000000000079011b6501f88f532c00998d4648d239b1ce87da27450caaab705a5c8412149720e6dd229a4b97d25600ca7222a7ae434145a5d1440229000106a45bd00f3e0e33b07a5c23ad927eaa00f98a77e7818ff59e2c3b2c03d5ffaeb6dba4cb08b9fa2d122e8acbe726c4a70009ae086496e0d3ac00d70438c034e1f1314b70c0010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
-        let encoded_payload = EncodedPayload {
-            encoded_payload: raw_eigenda_blob.into(),
-        };
-        (altda_commitment, encoded_payload)
-    }
-
     // eigenda failover to eth calldata only, which uses eip 1559 tx
     // see https://github.com/Layr-Labs/optimism/blob/24baeb1c87879ee1900551aabbb7c154dc058d14/op-service/txmgr/txmgr.go#L342
     // inbox address: 0x000faef0a3d9711c3e9bbc4f3e2730dd75167da3
     // batcher address: 0x15F447c49D9eAC8ecA80ce12c5620278E7F59d2F
     pub(crate) fn valid_eip1559_txs_with_altda_commitment(num: usize) -> Vec<TxEnvelope> {
-        // https://sepolia.etherscan.io/getRawTx?tx=0x9a22ccb0029bc8b0ddd073be1a1d923b7ae2b2ea52100bae0db4424f9107e9c0
-        let raw_tx = alloy_primitives::hex::decode("0x02f904f583aa36a78212f2843b9aca0084b2d05e008301057294000faef0a3d9711c3e9bbc4f3e2730dd75167da380b9048301010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001c001a0445ab87abefec130d63733b3bcafc7ee0c0f8367e61b580be4f0cf0c3d21a03aa02d054c857c76e9dbf47d63d0b70b58200e14e9f9ba2eb47343c3b67faab93a72").unwrap();
-        let eip1559 = TxEnvelope::decode(&mut raw_tx.as_slice()).unwrap();
+        // https://sepolia.etherscan.io/tx/0x9a22ccb0029bc8b0ddd073be1a1d923b7ae2b2ea52100bae0db4424f9107e9c0
+        // its calldata is the derivation version byte followed by the V3 altda commitment fixture
+        let tx = TxEip1559 {
+            chain_id: 11155111,
+            nonce: 4850,
+            gas_limit: 66930,
+            max_fee_per_gas: 3_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: TxKind::Call(L1_INBOX_ADDRESS),
+            value: U256::ZERO,
+            access_list: Default::default(),
+            input: [
+                &[ALTDA_DERIVATION_VERSION][..],
+                &v3_altda_commitment_calldata(),
+            ]
+            .concat()
+            .into(),
+        };
+        let signature = alloy_primitives::Signature::new(
+            U256::from_be_bytes(
+                b256!("0x445ab87abefec130d63733b3bcafc7ee0c0f8367e61b580be4f0cf0c3d21a03a").0,
+            ),
+            U256::from_be_bytes(
+                b256!("0x2d054c857c76e9dbf47d63d0b70b58200e14e9f9ba2eb47343c3b67faab93a72").0,
+            ),
+            true,
+        );
+        let eip1559 = TxEnvelope::Eip1559(Signed::new_unhashed(tx, signature));
         vec![eip1559.clone(); num]
     }
 
@@ -512,6 +683,150 @@ mod tests {
         assert!(!source.data.is_empty());
     }
 
+    // with the policy set, the altda commitment is discarded at the third consecutive temporary error
+    #[cfg(feature = "degraded-mode")]
+    #[tokio::test]
+    async fn test_load_eigenda_or_calldata_discard_after_temporary_errors() {
+        let mut source = default_test_eigenda_data_source().with_discard_after_temporary_errors(3);
+        configure_source_with_valid_eigenda_preimage(&mut source, 1);
+        source.eigenda_source.eigenda_fetcher.should_preimage_err = true;
+
+        for _ in 0..2 {
+            assert!(matches!(
+                source
+                    .load_eigenda_or_calldata(&BlockInfo::default(), BATCHER_ADDRESS)
+                    .await,
+                Err(PipelineErrorKind::Temporary(_))
+            ));
+            assert!(!source.open);
+        }
+
+        source
+            .load_eigenda_or_calldata(&BlockInfo::default(), BATCHER_ADDRESS)
+            .await
+            .expect("should be ok");
+        assert!(source.open);
+        assert!(source.data.is_empty());
+        assert_eq!(source.reload_count(), 3);
+
+        // the count starts over for the next l1 data
+        source.clear();
+        assert!(matches!(
+            source
+                .load_eigenda_or_calldata(&BlockInfo::default(), BATCHER_ADDRESS)
+                .await,
+            Err(PipelineErrorKind::Temporary(_))
+        ));
+    }
+
+    // a stale cert is discarded, the callback receives its altda commitment and the reason
+    #[tokio::test]
+    async fn test_load_eigenda_or_calldata_on_discard() {
+        let discarded: Arc<Mutex<Vec<(AltDACommitment, String)>>> = Default::default();
+        let sink = discarded.clone();
+        let mut source =
+            default_test_eigenda_data_source().with_on_discard(move |altda_commitment, reason| {
+                sink.lock()
+                    .unwrap()
+                    .push((altda_commitment.clone(), reason.to_string()))
+            });
+        configure_source_with_valid_eigenda_preimage(&mut source, 1);
+
+        // the cert is included far past its reference block number plus the recency window
+        let block_ref = BlockInfo {
+            number: u64::MAX,
+            ..Default::default()
+        };
+        source
+            .load_eigenda_or_calldata(&block_ref, BATCHER_ADDRESS)
+            .await
+            .expect("should be ok");
+        assert!(source.data.is_empty());
+
+        let (altda_commitment, _) = valid_encoded_payload_with_altda_commitment();
+        assert_eq!(
+            *discarded.lock().unwrap(),
+            vec![(altda_commitment, "da cert is not recent enough".to_string())]
+        );
+    }
+
+    // every attempt to pull l1 data is counted, serving the loaded data is not
+    #[tokio::test]
+    async fn test_reload_count_after_temporary_error() {
+        let mut source = default_test_eigenda_data_source();
+        configure_source_with_valid_eigenda_preimage(&mut source, 1);
+        assert_eq!(source.reload_count(), 0);
+
+        source.eigenda_source.eigenda_fetcher.should_preimage_err = true;
+        assert!(matches!(
+            source
+                .load_eigenda_or_calldata(&BlockInfo::default(), BATCHER_ADDRESS)
+                .await,
+            Err(PipelineErrorKind::Temporary(_))
+        ));
+        assert_eq!(source.reload_count(), 1);
+
+        source.eigenda_source.eigenda_fetcher.should_preimage_err = false;
+        source
+            .load_eigenda_or_calldata(&BlockInfo::default(), BATCHER_ADDRESS)
+            .await
+            .expect("should be ok");
+        assert_eq!(source.reload_count(), 2);
+
+        // the source is open, no data is pulled
+        source
+            .load_eigenda_or_calldata(&BlockInfo::default(), BATCHER_ADDRESS)
+            .await
+            .expect("should be ok");
+        assert_eq!(source.reload_count(), 2);
+    }
+
+    // the same altda commitment posted by two batchers, the second tx recovers to another signer
+    // since its signature has the other y parity
+    #[tokio::test]
+    async fn test_load_eigenda_or_calldata_multiple_batcher_addresses() {
+        let mut source = default_test_eigenda_data_source();
+        configure_source_with_valid_eigenda_preimage(&mut source, 1);
+
+        let tx = valid_eip1559_txs_with_altda_commitment(1).remove(0);
+        let TxEnvelope::Eip1559(signed) = tx.clone() else {
+            panic!("fixture is an eip1559 tx");
+        };
+        let (unsigned, signature, _) = signed.into_parts();
+        let signature =
+            alloy_primitives::Signature::new(signature.r(), signature.s(), !signature.v());
+        let other_tx = TxEnvelope::Eip1559(Signed::new_unhashed(unsigned, signature));
+        let other_batcher_address = other_tx.recover_signer().unwrap();
+        assert_ne!(other_batcher_address, BATCHER_ADDRESS);
+        // replaces the block of the single tx inserted above
+        source.ethereum_source.blob_source.chain_provider = TestChainProvider::default();
+        source
+            .ethereum_source
+            .blob_source
+            .chain_provider
+            .insert_block_with_transactions(1, BlockInfo::default(), vec![tx, other_tx]);
+
+        // only the batcher of the system config is accepted by default
+        source
+            .load_eigenda_or_calldata(&BlockInfo::default(), BATCHER_ADDRESS)
+            .await
+            .expect("should be ok");
+        assert_eq!(source.data.len(), 1);
+
+        let mut source =
+            source.with_batcher_addresses(vec![BATCHER_ADDRESS, other_batcher_address]);
+        source.clear();
+        source
+            .load_eigenda_or_calldata(&BlockInfo::default(), BATCHER_ADDRESS)
+            .await
+            .expect("should be ok");
+        assert_eq!(source.data.len(), 2);
+        assert!(source
+            .data
+            .iter()
+            .all(|d| matches!(d, EigenDAOrCalldata::EigenDA(..))));
+    }
+
     // (ToDo bx) once there is calldata failover transaction, complete this test
     #[tokio::test]
     async fn test_load_eigenda_or_calldata_chain_provider_1559_tx_with_ethda_failover() {}
@@ -562,9 +877,11 @@ mod tests {
             ]
             .into(),
         };
-        source
-            .data
-            .push(EigenDAOrCalldata::EigenDA(encoded_payload));
+        let (altda_commitment, _) = valid_encoded_payload_with_altda_commitment();
+        source.data.push(EigenDAOrCalldata::EigenDA(
+            altda_commitment,
+            encoded_payload,
+        ));
 
         let data = source
             .next(&BlockInfo::default(), Address::ZERO)
@@ -578,9 +895,33 @@ mod tests {
         let mut source = default_test_eigenda_data_source();
         source.open = true;
         // the default does not satisfy length requirement
-        source
-            .data
-            .push(EigenDAOrCalldata::EigenDA(EncodedPayload::default()));
+        let (altda_commitment, _) = valid_encoded_payload_with_altda_commitment();
+        source.data.push(EigenDAOrCalldata::EigenDA(
+            altda_commitment,
+            EncodedPayload::default(),
+        ));
+
+        let err = source
+            .next(&BlockInfo::default(), Address::ZERO)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PipelineErrorKind::Temporary(PipelineError::Eof)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_open_eigenda_blob_with_many_failed_decoding_encoded_payloads() {
+        let mut source = default_test_eigenda_data_source();
+        source.open = true;
+        let (altda_commitment, _) = valid_encoded_payload_with_altda_commitment();
+        for _ in 0..10_000 {
+            source.data.push(EigenDAOrCalldata::EigenDA(
+                altda_commitment.clone(),
+                EncodedPayload::default(),
+            ));
+        }
 
         let err = source
             .next(&BlockInfo::default(), Address::ZERO)
@@ -590,6 +931,7 @@ mod tests {
             err,
             PipelineErrorKind::Temporary(PipelineError::Eof)
         ));
+        assert!(source.data.is_empty());
     }
 
     // the source isn't provided with any data, and isn't open yet. So it uses Ethereum source, but Provider
@@ -640,4 +982,22 @@ mod tests {
             PipelineErrorKind::Temporary(PipelineError::Eof)
         ));
     }
+
+    // replay a block carrying two altda commitments, both are decoded from the pre-seeded preimages
+    #[tokio::test]
+    async fn test_drain_block_with_two_1559_txs() {
+        let mut source = default_test_eigenda_data_source();
+        configure_source_with_valid_eigenda_preimage(&mut source, 2);
+        let (_, encoded_payload) = valid_encoded_payload_with_altda_commitment();
+        let payload = encoded_payload.decode().unwrap();
+
+        let items = source
+            .drain_block(&BlockInfo::default(), BATCHER_ADDRESS)
+            .await
+            .expect("should be ok");
+        assert_eq!(items, vec![payload.clone(), payload]);
+        // the source is cleared, ready for the next block
+        assert!(!source.open);
+        assert!(source.data.is_empty());
+    }
 }
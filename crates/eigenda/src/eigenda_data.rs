@@ -6,7 +6,11 @@ use crate::{
     BYTES_PER_FIELD_ELEMENT,
 };
 use crate::{ENCODED_PAYLOAD_HEADER_LEN_BYTES, PAYLOAD_ENCODING_VERSION_0};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
 use alloy_primitives::Bytes;
+use eigenda_cert::AltDACommitment;
 use rust_kzg_bn254_primitives::helpers;
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +35,43 @@ impl EncodedPayload {
         }
     }
 
+    /// Encodes opaque rollup data into an [EncodedPayload], the reverse of [EncodedPayload::decode].
+    /// This matches the codec of the eigenda proxy, see
+    /// <https://github.com/Layr-Labs/eigenda/blob/master/encoding/utils/codec/codec.go#L12>
+    ///
+    /// The first field element is the header: a 0 byte for the bn254 field element constraint,
+    /// the payload encoding version, then the rollup data length as big endian u32. The rollup
+    /// data follows with an empty byte inserted for every 31 bytes. The result is padded to a
+    /// power of 2 number of field elements, such that it satisfies the length invariant checked
+    /// by decode.
+    pub fn encode(rollup_data: &[u8], payload_encoding_version: u8) -> Self {
+        let padded_payload = helpers::pad_payload(rollup_data);
+
+        // the first field element contains the header
+        let num_field_elements = (padded_payload.len() + ENCODED_PAYLOAD_HEADER_LEN_BYTES)
+            .div_ceil(BYTES_PER_FIELD_ELEMENT);
+        let mut encoded_payload =
+            vec![0u8; num_field_elements.next_power_of_two() * BYTES_PER_FIELD_ELEMENT];
+
+        encoded_payload[1] = payload_encoding_version;
+        // encode length as uint32
+        encoded_payload[2..6].copy_from_slice(&(rollup_data.len() as u32).to_be_bytes());
+        encoded_payload[ENCODED_PAYLOAD_HEADER_LEN_BYTES
+            ..ENCODED_PAYLOAD_HEADER_LEN_BYTES + padded_payload.len()]
+            .copy_from_slice(&padded_payload);
+
+        Self {
+            encoded_payload: encoded_payload.into(),
+        }
+    }
+
+    /// Encodes a rollup [Payload] into an [EncodedPayload] with the given payload encoding version,
+    /// matching the codec of the eigenda proxy. This is the entry point witgen and the host use
+    /// to turn rollup data into an encoded payload, see [EncodedPayload::encode] for the layout.
+    pub fn from_payload(payload: &Payload, version: u8) -> Self {
+        Self::encode(payload, version)
+    }
+
     /// Returns the raw bytes of the encoded payload.
     pub fn serialize(&self) -> &Bytes {
         &self.encoded_payload
@@ -41,6 +82,33 @@ impl EncodedPayload {
         (self.encoded_payload.len() / BYTES_PER_FIELD_ELEMENT) as u32
     }
 
+    /// Checks the encoded payload fits in the blob declared by the cert, i.e. it holds at most
+    /// [AltDACommitment::get_num_field_element] symbols. A shorter encoded payload is accepted, its
+    /// trailing symbols being zero.
+    pub fn assert_matches_cert(
+        &self,
+        commitment: &AltDACommitment,
+    ) -> Result<(), HokuleaStatelessError> {
+        let cert_len_symbols = commitment.get_num_field_element();
+        if self.len_symbols() as usize > cert_len_symbols {
+            return Err(HokuleaStatelessError::EncodedPayloadExceedsCertLength {
+                len_symbols: self.len_symbols(),
+                cert_len_symbols,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the power of 2 number of symbols required to encode the payload length claimed by
+    /// the header, i.e. the length of the encoded payload [EncodedPayload::encode] would
+    /// produce. Returns an error if the header is invalid.
+    pub fn claimed_len_symbols(&self) -> Result<u32, HokuleaStatelessError> {
+        let payload_len = self.decode_header()? as usize;
+        // the first field element contains the header, every other holds 31 bytes of the payload
+        let num_field_elements = 1 + payload_len.div_ceil(BYTES_PER_FIELD_ELEMENT - 1);
+        Ok(num_field_elements.next_power_of_two() as u32)
+    }
+
     /// Checks whether the encoded payload satisfies its length invariant.
     /// EncodedPayloads must contain a power of 2 number of Field Elements, each of length 32.
     /// This means the only valid encoded payloads have byte lengths of 32, 64, 128, 256, etc.
@@ -74,6 +142,23 @@ impl EncodedPayload {
         Ok(())
     }
 
+    /// Checks that every 32 byte chunk of the body is a valid bn254 field element. The check is
+    /// conservative, same as the one done by the host, it requires the top 3 bits of each chunk
+    /// to be zero, which is sufficient for the chunk to be smaller than the field modulus.
+    fn check_field_elements(&self) -> Result<(), HokuleaStatelessError> {
+        let body = &self.encoded_payload[ENCODED_PAYLOAD_HEADER_LEN_BYTES..];
+        for (i, chunk) in body.chunks(BYTES_PER_FIELD_ELEMENT).enumerate() {
+            if chunk[0] & 0b1110_0000 != 0 {
+                return Err(EncodedPayloadDecodingError::FieldElementOutOfRange {
+                    // header is the first field element
+                    index: i + 1,
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
     /// Validates the header (first field element = 32 bytes) of the encoded payload,
     /// and returns the claimed length of the payload if the header is valid.
     fn decode_header(&self) -> Result<u32, HokuleaStatelessError> {
@@ -106,7 +191,13 @@ impl EncodedPayload {
 
     /// Decodes the payload from the encoded payload bytes.
     /// Removes internal padding and extracts the payload data based on the claimed length.
-    fn decode_payload(&self, payload_len: u32) -> Result<Payload, HokuleaStatelessError> {
+    /// If strict, the decoded body must not hold anything beyond what [EncodedPayload::encode]
+    /// produces for the payload, i.e. there is no external padding.
+    fn decode_payload(
+        &self,
+        payload_len: u32,
+        strict: bool,
+    ) -> Result<Payload, HokuleaStatelessError> {
         let body = self
             .encoded_payload
             .slice(ENCODED_PAYLOAD_HEADER_LEN_BYTES..);
@@ -130,14 +221,59 @@ impl EncodedPayload {
             .into());
         }
 
+        // the encoding itself fills the last field element holding payload data and the power of
+        // 2 number of field elements with zeros, anything beyond that is external padding
+        if strict {
+            let encoded_body_len =
+                (self.claimed_len_symbols()? as usize - 1) * (BYTES_PER_FIELD_ELEMENT - 1);
+            let padding = &decoded_body[payload_len as usize..];
+            if decoded_body.len() > encoded_body_len || padding.iter().any(|byte| *byte != 0) {
+                return Err(EncodedPayloadDecodingError::UnexpectedExternalPadding {
+                    actual: decoded_body.len(),
+                    claimed: payload_len,
+                }
+                .into());
+            }
+        }
+
         Ok(decoded_body.slice(0..payload_len as usize))
     }
 
-    /// Decodes the encoded payload into raw byte data. Reverse of the encode function below
+    /// Decodes the encoded payload into raw byte data. Reverse of [EncodedPayload::encode]
     /// Returns a [EncodedPayloadDecodingError] if the encoded payload is invalid.
     ///
     /// Applies the inverse of PayloadEncodingVersion0 to an EncodedPayload, and returns the decoded payload.
+    ///
+    /// The blob an encoded payload is read from may hold more field elements than the payload
+    /// needs, since its length is the power of two committed in the cert rather than the one
+    /// chosen by [EncodedPayload::encode]. Decoding is driven by the payload length in the
+    /// header, so any trailing zero field elements are ignored and the decoded payload is the
+    /// same as for the unpadded encoding.
     pub fn decode(&self) -> Result<Payload, HokuleaStatelessError> {
+        self.decode_with(false, false)
+    }
+
+    /// Same as [EncodedPayload::decode], but for rollups enforcing tight encoding. It returns an
+    /// error if the encoded payload contains any external padding after the payload: a non zero
+    /// byte after the payload, or more field elements than [EncodedPayload::encode] produces.
+    pub fn decode_strict(&self) -> Result<Payload, HokuleaStatelessError> {
+        self.decode_with(true, false)
+    }
+
+    /// Same as [EncodedPayload::decode], but additionally checks that every field element of the
+    /// body is within the bn254 field range. [EncodedPayload::decode] skips this check, since the
+    /// internal padding byte is dropped regardless of its value.
+    pub fn decode_checked(&self) -> Result<Payload, HokuleaStatelessError> {
+        self.decode_with(false, true)
+    }
+
+    /// Decodes the encoded payload. If strict, external padding after the payload is rejected.
+    /// If check_field_elements, every field element of the body must be in the bn254 field range.
+    fn decode_with(
+        &self,
+        strict: bool,
+        check_field_elements: bool,
+    ) -> Result<Payload, HokuleaStatelessError> {
         // Check length invariant
         self.check_len_invariant()?;
 
@@ -145,11 +281,33 @@ impl EncodedPayload {
         let payload_len_in_header = self.decode_header()?;
         debug!(target: "eigenda-datasource", "rollup payload length in bytes {:?}", payload_len_in_header);
 
+        if check_field_elements {
+            self.check_field_elements()?;
+        }
+
         // Decode payload using the helper method
-        self.decode_payload(payload_len_in_header)
+        self.decode_payload(payload_len_in_header, strict)
     }
 }
 
+/// Decodes a sequence of encoded payloads, for instance the ones of the certs of a batch, into
+/// their payloads with [EncodedPayload::decode]. Concatenated in order, the payloads are the rollup
+/// channel bytes. It stops at the first encoded payload which fails to decode, and reports its index
+pub fn decode_payloads(payloads: &[EncodedPayload]) -> Result<Vec<Payload>, HokuleaStatelessError> {
+    payloads
+        .iter()
+        .enumerate()
+        .map(|(index, encoded_payload)| {
+            encoded_payload
+                .decode()
+                .map_err(|e| HokuleaStatelessError::PayloadAtIndex {
+                    index,
+                    source: Box::new(e),
+                })
+        })
+        .collect()
+}
+
 /// Utility function to check if a number is a power of two
 fn is_power_of_two(n: usize) -> bool {
     n != 0 && (n & (n - 1)) == 0
@@ -158,52 +316,46 @@ fn is_power_of_two(n: usize) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::valid_encoded_payload_with_altda_commitment;
     use alloc::vec;
     use alloy_primitives::Bytes;
 
-    /// The encode function accepts an input of opaque rollup data array into an [EncodedPayload].
-    /// [EncodedPayload] contains a header of 32 bytes and a transformation of input data
-    /// The 0 index byte of header is always 0, to comply to bn254 field element constraint
-    /// The 1 index byte of header is proxy encoding version.
-    /// The 2-4 indices of header are storing the length of the input rollup data in big endien
-    /// The payload is prepared by padding an empty byte for every 31 bytes from the rollup data
-    /// This matches exactly the eigenda proxy implementation, whose logic is in
-    /// <https://github.com/Layr-Labs/eigenda/blob/master/encoding/utils/codec/codec.go#L12>
-    ///
-    /// The length of (header + payload) by the encode function is always multiple of 32
-    /// The eigenda proxy does not take such constraint.
-    fn encode(rollup_data: &[u8], payload_encoding_version: u8) -> EncodedPayload {
-        let rollup_data_size = rollup_data.len() as u32;
-
-        let padded_rollup_data = helpers::pad_payload(rollup_data);
-
-        let min_blob_payload_size = padded_rollup_data.len();
-
-        // the first field element contains the header
-        let blob_size = min_blob_payload_size + BYTES_PER_FIELD_ELEMENT;
-
-        // round up to the closest multiple of 32
-        let blob_size = blob_size.div_ceil(BYTES_PER_FIELD_ELEMENT) * BYTES_PER_FIELD_ELEMENT;
-
-        let mut encoded_payload = vec![0u8; blob_size as usize];
-
-        encoded_payload[1] = payload_encoding_version;
-        // encode length as uint32
-        encoded_payload[2..6].copy_from_slice(&rollup_data_size.to_be_bytes());
-
-        encoded_payload
-            [BYTES_PER_FIELD_ELEMENT..(BYTES_PER_FIELD_ELEMENT + min_blob_payload_size as usize)]
-            .copy_from_slice(&padded_rollup_data);
+    #[test]
+    fn test_assert_matches_cert() {
+        let (altda_commitment, encoded_payload) = valid_encoded_payload_with_altda_commitment();
+        let cert_len_symbols = altda_commitment.get_num_field_element();
+
+        // equal
+        assert_eq!(encoded_payload.len_symbols() as usize, cert_len_symbols);
+        assert!(encoded_payload
+            .assert_matches_cert(&altda_commitment)
+            .is_ok());
+
+        // under
+        let shorter = EncodedPayload::deserialize(
+            encoded_payload.serialize()[..BYTES_PER_FIELD_ELEMENT]
+                .to_vec()
+                .into(),
+        );
+        assert!(shorter.assert_matches_cert(&altda_commitment).is_ok());
 
-        EncodedPayload {
-            encoded_payload: Bytes::from(encoded_payload),
-        }
+        // over
+        let mut longer = encoded_payload.serialize().to_vec();
+        longer.extend_from_slice(&[0u8; BYTES_PER_FIELD_ELEMENT]);
+        let longer = EncodedPayload::deserialize(longer.into());
+        assert_eq!(
+            longer.assert_matches_cert(&altda_commitment),
+            Err(HokuleaStatelessError::EncodedPayloadExceedsCertLength {
+                len_symbols: cert_len_symbols as u32 + 1,
+                cert_len_symbols,
+            })
+        );
     }
 
     #[test]
     fn test_encode_and_decode_success() {
         let rollup_data = vec![1, 2, 3, 4];
-        let encoded_payload = encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+        let encoded_payload = EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
         let data_len = encoded_payload.encoded_payload.len();
         assert!(data_len % BYTES_PER_FIELD_ELEMENT == 0 && data_len != 0);
 
@@ -215,7 +367,7 @@ mod tests {
     #[test]
     fn test_encode_and_decode_success_empty() {
         let rollup_data = vec![];
-        let encoded_payload = encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+        let encoded_payload = EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
         let data_len = encoded_payload.encoded_payload.len();
         // 32 byte is encoded payload header size
         assert!(data_len == 32);
@@ -228,7 +380,7 @@ mod tests {
     #[test]
     fn test_encode_and_decode_error_invalid_length() {
         let rollup_data = vec![1, 2, 3, 4];
-        let mut encoded_payload = encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+        let mut encoded_payload = EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
         encoded_payload.encoded_payload.truncate(33);
         let result = encoded_payload.decode();
         assert!(result.is_err());
@@ -238,10 +390,106 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_strict() {
+        // normally encoded payloads pass, whether or not the payload fills its last field element
+        // and the power of 2 number of field elements
+        for payload_len in [0, 4, 31, 32, 93, 94, 1000] {
+            let rollup_data: Vec<u8> = (0..payload_len).map(|i| (i % 255) as u8 + 1).collect();
+            let encoded_payload = EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+            assert_eq!(
+                encoded_payload.decode_strict().unwrap(),
+                Bytes::from(rollup_data)
+            );
+        }
+
+        // 4 bytes of rollup data occupy a single field element of 31 bytes after the header
+        let rollup_data = vec![1, 2, 3, 4];
+        let encoded_payload = EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+
+        // extra zero field elements after the encoding are external padding
+        let mut padded = encoded_payload.serialize().to_vec();
+        padded.extend_from_slice(&[0u8; 2 * BYTES_PER_FIELD_ELEMENT]);
+        let padded = EncodedPayload::deserialize(padded.into());
+        assert_eq!(padded.decode().unwrap(), Bytes::from(rollup_data.clone()));
+        assert_eq!(
+            padded.decode_strict().unwrap_err(),
+            EncodedPayloadDecodingError::UnexpectedExternalPadding {
+                actual: 93,
+                claimed: 4,
+            }
+            .into()
+        );
+
+        // so is data after the payload in its last field element
+        let mut trailing = encoded_payload.serialize().to_vec();
+        trailing[ENCODED_PAYLOAD_HEADER_LEN_BYTES + 1 + rollup_data.len()] = 1;
+        let trailing = EncodedPayload::deserialize(trailing.into());
+        assert_eq!(trailing.decode().unwrap(), Bytes::from(rollup_data));
+        assert_eq!(
+            trailing.decode_strict().unwrap_err(),
+            EncodedPayloadDecodingError::UnexpectedExternalPadding {
+                actual: 31,
+                claimed: 4,
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_from_payload_and_decode() {
+        // payload lengths, number of field elements of the encoded payload
+        let cases = [
+            (0, 1),
+            (1, 2),
+            (31, 2),
+            (32, 4),
+            (93, 4),
+            (94, 8),
+            (1000, 64),
+        ];
+
+        for (payload_len, num_field_elements) in cases {
+            let payload: Payload = (0..payload_len).map(|i| i as u8).collect();
+            let encoded_payload =
+                EncodedPayload::from_payload(&payload, PAYLOAD_ENCODING_VERSION_0);
+            assert_eq!(encoded_payload.len_symbols(), num_field_elements);
+            assert_eq!(
+                encoded_payload.claimed_len_symbols().unwrap(),
+                num_field_elements
+            );
+            assert_eq!(encoded_payload.decode().unwrap(), payload);
+            assert_eq!(encoded_payload.decode_checked().unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn test_decode_checked() {
+        let rollup_data = vec![1; 93];
+        let encoded_payload = EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+        // header and three body field elements
+        assert_eq!(encoded_payload.len_symbols(), 4);
+        assert_eq!(
+            encoded_payload.decode_checked().unwrap(),
+            Bytes::from(rollup_data.clone())
+        );
+
+        // out of range first byte on the second body field element
+        let mut bytes = encoded_payload.serialize().to_vec();
+        bytes[2 * BYTES_PER_FIELD_ELEMENT] = 0xFF;
+        let encoded_payload = EncodedPayload::deserialize(bytes.into());
+        assert_eq!(
+            encoded_payload.decode_checked().unwrap_err(),
+            EncodedPayloadDecodingError::FieldElementOutOfRange { index: 2 }.into()
+        );
+        // decode does not look at the padding byte
+        assert_eq!(encoded_payload.decode().unwrap(), Bytes::from(rollup_data));
+    }
+
     #[test]
     fn test_serde_on_encoded_payload() {
         let rollup_data = vec![1, 2, 3, 4];
-        let encoded_payload = encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+        let encoded_payload = EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
         let ser = encoded_payload.serialize();
         let deserialized_encoded_payload = EncodedPayload::deserialize(ser.clone());
         assert_eq!(encoded_payload, deserialized_encoded_payload);
@@ -400,10 +648,95 @@ mod tests {
                 .decode_header()
                 .expect("should have decoded header successfully");
 
-            match encoded_payload.decode_payload(length_in_byte) {
+            match encoded_payload.decode_payload(length_in_byte, false) {
                 Ok(payload) => assert_eq!(Ok(payload), case.result),
                 Err(e) => assert_eq!(Err(e), case.result),
             }
         }
     }
+
+    #[test]
+    fn test_decode_with_blob_larger_than_encoded_payload() {
+        let payload: Payload = (0..93).map(|i| i as u8).collect();
+        let encoded_payload = EncodedPayload::encode(&payload, PAYLOAD_ENCODING_VERSION_0);
+        assert_eq!(encoded_payload.len_symbols(), 4);
+
+        // pad with zero field elements up to larger powers of two, as the blob committed in the
+        // cert may be longer than the encoded payload
+        for num_field_elements in [8, 16, 64] {
+            let mut encoded = encoded_payload.serialize().to_vec();
+            encoded.resize(num_field_elements * BYTES_PER_FIELD_ELEMENT, 0);
+            let padded = EncodedPayload::deserialize(Bytes::from(encoded));
+            assert!(padded.check_len_invariant().is_ok());
+            assert_eq!(padded.decode().unwrap(), payload);
+            assert_eq!(padded.decode_checked().unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn test_encode_and_decode_roundtrip() {
+        // lengths around the field element and power of two boundaries, with pseudo random data
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let lengths = (0..=128).chain([247, 248, 249, 495, 496, 497, 1000, 4096, 30000]);
+        for len in lengths {
+            let rollup_data: vec::Vec<u8> = (0..len)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    seed as u8
+                })
+                .collect();
+            let encoded_payload = EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+
+            assert!(encoded_payload.check_len_invariant().is_ok());
+            assert_eq!(
+                encoded_payload.len_symbols(),
+                encoded_payload.claimed_len_symbols().unwrap()
+            );
+            assert_eq!(encoded_payload.decode_header().unwrap(), len as u32);
+            assert_eq!(
+                encoded_payload.decode().unwrap(),
+                Bytes::from(rollup_data.clone())
+            );
+            assert_eq!(
+                encoded_payload.decode_checked().unwrap(),
+                Bytes::from(rollup_data)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_payloads() {
+        let first = vec![1u8, 2, 3];
+        let second = vec![4u8; 40];
+        let payloads = [
+            EncodedPayload::encode(&first, PAYLOAD_ENCODING_VERSION_0),
+            EncodedPayload::encode(&second, PAYLOAD_ENCODING_VERSION_0),
+        ];
+        assert_eq!(
+            decode_payloads(&payloads).unwrap(),
+            vec![Payload::from(first.clone()), Payload::from(second)]
+        );
+        assert_eq!(decode_payloads(&[]).unwrap(), Vec::<Payload>::new());
+
+        // the second encoded payload has an invalid header
+        let mut invalid = EncodedPayload::encode(&first, PAYLOAD_ENCODING_VERSION_0)
+            .encoded_payload
+            .to_vec();
+        invalid[0] = 1;
+        let payloads = [
+            EncodedPayload::encode(&first, PAYLOAD_ENCODING_VERSION_0),
+            EncodedPayload {
+                encoded_payload: invalid.into(),
+            },
+        ];
+        assert_eq!(
+            decode_payloads(&payloads),
+            Err(HokuleaStatelessError::PayloadAtIndex {
+                index: 1,
+                source: Box::new(EncodedPayloadDecodingError::InvalidHeaderFirstByte(1).into()),
+            })
+        );
+    }
 }
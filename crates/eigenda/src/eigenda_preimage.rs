@@ -6,7 +6,22 @@ use crate::HokuleaPreimageError;
 
 use crate::errors::{HokuleaErrorKind, HokuleaStatelessError};
 use alloy_primitives::Bytes;
-use eigenda_cert::AltDACommitment;
+use eigenda_cert::{AltDACommitment, AltDACommitmentParseError, EigenDAVersionedCert};
+use tracing::{Instrument, Span};
+
+/// Returns a span identifying a cert by its digest. Every log emitted while processing the cert,
+/// including the ones of the preimage provider, is recorded within the span, so that all logs of
+/// a single cert can be filtered by its digest
+pub fn cert_span(altda_commitment: &AltDACommitment) -> Span {
+    info_span!(
+        target: "eigenda_preimage_source",
+        "eigenda_cert",
+        digest = %altda_commitment.to_digest(),
+        cert_version = altda_commitment.cert_version_str(),
+        rbn = altda_commitment.get_rbn(),
+        num_field_element = altda_commitment.get_num_field_element(),
+    )
+}
 
 /// A data iterator that reads from a preimage.
 #[derive(Debug, Clone)]
@@ -27,19 +42,34 @@ where
         Self { eigenda_fetcher }
     }
 
-    /// Fetches the preimages from the source for calldata.
+    /// Fetches the preimages from the source for calldata. The parsed altda commitment is
+    /// returned along with its encoded payload, such that it can identify the payload later on
     pub async fn next(
         &mut self,
         calldata: &Bytes,
         l1_inclusion_bn: u64,
-    ) -> Result<EncodedPayload, HokuleaErrorKind> {
+    ) -> Result<(AltDACommitment, EncodedPayload), HokuleaErrorKind> {
         let altda_commitment = self.parse(calldata)?;
+        let span = cert_span(&altda_commitment);
 
+        let encoded_payload = self
+            .fetch_preimages(&altda_commitment, l1_inclusion_bn)
+            .instrument(span)
+            .await?;
+        Ok((altda_commitment, encoded_payload))
+    }
+
+    /// Fetches the recency window, validity and encoded payload of a parsed altda commitment
+    async fn fetch_preimages(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+        l1_inclusion_bn: u64,
+    ) -> Result<EncodedPayload, HokuleaErrorKind> {
         info!(target: "eigenda_preimage_source", "parsed an altda commitment of version {}", altda_commitment.cert_version_str());
         // get recency window size, discard the old cert if necessary
         match self
             .eigenda_fetcher
-            .get_recency_window(&altda_commitment)
+            .get_recency_window_at(altda_commitment, l1_inclusion_bn)
             .await
         {
             Ok(recency) => {
@@ -58,7 +88,7 @@ where
         };
 
         // get cert validty via preimage oracle, discard cert if invalid
-        match self.eigenda_fetcher.get_validity(&altda_commitment).await {
+        match self.eigenda_fetcher.get_validity(altda_commitment).await {
             Ok(true) => (),
             Ok(false) => return Err(HokuleaPreimageError::InvalidCert.into()),
             Err(e) => return Err(e.into()),
@@ -66,7 +96,7 @@ where
 
         // get encoded payload via preimage oracle
         self.eigenda_fetcher
-            .get_encoded_payload(&altda_commitment)
+            .get_encoded_payload(altda_commitment)
             .await
             .map_err(|e| e.into())
     }
@@ -84,6 +114,16 @@ where
                 return Err(HokuleaStatelessError::ParseError(e));
             }
         };
+        // V1 certs are only parsed for digesting historical certs, they have no validity proof
+        // and are discarded by the derivation like any unsupported cert version
+        if let EigenDAVersionedCert::V1(_) = altda_commitment.versioned_cert {
+            warn!(target: "preimage_source", "Discarding a V1 altda commitment");
+            return Err(HokuleaStatelessError::ParseError(
+                AltDACommitmentParseError::UnsupportedCertVersionType(
+                    EigenDAVersionedCert::V1_VERSION_BYTE,
+                ),
+            ));
+        }
         Ok(altda_commitment)
     }
 }
@@ -96,7 +136,6 @@ mod tests {
     use alloc::string::ToString;
     use alloc::vec;
     use alloy_primitives::hex;
-    use eigenda_cert::AltDACommitmentParseError;
 
     const CALLDATA_HEX: &str = "0x01010001f9035ef901cdf901c8f9018080820001f90158f842a013cb9a6e004f28a193672a95b2ee4a2addc14bfe705eb3c1695f34dccfdf4d7fa01de675df78f68e6f40643f148b7dcf7b30e7bbb5ec5ed66efcf82e02a148b45ef888f842a00ca1a4b18243aed65a6887cb3da7ab7a9b8138261ad5fa7a7ef61fcf45ad0f77a012969add06ec97e0b24ef9f69633114966952c02150f8bb28a55a5fac60c7644f842a00c137feb7cf2cf625b826eebd5a1ffd400446e03336c6ff07061b7a9adc32376a00cd9277cc3e8c2a6c896c4e7c045504d1cff34ec9e8a6648e8ef4f335ae5b943f887f842a02b977c12979aed6688323f70e2d5ca9e2640fe14bf0a5e26ddfac95134d9c09ea02c204a0405fb9c3cb890219c6fccff0a9a265415656c5896449884c6a64caedef841a00104c001661c0169aac0fb16db9f30b70f8e13da88c539904b61895d3494c7889fca145e3f25f772c7e951708a541d8d14bb923edea351eeb0bbc928ae5b798508a0676a73762570ea5c17427aed9db14a85b268fafc282cbbe0c3db9165487133c9b84118cf5bd976613bb6a63009b15613d137f2555d2418da654a11781ac2cf5bf2fb63d44a580d2f15628f4b1cdb9526e1f774360b8ef2e5e451f18a80411d06b42b01c1808080e5a05e27869d58bd1fe21f34d0e9120abe775896df7c0829cf4d870f576f188cbe30838a8d05f90162c0c0f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a027b90b5da16ef02417ad5820223e680d2c2d19a3f1d30566cfbb7b9aa30abf6da022432d9b57d271b8dd84bfb4ccd9df36b84e422cb471b35d50d55ae83a03f16ef842a0018ed79d6c0707cc6f4ec81bcea6c4cc0096f0e3635961caf3271c3c9a36a9dfa0179360dc4646a7c49bf730e1789c00622facd7836faa3c747be0f2d824cb1412f841a02147a377c426a6b91bd27342dfe180882d130d9fbbdcb147477f025082135c189f468884960c4e83243b3aeb52ef2eb017fa81ec4b98f63bedc7c1dc27ec0bfec20705c20805c2c0c0820001";
 
@@ -131,6 +170,17 @@ mod tests {
                 input: hex::decode(CALLDATA_HEX).unwrap(),
                 result: Ok(()),
             },
+            // 0x01 (OP derivation version byte) ++ V1 altda commitment
+            Case {
+                input: [
+                    &[1u8][..],
+                    &test_utils::v1_altda_commitment().to_rlp_bytes(),
+                ]
+                .concat(),
+                result: Err(HokuleaStatelessError::ParseError(
+                    AltDACommitmentParseError::UnsupportedCertVersionType(0),
+                )),
+            },
         ];
 
         for case in cases {
@@ -272,9 +322,85 @@ mod tests {
                 .insert_encoded_payload(&altda_commitment, case.encoded_payload);
 
             match preimage_source.next(&calldata, l1_inclusion_number).await {
-                Ok(encoded_payload) => assert_eq!(encoded_payload, case.result.unwrap()),
+                Ok((_, encoded_payload)) => assert_eq!(encoded_payload, case.result.unwrap()),
                 Err(e) => assert_eq!(Err(e), case.result),
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_next_recency_boundary() {
+        let calldata = hex::decode(CALLDATA_HEX).unwrap().into();
+        let mut preimage_source = default_test_preimage_source();
+        let altda_commitment = preimage_source.parse(&calldata).unwrap();
+        let rbn = altda_commitment.get_rbn();
+        let recency = 100;
+        let encoded_payload = EncodedPayload {
+            encoded_payload: vec![0u8; 64].into(),
+        };
+
+        // l1 inclusion block number, accepted
+        let cases = [
+            (rbn, true),
+            (rbn + recency - 1, true),
+            // a cert included exactly at rbn + recency is still recent
+            (rbn + recency, true),
+            (rbn + recency + 1, false),
+        ];
+
+        for (l1_inclusion_bn, accepted) in cases {
+            preimage_source
+                .eigenda_fetcher
+                .insert_recency(&altda_commitment, Ok(recency));
+            preimage_source
+                .eigenda_fetcher
+                .insert_validity(&altda_commitment, Ok(true));
+            preimage_source
+                .eigenda_fetcher
+                .insert_encoded_payload(&altda_commitment, Ok(encoded_payload.clone()));
+
+            let result = preimage_source
+                .next(&calldata, l1_inclusion_bn)
+                .await
+                .map(|(_, encoded_payload)| encoded_payload);
+            if accepted {
+                assert_eq!(result, Ok(encoded_payload.clone()));
+            } else {
+                assert_eq!(result, Err(HokuleaPreimageError::NotRecentCert.into()));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_discards_v1_cert() {
+        let altda_commitment = test_utils::v1_altda_commitment();
+        let calldata: Bytes = [&[1u8][..], &altda_commitment.to_rlp_bytes()]
+            .concat()
+            .into();
+        let mut preimage_source = default_test_preimage_source();
+        // the preimages are available, but a V1 cert never reaches the preimage lookups
+        preimage_source
+            .eigenda_fetcher
+            .insert_recency(&altda_commitment, Ok(100));
+        preimage_source
+            .eigenda_fetcher
+            .insert_validity(&altda_commitment, Ok(true));
+        preimage_source.eigenda_fetcher.insert_encoded_payload(
+            &altda_commitment,
+            Ok(EncodedPayload {
+                encoded_payload: vec![0u8; 64].into(),
+            }),
+        );
+
+        let result = preimage_source
+            .next(&calldata, altda_commitment.get_rbn())
+            .await
+            .map(|(_, encoded_payload)| encoded_payload);
+        assert_eq!(
+            result,
+            Err(HokuleaErrorKind::Discard(
+                AltDACommitmentParseError::UnsupportedCertVersionType(0).to_string()
+            ))
+        );
+    }
 }
@@ -34,6 +34,23 @@ pub enum HokuleaStatelessError {
     /// encoded payload decoding error, inbox sender has violated the encoding rule
     #[error("cannot decode an encoded payload")]
     DecodingError(#[from] EncodedPayloadDecodingError),
+    /// decoding error of an encoded payload within a sequence, see [crate::decode_payloads]
+    #[error("cannot decode encoded payload at index {index}")]
+    PayloadAtIndex {
+        /// position of the encoded payload in the sequence
+        index: usize,
+        /// error decoding the encoded payload
+        #[source]
+        source: alloc::boxed::Box<HokuleaStatelessError>,
+    },
+    /// encoded payload holds more symbols than the blob length declared by the cert
+    #[error("encoded payload has {len_symbols} symbols, but the cert declares a blob of {cert_len_symbols} symbols")]
+    EncodedPayloadExceedsCertLength {
+        /// number of symbols in the encoded payload
+        len_symbols: u32,
+        /// number of symbols declared by the cert
+        cert_len_symbols: usize,
+    },
 }
 
 /// define conversion error
@@ -45,6 +62,12 @@ impl From<HokuleaStatelessError> for HokuleaErrorKind {
             }
             HokuleaStatelessError::ParseError(e) => HokuleaErrorKind::Discard(e.to_string()),
             HokuleaStatelessError::DecodingError(e) => HokuleaErrorKind::Discard(e.to_string()),
+            e @ HokuleaStatelessError::PayloadAtIndex { .. } => {
+                HokuleaErrorKind::Discard(e.to_string())
+            }
+            e @ HokuleaStatelessError::EncodedPayloadExceedsCertLength { .. } => {
+                HokuleaErrorKind::Discard(e.to_string())
+            }
         }
     }
 }
@@ -80,6 +103,23 @@ pub enum EncodedPayloadDecodingError {
         /// Claimed length from header
         claimed: u32,
     },
+    /// unpadded data holds more than the encoding of the length claimed in header, only an error
+    /// for strict decoding
+    #[error("unpadded data of length {actual} holds external padding beyond the encoding of length {claimed} claimed in encoded payload header, external padding is not allowed")]
+    UnexpectedExternalPadding {
+        /// Actual unpadded data length
+        actual: usize,
+        /// Claimed length from header
+        claimed: u32,
+    },
+    /// a field element of the body is not a valid bn254 field element, only an error for checked decoding
+    #[error(
+        "field element at index {index} of the encoded payload is out of the bn254 field range"
+    )]
+    FieldElementOutOfRange {
+        /// Index of the field element within the encoded payload, the header is at index 0
+        index: usize,
+    },
 }
 
 /// The [HokuleaPreimageError] contains application errors, that is directly relates
@@ -22,13 +22,13 @@ pub use traits::{
 };
 
 mod eigenda;
-pub use eigenda::EigenDADataSource;
+pub use eigenda::{DiscardCallback, EigenDADataSource};
 
 mod eigenda_preimage;
-pub use eigenda_preimage::EigenDAPreimageSource;
+pub use eigenda_preimage::{cert_span, EigenDAPreimageSource};
 
 mod eigenda_data;
-pub use eigenda_data::{EncodedPayload, Payload};
+pub use eigenda_data::{decode_payloads, EncodedPayload, Payload};
 
 mod errors;
 pub use errors::{
@@ -43,3 +43,5 @@ pub use constant::ALTDA_DERIVATION_VERSION;
 pub use constant::BYTES_PER_FIELD_ELEMENT;
 pub use constant::ENCODED_PAYLOAD_HEADER_LEN_BYTES;
 pub use constant::PAYLOAD_ENCODING_VERSION_0;
+
+pub mod prelude;
@@ -0,0 +1,42 @@
+//! Re-exports the items commonly needed to plug EigenDA into a derivation pipeline, such that
+//! `use hokulea_eigenda::prelude::*;` covers typical client and host code.
+pub use crate::{
+    EigenDADataSource, EigenDAPreimageProvider, EncodedPayload, HokuleaErrorKind, Payload,
+    RESERVED_EIGENDA_API_BYTE_FOR_RECENCY, RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY,
+    RESERVED_EIGENDA_API_BYTE_INDEX,
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::test_utils::TestEigenDAPreimageProvider;
+    use crate::PAYLOAD_ENCODING_VERSION_0;
+    use alloc::string::ToString;
+    use kona_derive::test_utils::{TestBlobProvider, TestChainProvider};
+
+    fn assert_preimage_provider<A: EigenDAPreimageProvider>() {}
+
+    #[test]
+    fn test_prelude() {
+        assert_preimage_provider::<TestEigenDAPreimageProvider>();
+        let _reload_count: fn(
+            &EigenDADataSource<TestChainProvider, TestBlobProvider, TestEigenDAPreimageProvider>,
+        ) -> u64 = EigenDADataSource::reload_count;
+
+        let payload = Payload::from_static(&[1, 2, 3]);
+        let encoded_payload = EncodedPayload::encode(&payload, PAYLOAD_ENCODING_VERSION_0);
+        assert_eq!(encoded_payload.decode().unwrap(), payload);
+
+        let err = HokuleaErrorKind::Discard("discard".to_string());
+        assert!(matches!(err, HokuleaErrorKind::Discard(_)));
+
+        assert_ne!(
+            RESERVED_EIGENDA_API_BYTE_FOR_RECENCY,
+            RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY
+        );
+        // the reserved byte lies in the 80 bytes digest template of an altda commitment
+        let mut address_template = [0u8; 80];
+        address_template[RESERVED_EIGENDA_API_BYTE_INDEX] = RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY;
+        assert_eq!(address_template.iter().filter(|b| **b != 0).count(), 1);
+    }
+}
@@ -3,10 +3,16 @@
 use crate::errors::HokuleaErrorKind;
 use crate::{EigenDAPreimageProvider, EncodedPayload};
 use alloy_primitives::{map::HashMap, B256};
-use eigenda_cert::AltDACommitment;
+use eigenda_cert::test_utils::v3_altda_commitment;
+use eigenda_cert::v1_cert::{
+    BatchHeaderV1, BatchMetadataV1, BlobHeaderV1, BlobQuorumParamV1, BlobVerificationProofV1,
+    G1CommitmentV1,
+};
+use eigenda_cert::{AltDACommitment, BlobInfoV1, EigenDAVersionedCert};
 
 use alloc::boxed::Box;
 use alloc::string::ToString;
+use alloc::vec;
 use async_trait::async_trait;
 
 /// Custom hokulea preimage error
@@ -131,3 +137,57 @@ impl EigenDAPreimageProvider for TestEigenDAPreimageProvider {
             .clone()
     }
 }
+
+// the altda commitment and encoded payload correspond to the eip1559 tx of
+// [crate::eigenda::tests::valid_eip1559_txs_with_altda_commitment]
+pub(crate) fn valid_encoded_payload_with_altda_commitment() -> (AltDACommitment, EncodedPayload) {
+    let altda_commitment = v3_altda_commitment();
+    // the encoded payload corresponding to the altda commitment
+    let raw_eigenda_blob = alloy_primitives::hex::decode("00000000009100000000000000000000000000000000000000000000000000000000ab80c99f814a3541886f8f4a65f61b67000000000079011b6501f88f532c00998d4648d239b1ce87da27450caaab705a5c8412149720e6dd229a4b97d25600ca7222a7ae434145a5d1440229000106a45bd00f3e0e33b07a5c23ad927eaa00f98a77e7818ff59e2c3b2c03d5ffaeb6dba4cb08b9fa2d122e8acbe726c4a70009ae086496e0d3ac00d70438c034e1f1314b70c0010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
+    let encoded_payload = EncodedPayload {
+        encoded_payload: raw_eigenda_blob.into(),
+    };
+    (altda_commitment, encoded_payload)
+}
+
+// a V1 altda commitment, which parses but is not supported by the derivation
+pub(crate) fn v1_altda_commitment() -> AltDACommitment {
+    let blob_info = BlobInfoV1 {
+        blob_header: BlobHeaderV1 {
+            commitment: G1CommitmentV1 {
+                x: vec![1u8; 32].into(),
+                y: vec![2u8; 32].into(),
+            },
+            data_length: 16,
+            blob_quorum_params: vec![BlobQuorumParamV1 {
+                quorum_number: 0,
+                adversary_threshold_percentage: 33,
+                confirmation_threshold_percentage: 55,
+                chunk_length: 1,
+            }],
+        },
+        blob_verification_proof: BlobVerificationProofV1 {
+            batch_id: 7,
+            blob_index: 3,
+            batch_metadata: BatchMetadataV1 {
+                batch_header: BatchHeaderV1 {
+                    batch_root: vec![3u8; 32].into(),
+                    quorum_numbers: vec![0].into(),
+                    quorum_signed_percentages: vec![100].into(),
+                    reference_block_number: 1234,
+                },
+                signatory_record_hash: vec![4u8; 32].into(),
+                fee: vec![0].into(),
+                confirmation_block_number: 1300,
+                batch_header_hash: vec![5u8; 32].into(),
+            },
+            inclusion_proof: vec![6u8; 64].into(),
+            quorum_indexes: vec![0].into(),
+        },
+    };
+    AltDACommitment {
+        commitment_type: 1,
+        da_layer_byte: 0,
+        versioned_cert: EigenDAVersionedCert::V1(blob_info),
+    }
+}
@@ -18,6 +18,17 @@ pub trait EigenDAPreimageProvider {
         altda_commitment: &AltDACommitment,
     ) -> Result<u64, Self::Error>;
 
+    /// Same as [EigenDAPreimageProvider::get_recency_window], for an altda commitment included in
+    /// the l1 block l1_inclusion_bn. A provider backed by a host can pass the block number on, such
+    /// that the host does not fetch the payload of a cert which is not recent.
+    async fn get_recency_window_at(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+        _l1_inclusion_bn: u64,
+    ) -> Result<u64, Self::Error> {
+        self.get_recency_window(altda_commitment).await
+    }
+
     /// Fetch preimage about the validity of a DA cert. Return generic preimage error. Return false when
     /// DA cert is invalid. True if it is valid.
     async fn get_validity(
@@ -34,14 +45,8 @@ pub trait EigenDAPreimageProvider {
     ) -> Result<EncodedPayload, Self::Error>;
 }
 
-/// The index where INTERFACE_BYTE is located
-/// More see <https://github.com/Layr-Labs/hokulea/tree/master/docs#reserved-addresses-for-da-certificates>
-pub const RESERVED_EIGENDA_API_BYTE_INDEX: usize = 32;
-
-/// In the address space of preimage oracle, which interface type a validity query is addressed at
-/// More see <https://github.com/Layr-Labs/hokulea/tree/master/docs#reserved-addresses-for-da-certificates>
-pub const RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY: u8 = 1;
-
-/// In the address space of preimage oracle, which interface type a recency query is addressed at
-/// More see <https://github.com/Layr-Labs/hokulea/tree/master/docs#reserved-addresses-for-da-certificates>
-pub const RESERVED_EIGENDA_API_BYTE_FOR_RECENCY: u8 = 2;
+// the reserved addresses are defined along with the key layout of AltDACommitment::preimage_keys
+pub use eigenda_cert::{
+    RESERVED_EIGENDA_API_BYTE_FOR_RECENCY, RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY,
+    RESERVED_EIGENDA_API_BYTE_INDEX,
+};
@@ -1,14 +1,16 @@
 use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
 use alloc::sync::Arc;
-use alloy_primitives::keccak256;
+use alloy_primitives::{keccak256, B256};
 use async_trait::async_trait;
-use eigenda_cert::AltDACommitment;
+use eigenda_cert::{AltDACommitment, BlobKey};
 use hokulea_eigenda::{
     EigenDAPreimageProvider, EncodedPayload, BYTES_PER_FIELD_ELEMENT,
     RESERVED_EIGENDA_API_BYTE_FOR_RECENCY, RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY,
     RESERVED_EIGENDA_API_BYTE_INDEX,
 };
-use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
+use kona_preimage::{errors::PreimageOracleError, CommsClient, PreimageKey, PreimageKeyType};
+use spin::Mutex;
 
 use crate::errors::HokuleaOracleProviderError;
 use crate::hint::ExtendedHintType;
@@ -21,29 +23,56 @@ use alloc::vec::Vec;
 pub struct OracleEigenDAPreimageProvider<T: CommsClient> {
     /// The preimage oracle client.
     oracle: Arc<T>,
+    /// The recency windows and validities already read from the oracle, if shared by the caller
+    cache: Option<OraclePreimageCache>,
 }
 
 impl<T: CommsClient> OracleEigenDAPreimageProvider<T> {
     /// Constructs a new oracle-backed EigenDA provider.
     pub fn new(oracle: Arc<T>) -> Self {
-        Self { oracle }
+        Self {
+            oracle,
+            cache: None,
+        }
     }
-}
 
-#[async_trait]
-impl<T: CommsClient + Sync + Send> EigenDAPreimageProvider for OracleEigenDAPreimageProvider<T> {
-    type Error = HokuleaOracleProviderError;
+    /// Constructs an oracle-backed EigenDA provider which consults the cache before reading a
+    /// recency window or a validity from the oracle, and writes the values read to it. Running
+    /// many derivations against the same oracle with one cache reads them once per cert
+    pub fn with_cache(oracle: Arc<T>, cache: OraclePreimageCache) -> Self {
+        Self {
+            oracle,
+            cache: Some(cache),
+        }
+    }
+}
 
-    /// Fetch preimage about the recency window
-    async fn get_recency_window(
+impl<T: CommsClient + Sync + Send> OracleEigenDAPreimageProvider<T> {
+    /// Fetches the recency window, see [EigenDAPreimageProvider::get_recency_window_at]
+    async fn fetch_recency_window(
         &mut self,
         altda_commitment: &AltDACommitment,
-    ) -> Result<u64, Self::Error> {
+        l1_inclusion_bn: Option<u64>,
+    ) -> Result<u64, HokuleaOracleProviderError> {
+        if let Some(recency) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(altda_commitment).recency)
+        {
+            return Ok(recency);
+        }
+
         let altda_commitment_bytes = altda_commitment.to_rlp_bytes();
         // hint the host about a new altda commitment. If it is the first time the host receiving it, the
-        // host then prepares all the necessary preimage; if not, the host simply returns data from its cache
+        // host then prepares all the necessary preimage; if not, the host simply returns data from its cache.
+        // Knowing the l1 inclusion block, the host skips the payload of a cert which is not recent
+        let hint = match l1_inclusion_bn {
+            Some(l1_inclusion_bn) => ExtendedHintType::EigenDACertWithL1Inclusion
+                .encode_with(&[&altda_commitment_bytes, &l1_inclusion_bn.to_be_bytes()]),
+            None => ExtendedHintType::EigenDACert.encode_with(&[&altda_commitment_bytes]),
+        };
         self.oracle
-            .write(&ExtendedHintType::EigenDACert.encode_with(&[&altda_commitment_bytes]))
+            .write(&hint)
             .await
             .map_err(HokuleaOracleProviderError::Preimage)?;
 
@@ -70,7 +99,75 @@ impl<T: CommsClient + Sync + Send> EigenDAPreimageProvider for OracleEigenDAPrei
         buf.copy_from_slice(&recency_bytes);
 
         // use BigEndian
-        Ok(u64::from_be_bytes(buf))
+        let recency = u64::from_be_bytes(buf);
+        if let Some(cache) = &self.cache {
+            cache.update(altda_commitment, |cached| cached.recency = Some(recency));
+        }
+        Ok(recency)
+    }
+}
+
+/// OraclePreimageCache keeps the recency window and the validity read from the oracle for each
+/// cert, keyed by the digest of its altda commitment. Clones share the same cache
+#[derive(Debug, Clone, Default)]
+pub struct OraclePreimageCache {
+    entries: Arc<Mutex<BTreeMap<B256, CachedPreimages>>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct CachedPreimages {
+    recency: Option<u64>,
+    validity: Option<bool>,
+}
+
+impl OraclePreimageCache {
+    /// Creates an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get(&self, altda_commitment: &AltDACommitment) -> CachedPreimages {
+        self.entries
+            .lock()
+            .get(&altda_commitment.to_digest())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn update(
+        &self,
+        altda_commitment: &AltDACommitment,
+        update: impl FnOnce(&mut CachedPreimages),
+    ) {
+        update(
+            self.entries
+                .lock()
+                .entry(altda_commitment.to_digest())
+                .or_default(),
+        );
+    }
+}
+
+#[async_trait]
+impl<T: CommsClient + Sync + Send> EigenDAPreimageProvider for OracleEigenDAPreimageProvider<T> {
+    type Error = HokuleaOracleProviderError;
+
+    /// Fetch preimage about the recency window
+    async fn get_recency_window(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<u64, Self::Error> {
+        self.fetch_recency_window(altda_commitment, None).await
+    }
+
+    /// Fetch preimage about the recency window, hinting the host about the l1 inclusion block
+    async fn get_recency_window_at(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+        l1_inclusion_bn: u64,
+    ) -> Result<u64, Self::Error> {
+        self.fetch_recency_window(altda_commitment, Some(l1_inclusion_bn))
+            .await
     }
 
     /// Query preimage about the validity of a DA cert
@@ -78,6 +175,14 @@ impl<T: CommsClient + Sync + Send> EigenDAPreimageProvider for OracleEigenDAPrei
         &mut self,
         altda_commitment: &AltDACommitment,
     ) -> Result<bool, Self::Error> {
+        if let Some(validity) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(altda_commitment).validity)
+        {
+            return Ok(validity);
+        }
+
         let altda_commitment_bytes = altda_commitment.to_rlp_bytes();
         // hint the host about a new altda commitment. If it is the first time the host receiving it, the
         // host then prepares all the necessary preimage; if not, the host simply returns data from its cache
@@ -91,6 +196,8 @@ impl<T: CommsClient + Sync + Send> EigenDAPreimageProvider for OracleEigenDAPrei
         // make the call about validity of a altda commitment
         address_template[RESERVED_EIGENDA_API_BYTE_INDEX] = RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY;
 
+        // a missing key is reported separately from other oracle errors, such that a host which has not
+        // processed the cert is not confused with an invalid cert
         let validity = self
             .oracle
             .get(PreimageKey::new(
@@ -98,18 +205,27 @@ impl<T: CommsClient + Sync + Send> EigenDAPreimageProvider for OracleEigenDAPrei
                 PreimageKeyType::GlobalGeneric,
             ))
             .await
-            .map_err(HokuleaOracleProviderError::Preimage)?;
+            .map_err(|e| match e {
+                PreimageOracleError::KeyNotFound => {
+                    HokuleaOracleProviderError::MissingValidityPreimage
+                }
+                e => HokuleaOracleProviderError::Preimage(e),
+            })?;
 
         // validity is expected as a boolean
         if validity.is_empty() || validity.len() != 1 {
             return Err(HokuleaOracleProviderError::InvalidHokuleaPreimageQueryResponse);
         }
 
-        match validity[0] {
-            0 => Ok(false),
-            1 => Ok(true),
-            _ => Err(HokuleaOracleProviderError::InvalidHokuleaPreimageQueryResponse),
+        let validity = match validity[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(HokuleaOracleProviderError::InvalidHokuleaPreimageQueryResponse),
+        };
+        if let Some(cache) = &self.cache {
+            cache.update(altda_commitment, |cached| cached.validity = Some(validity));
         }
+        Ok(validity)
     }
 
     /// Get encoded payload
@@ -126,50 +242,77 @@ impl<T: CommsClient + Sync + Send> EigenDAPreimageProvider for OracleEigenDAPrei
             .map_err(HokuleaOracleProviderError::Preimage)?;
 
         let blob_length_fe = altda_commitment.get_num_field_element();
+        if blob_length_fe == 0 {
+            return Err(HokuleaOracleProviderError::ZeroLengthBlob);
+        }
 
         // data_length measurs in field element, multiply to get num bytes
         let mut encoded_payload: Vec<u8> = vec![0; blob_length_fe * BYTES_PER_FIELD_ELEMENT];
-        let field_element_key = altda_commitment.digest_template();
         self.fetch_encoded_payload(
-            field_element_key,
+            BlobKey::from_commitment(altda_commitment),
             blob_length_fe as u64,
             &mut encoded_payload,
         )
         .await?;
 
-        Ok(EncodedPayload {
+        let encoded_payload = EncodedPayload {
             encoded_payload: encoded_payload.into(),
-        })
+        };
+
+        // the payload length claimed by the header must fit in the blob length declared by the cert.
+        // An invalid header is left to the decoding, which discards the encoded payload
+        if let Ok(claimed_len_symbols) = encoded_payload.claimed_len_symbols() {
+            if claimed_len_symbols as usize > blob_length_fe {
+                return Err(HokuleaOracleProviderError::InvalidHokuleaPreimageQueryResponse);
+            }
+        }
+
+        Ok(encoded_payload)
     }
 }
 
 impl<T: CommsClient + Sync + Send> OracleEigenDAPreimageProvider<T> {
+    /// Returns a stream over the field elements of the encoded payload of the altda commitment.
+    /// Unlike [EigenDAPreimageProvider::get_encoded_payload], a field element is only fetched from
+    /// the oracle when it is pulled from the stream, such that the whole encoded payload is never
+    /// held in memory
+    pub async fn stream_encoded_payload(
+        &self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<EncodedPayloadStream<T>, HokuleaOracleProviderError> {
+        let altda_commitment_bytes = altda_commitment.to_rlp_bytes();
+        // hint the host about a new altda commitment. If it is the first time the host receiving it, the
+        // host then prepares all the necessary preimage; if not, the host simply returns data from its cache
+        self.oracle
+            .write(&ExtendedHintType::EigenDACert.encode_with(&[&altda_commitment_bytes]))
+            .await
+            .map_err(HokuleaOracleProviderError::Preimage)?;
+
+        let blob_length_fe = altda_commitment.get_num_field_element();
+        if blob_length_fe == 0 {
+            return Err(HokuleaOracleProviderError::ZeroLengthBlob);
+        }
+
+        Ok(EncodedPayloadStream {
+            oracle: self.oracle.clone(),
+            blob_key: BlobKey::from_commitment(altda_commitment),
+            blob_length_fe: blob_length_fe as u64,
+            next_idx_fe: 0,
+        })
+    }
+
     /// This is a helper that constructs comm keys for every field element,
     /// The key must be consistnet to the prefetch function from the FetcherWithEigenDASupport
     /// object inside the host
     async fn fetch_encoded_payload(
         &mut self,
-        mut field_element_key: [u8; 80],
+        blob_key: BlobKey,
         blob_length: u64,
         encoded_payload: &mut [u8],
     ) -> Result<(), HokuleaOracleProviderError> {
         for idx_fe in 0..blob_length {
-            // last 8 bytes for index
-            let index_byte: [u8; 8] = idx_fe.to_be_bytes();
-            field_element_key[72..].copy_from_slice(&index_byte);
-
-            // get field element
-            let mut field_element = [0u8; 32];
-            self.oracle
-                .get_exact(
-                    PreimageKey::new(
-                        *keccak256(field_element_key),
-                        PreimageKeyType::GlobalGeneric,
-                    ),
-                    &mut field_element,
-                )
-                .await
-                .map_err(HokuleaOracleProviderError::Preimage)?;
+            let field_element =
+                fetch_field_element(self.oracle.as_ref(), &blob_key, idx_fe).await?;
 
             encoded_payload[(idx_fe as usize) << 5..(idx_fe as usize + 1) << 5]
                 .copy_from_slice(field_element.as_ref());
@@ -177,3 +320,360 @@ impl<T: CommsClient + Sync + Send> OracleEigenDAPreimageProvider<T> {
         Ok(())
     }
 }
+
+/// Fetches the field element at idx_fe of the encoded payload of the blob key
+async fn fetch_field_element<T: CommsClient + Sync + Send>(
+    oracle: &T,
+    blob_key: &BlobKey,
+    idx_fe: u64,
+) -> Result<[u8; BYTES_PER_FIELD_ELEMENT], HokuleaOracleProviderError> {
+    let field_element_key = blob_key.with_index(idx_fe);
+
+    // get field element
+    let mut field_element = [0u8; BYTES_PER_FIELD_ELEMENT];
+    oracle
+        .get_exact(
+            PreimageKey::new(
+                *keccak256(field_element_key),
+                PreimageKeyType::GlobalGeneric,
+            ),
+            &mut field_element,
+        )
+        .await
+        .map_err(HokuleaOracleProviderError::Preimage)?;
+    Ok(field_element)
+}
+
+/// Field elements of an encoded payload fetched on demand from the oracle, in order, see
+/// [OracleEigenDAPreimageProvider::stream_encoded_payload]
+#[derive(Debug)]
+pub struct EncodedPayloadStream<T: CommsClient> {
+    oracle: Arc<T>,
+    blob_key: BlobKey,
+    blob_length_fe: u64,
+    next_idx_fe: u64,
+}
+
+impl<T: CommsClient + Sync + Send> EncodedPayloadStream<T> {
+    /// Returns the number of field elements not yet pulled from the stream
+    pub fn remaining(&self) -> u64 {
+        self.blob_length_fe - self.next_idx_fe
+    }
+
+    /// Fetches the next field element, or returns None once all field elements declared by the
+    /// cert are pulled. As for the eager path, the header in the first field element must not claim
+    /// a payload longer than the blob declared by the cert
+    pub async fn next_field_element(
+        &mut self,
+    ) -> Option<Result<[u8; BYTES_PER_FIELD_ELEMENT], HokuleaOracleProviderError>> {
+        if self.remaining() == 0 {
+            return None;
+        }
+        let idx_fe = self.next_idx_fe;
+        let field_element =
+            match fetch_field_element(self.oracle.as_ref(), &self.blob_key, idx_fe).await {
+                Ok(field_element) => field_element,
+                Err(e) => return Some(Err(e)),
+            };
+        self.next_idx_fe += 1;
+
+        if idx_fe == 0 {
+            let header = EncodedPayload {
+                encoded_payload: field_element.to_vec().into(),
+            };
+            if let Ok(claimed_len_symbols) = header.claimed_len_symbols() {
+                if claimed_len_symbols as u64 > self.blob_length_fe {
+                    return Some(Err(
+                        HokuleaOracleProviderError::InvalidHokuleaPreimageQueryResponse,
+                    ));
+                }
+            }
+        }
+        Some(Ok(field_element))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::map::HashMap;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use eigenda_cert::test_utils::v3_altda_commitment;
+    use hokulea_eigenda::HokuleaErrorKind;
+    use kona_preimage::{errors::PreimageOracleResult, HintWriterClient, PreimageOracleClient};
+
+    // an oracle serving preimages from memory, and ignoring hints
+    #[derive(Debug, Default)]
+    struct TestOracle {
+        preimages: HashMap<[u8; 32], Vec<u8>>,
+        num_gets: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl PreimageOracleClient for TestOracle {
+        async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            self.num_gets.fetch_add(1, Ordering::SeqCst);
+            self.preimages
+                .get(&<[u8; 32]>::from(key))
+                .cloned()
+                .ok_or(PreimageOracleError::KeyNotFound)
+        }
+
+        async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+            let preimage = self.get(key).await?;
+            buf.copy_from_slice(&preimage);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl HintWriterClient for TestOracle {
+        async fn write(&self, _hint: &str) -> PreimageOracleResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_validity_missing_preimage() {
+        let altda_commitment = v3_altda_commitment();
+
+        // the host stored the recency, but never processed the validity of the cert
+        let mut oracle = TestOracle::default();
+        let mut recency_address = altda_commitment.digest_template();
+        recency_address[RESERVED_EIGENDA_API_BYTE_INDEX] = RESERVED_EIGENDA_API_BYTE_FOR_RECENCY;
+        let recency_key =
+            PreimageKey::new(*keccak256(recency_address), PreimageKeyType::GlobalGeneric);
+        oracle
+            .preimages
+            .insert(recency_key.into(), 100u64.to_be_bytes().to_vec());
+
+        let mut provider = OracleEigenDAPreimageProvider::new(Arc::new(oracle));
+        assert_eq!(
+            provider
+                .get_recency_window(&altda_commitment)
+                .await
+                .unwrap(),
+            100
+        );
+
+        let err = provider.get_validity(&altda_commitment).await.unwrap_err();
+        assert!(matches!(
+            err,
+            HokuleaOracleProviderError::MissingValidityPreimage
+        ));
+        assert!(matches!(
+            HokuleaErrorKind::from(err),
+            HokuleaErrorKind::Temporary(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_with_cache_repeated_cert() {
+        let altda_commitment = v3_altda_commitment();
+
+        let mut oracle = TestOracle::default();
+        for (reserved_byte, preimage) in [
+            (
+                RESERVED_EIGENDA_API_BYTE_FOR_RECENCY,
+                100u64.to_be_bytes().to_vec(),
+            ),
+            (RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY, vec![1u8]),
+        ] {
+            let mut address = altda_commitment.digest_template();
+            address[RESERVED_EIGENDA_API_BYTE_INDEX] = reserved_byte;
+            oracle.preimages.insert(*keccak256(address), preimage);
+        }
+        let oracle = Arc::new(oracle);
+
+        // two derivations against the same oracle share the cache
+        let cache = OraclePreimageCache::new();
+        for _ in 0..2 {
+            let mut provider =
+                OracleEigenDAPreimageProvider::with_cache(oracle.clone(), cache.clone());
+            for _ in 0..2 {
+                assert_eq!(
+                    provider
+                        .get_recency_window(&altda_commitment)
+                        .await
+                        .unwrap(),
+                    100
+                );
+                assert!(provider.get_validity(&altda_commitment).await.unwrap());
+            }
+        }
+        // the recency window and the validity are read from the oracle once
+        assert_eq!(oracle.num_gets.load(Ordering::SeqCst), 2);
+
+        // without a cache, every query reads the oracle
+        let mut provider = OracleEigenDAPreimageProvider::new(oracle.clone());
+        provider
+            .get_recency_window(&altda_commitment)
+            .await
+            .unwrap();
+        provider
+            .get_recency_window(&altda_commitment)
+            .await
+            .unwrap();
+        assert_eq!(oracle.num_gets.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_get_encoded_payload_exceeds_cert_length() {
+        let altda_commitment = v3_altda_commitment();
+        let blob_length_fe = altda_commitment.get_num_field_element();
+
+        // serve the header field element claiming payload_len, and zero field elements for the rest
+        let oracle_with_header = |payload_len: u32| {
+            let mut oracle = TestOracle::default();
+            let mut field_element_key = altda_commitment.digest_template();
+            for idx_fe in 0..blob_length_fe as u64 {
+                field_element_key[72..].copy_from_slice(&idx_fe.to_be_bytes());
+                let mut field_element = vec![0u8; BYTES_PER_FIELD_ELEMENT];
+                if idx_fe == 0 {
+                    field_element[2..6].copy_from_slice(&payload_len.to_be_bytes());
+                }
+                oracle
+                    .preimages
+                    .insert(*keccak256(field_element_key), field_element);
+            }
+            oracle
+        };
+
+        // the claimed payload fills the blob declared by the cert
+        let payload_len = (blob_length_fe as u32 - 1) * (BYTES_PER_FIELD_ELEMENT as u32 - 1);
+        let mut provider =
+            OracleEigenDAPreimageProvider::new(Arc::new(oracle_with_header(payload_len)));
+        let encoded_payload = provider
+            .get_encoded_payload(&altda_commitment)
+            .await
+            .unwrap();
+        assert_eq!(encoded_payload.len_symbols() as usize, blob_length_fe);
+        assert_eq!(
+            encoded_payload.decode().unwrap().len(),
+            payload_len as usize
+        );
+
+        // the claimed payload needs a blob longer than the one declared by the cert
+        let mut provider =
+            OracleEigenDAPreimageProvider::new(Arc::new(oracle_with_header(payload_len + 1)));
+        let err = provider
+            .get_encoded_payload(&altda_commitment)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            HokuleaOracleProviderError::InvalidHokuleaPreimageQueryResponse
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_encoded_payload_zero_length_blob() {
+        let mut altda_commitment = v3_altda_commitment();
+        if let eigenda_cert::EigenDAVersionedCert::V3(c) = &mut altda_commitment.versioned_cert {
+            c.blob_inclusion_info
+                .blob_certificate
+                .blob_header
+                .commitment
+                .length = 0;
+        }
+        assert_eq!(altda_commitment.get_num_field_element(), 0);
+
+        let mut provider = OracleEigenDAPreimageProvider::new(Arc::new(TestOracle::default()));
+        let err = provider
+            .get_encoded_payload(&altda_commitment)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HokuleaOracleProviderError::ZeroLengthBlob));
+        assert!(matches!(
+            HokuleaErrorKind::from(err),
+            HokuleaErrorKind::Discard(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stream_encoded_payload_matches_get_encoded_payload() {
+        let altda_commitment = v3_altda_commitment();
+        let blob_length_fe = altda_commitment.get_num_field_element();
+        assert!(blob_length_fe > 1);
+
+        // a payload filling the blob declared by the cert
+        let payload_len = (blob_length_fe - 1) * (BYTES_PER_FIELD_ELEMENT - 1);
+        let payload: Vec<u8> = (0..payload_len).map(|i| i as u8).collect();
+        let expected = EncodedPayload::encode(&payload, 0);
+        assert_eq!(expected.len_symbols() as usize, blob_length_fe);
+
+        let mut oracle = TestOracle::default();
+        let mut field_element_key = altda_commitment.digest_template();
+        for (idx_fe, field_element) in expected
+            .encoded_payload
+            .chunks(BYTES_PER_FIELD_ELEMENT)
+            .enumerate()
+        {
+            field_element_key[72..].copy_from_slice(&(idx_fe as u64).to_be_bytes());
+            oracle
+                .preimages
+                .insert(*keccak256(field_element_key), field_element.to_vec());
+        }
+
+        let mut provider = OracleEigenDAPreimageProvider::new(Arc::new(oracle));
+        let mut stream = provider
+            .stream_encoded_payload(&altda_commitment)
+            .await
+            .unwrap();
+        assert_eq!(stream.remaining() as usize, blob_length_fe);
+        let mut streamed = Vec::new();
+        while let Some(field_element) = stream.next_field_element().await {
+            streamed.extend_from_slice(&field_element.unwrap());
+        }
+        assert_eq!(stream.remaining(), 0);
+
+        let encoded_payload = provider
+            .get_encoded_payload(&altda_commitment)
+            .await
+            .unwrap();
+        assert_eq!(streamed, encoded_payload.encoded_payload.to_vec());
+        assert_eq!(encoded_payload, expected);
+    }
+
+    #[tokio::test]
+    async fn test_preimage_keys_match_requested_keys() {
+        let altda_commitment = v3_altda_commitment();
+        let preimage_keys = altda_commitment.preimage_keys();
+        // recency, validity and the field elements, the kzg proof is never read from the oracle
+        assert_eq!(
+            preimage_keys.len(),
+            altda_commitment.get_num_field_element() + 2
+        );
+
+        // an oracle serving the preimage keys only, recency and validity first
+        let oracle_without = |skipped: Option<usize>| {
+            let mut oracle = TestOracle::default();
+            for (i, key) in preimage_keys.iter().enumerate() {
+                if Some(i) == skipped {
+                    continue;
+                }
+                let preimage = match i {
+                    0 => 100u64.to_be_bytes().to_vec(),
+                    1 => vec![1u8],
+                    _ => vec![0u8; BYTES_PER_FIELD_ELEMENT],
+                };
+                let key = PreimageKey::new(**key, PreimageKeyType::GlobalGeneric);
+                oracle.preimages.insert(key.into(), preimage);
+            }
+            oracle
+        };
+        let altda_commitment = &altda_commitment;
+        let query_all = |oracle: TestOracle| async move {
+            let mut provider = OracleEigenDAPreimageProvider::new(Arc::new(oracle));
+            provider.get_recency_window(altda_commitment).await?;
+            provider.get_validity(altda_commitment).await?;
+            provider.get_encoded_payload(altda_commitment).await
+        };
+
+        // the preimage keys are sufficient, and each of them is requested
+        assert!(query_all(oracle_without(None)).await.is_ok());
+        for skipped in 0..preimage_keys.len() {
+            assert!(query_all(oracle_without(Some(skipped))).await.is_err());
+        }
+    }
+}
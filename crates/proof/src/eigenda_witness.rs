@@ -1,6 +1,10 @@
 extern crate alloc;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
 use alloc::vec::Vec;
-use alloy_primitives::FixedBytes;
+use alloy_primitives::{keccak256, FixedBytes, B256};
 
 use eigenda_cert::AltDACommitment;
 use hokulea_eigenda::EncodedPayload;
@@ -8,6 +12,14 @@ use hokulea_eigenda::EncodedPayload;
 use canoe_verifier::CertValidity;
 use serde::{Deserialize, Serialize};
 
+use crate::errors::WitnessError;
+
+/// Version of the [EigenDAWitness] layout produced and accepted by this crate. It must be bumped
+/// whenever a field of EigenDAWitness is added, removed or changes its type, such that an artifact
+/// produced by an older witgen is rejected by [EigenDAWitness::decode] with a clear error rather
+/// than being misread. Witnesses serialized before versioning was introduced are version 0
+pub const CURRENT_WITNESS_VERSION: u32 = 2;
+
 /// EigenDAWitness contains preimage and witness data to be provided into
 /// the zkVM as part of Preimage Oracle. There are three types of preimages: 1. recency,
 /// 2. validity, 3. encoded payload.
@@ -42,16 +54,536 @@ use serde::{Deserialize, Serialize};
 /// pipeline calls for a preimage for a DA cert, the two DA certs must
 /// match, and otherwise there is failures. See PreloadedEigenDAPreimageProvider
 /// for more information
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EigenDAWitness {
+    /// version of the witness layout, see [CURRENT_WITNESS_VERSION]
+    pub version: u32,
     /// u64 containing the recency_window
     pub recencies: Vec<(AltDACommitment, u64)>,
     /// validity of a da cert
     pub validities: Vec<(AltDACommitment, CertValidity)>,
     /// encoded_payload corresponds to a da cert and its kzg proof
     pub encoded_payloads: Vec<(AltDACommitment, EncodedPayload, FixedBytes<64>)>,
-    /// used and populated at the end of canoe proof
-    /// it should only deserialize to one zk proof that proves all DA certs are
-    /// correct
-    pub canoe_proof_bytes: Option<Vec<u8>>,
+    /// used and populated at the end of canoe proof. Usually a single zk proof proving all
+    /// DA certs are correct, or several independent proofs, one per l1 anchor of the validities,
+    /// see [EigenDAWitness::canoe_proof_subsets]. Empty for verifiers which do not take a proof
+    pub canoe_proof_bytes: Vec<Vec<u8>>,
+}
+
+impl Default for EigenDAWitness {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_WITNESS_VERSION,
+            recencies: Vec::new(),
+            validities: Vec::new(),
+            encoded_payloads: Vec::new(),
+            canoe_proof_bytes: Vec::new(),
+        }
+    }
+}
+
+/// Only the version of a serialized witness, a missing version corresponds to a witness serialized
+/// before versioning was introduced
+#[derive(Deserialize)]
+struct WitnessVersion {
+    #[serde(default)]
+    version: u32,
+}
+
+impl EigenDAWitness {
+    /// Serializes the witness into bytes which can be read back by [EigenDAWitness::decode]
+    pub fn encode(&self) -> Result<Vec<u8>, WitnessError> {
+        serde_json::to_vec(self).map_err(|e| WitnessError::Serialization(e.to_string()))
+    }
+
+    /// Deserializes a witness from bytes produced by [EigenDAWitness::encode]. The version is
+    /// checked before the remaining fields, such that a witness of another layout is reported as
+    /// a version mismatch rather than a deserialization error on whichever field differs
+    pub fn decode(bytes: &[u8]) -> Result<Self, WitnessError> {
+        let WitnessVersion { version } = serde_json::from_slice(bytes)
+            .map_err(|e| WitnessError::Serialization(e.to_string()))?;
+        if version != CURRENT_WITNESS_VERSION {
+            return Err(WitnessError::VersionMismatch {
+                found: version,
+                expected: CURRENT_WITNESS_VERSION,
+            });
+        }
+        serde_json::from_slice(bytes).map_err(|e| WitnessError::Serialization(e.to_string()))
+    }
+
+    /// Loads a witness from a JSON file produced by [EigenDAWitness::encode], for instance a
+    /// witness dumped by the witgen phase, such that a zkVM run can be replayed without running
+    /// the derivation again
+    #[cfg(feature = "std")]
+    pub fn from_json_path(path: impl AsRef<std::path::Path>) -> Result<Self, WitnessError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|e| WitnessError::Io(format!("{}: {e}", path.display())))?;
+        Self::decode(&bytes)
+    }
+
+    /// Returns the keccak hash of the serialization produced by [EigenDAWitness::encode], which is
+    /// deterministic for a given witness
+    pub fn checksum(&self) -> B256 {
+        keccak256(
+            self.encode()
+                .expect("eigenda witness should be serializable"),
+        )
+    }
+
+    /// Reorders validities and encoded_payloads into the order the derivation pipeline consumes them,
+    /// which [crate::preloaded_eigenda_provider::PreloadedEigenDAPreimageProvider] relies on, for
+    /// witnesses whose entries were reordered by a tool reserializing them.
+    ///
+    /// The invariant is that recencies are in derivation order, which cannot be recovered once lost.
+    /// Every validity is the one of a cert in recencies, and every encoded payload is the one of a
+    /// cert claimed valid in validities, in the same relative order. Entries are matched to the
+    /// occurrences of their cert in the previous layer, an error is returned if an entry matches
+    /// no occurrence, or if only some occurrences of a repeated cert have an entry since the order
+    /// is ambiguous. The witness is left unchanged on error.
+    pub fn canonicalize(&mut self) -> Result<(), WitnessError> {
+        let recency_digests: Vec<B256> = self
+            .recencies
+            .iter()
+            .map(|(altda_commitment, _)| altda_commitment.to_digest())
+            .collect();
+        let validities = canonical_order(
+            "validities",
+            &recency_digests,
+            self.validities.clone(),
+            |(altda_commitment, _)| altda_commitment.to_digest(),
+        )?;
+
+        let valid_digests: Vec<B256> = validities
+            .iter()
+            .filter(|(_, cert_validity)| cert_validity.claimed_validity)
+            .map(|(altda_commitment, _)| altda_commitment.to_digest())
+            .collect();
+        let encoded_payloads = canonical_order(
+            "encoded_payloads",
+            &valid_digests,
+            self.encoded_payloads.clone(),
+            |(altda_commitment, _, _)| altda_commitment.to_digest(),
+        )?;
+
+        self.validities = validities;
+        self.encoded_payloads = encoded_payloads;
+        Ok(())
+    }
+
+    /// Returns a one line description of the witness with counts and sizes only. Unlike the
+    /// derived Debug, it never dumps encoded payloads or proof bytes, so it is usable in logs
+    pub fn summary(&self) -> String {
+        let payload_bytes: usize = self
+            .encoded_payloads
+            .iter()
+            .map(|(_, encoded_payload, _)| encoded_payload.serialize().len())
+            .sum();
+        let proof_bytes: usize = self.canoe_proof_bytes.iter().map(Vec::len).sum();
+        format!(
+            "EigenDAWitness{{recencies:{}, validities:{}, payloads:{}({}B), proofs:{}({}B)}}",
+            self.recencies.len(),
+            self.validities.len(),
+            self.encoded_payloads.len(),
+            payload_bytes,
+            self.canoe_proof_bytes.len(),
+            proof_bytes,
+        )
+    }
+
+    /// Splits the validities into the subsets each canoe proof attests, along with the proof.
+    /// Without any proof, all validities are checked by a verifier which takes none, and a single
+    /// proof covers all validities, whichever l1 anchor they are at. Several proofs are the
+    /// independent proofs of an l1 anchor each, the validities are grouped by (l1_chain_id,
+    /// l1_head_block_hash) in order of first occurrence and the i-th proof covers the i-th group
+    #[allow(clippy::type_complexity)]
+    pub fn canoe_proof_subsets(
+        &self,
+    ) -> Result<Vec<(Vec<(AltDACommitment, CertValidity)>, Option<Vec<u8>>)>, WitnessError> {
+        if self.canoe_proof_bytes.len() <= 1 {
+            return Ok(vec![(
+                self.validities.clone(),
+                self.canoe_proof_bytes.first().cloned(),
+            )]);
+        }
+
+        let mut anchors: Vec<(u64, B256)> = Vec::new();
+        let mut subsets: Vec<Vec<(AltDACommitment, CertValidity)>> = Vec::new();
+        for (altda_commitment, cert_validity) in &self.validities {
+            let anchor = (cert_validity.l1_chain_id, cert_validity.l1_head_block_hash);
+            let position = match anchors.iter().position(|a| *a == anchor) {
+                Some(position) => position,
+                None => {
+                    anchors.push(anchor);
+                    subsets.push(Vec::new());
+                    anchors.len() - 1
+                }
+            };
+            subsets[position].push((altda_commitment.clone(), cert_validity.clone()));
+        }
+        if subsets.len() != self.canoe_proof_bytes.len() {
+            return Err(WitnessError::CanoeProofCountMismatch {
+                num_proofs: self.canoe_proof_bytes.len(),
+                num_anchors: subsets.len(),
+            });
+        }
+        Ok(subsets
+            .into_iter()
+            .zip(self.canoe_proof_bytes.iter().cloned().map(Some))
+            .collect())
+    }
+}
+
+/// Orders the entries after the occurrences of their cert in the previous layer, see
+/// [EigenDAWitness::canonicalize]
+fn canonical_order<T>(
+    layer: &'static str,
+    previous_digests: &[B256],
+    entries: Vec<T>,
+    digest: impl Fn(&T) -> B256,
+) -> Result<Vec<T>, WitnessError> {
+    // number of occurrences of a cert in the previous layer and in the entries
+    let mut occurrences: BTreeMap<B256, (usize, usize)> = BTreeMap::new();
+    for previous_digest in previous_digests {
+        occurrences.entry(*previous_digest).or_default().0 += 1;
+    }
+    let mut entries: Vec<(B256, Option<T>)> = entries
+        .into_iter()
+        .map(|entry| (digest(&entry), Some(entry)))
+        .collect();
+    for (entry_digest, _) in &entries {
+        occurrences.entry(*entry_digest).or_default().1 += 1;
+    }
+    for (entry_digest, (num_previous, num_entries)) in &occurrences {
+        if num_entries > num_previous {
+            return Err(WitnessError::UnmatchedEntry {
+                layer,
+                digest: *entry_digest,
+            });
+        }
+        if *num_entries != 0 && num_entries < num_previous && *num_previous > 1 {
+            return Err(WitnessError::AmbiguousOrder {
+                layer,
+                digest: *entry_digest,
+            });
+        }
+    }
+
+    // entries of the same cert are interchangeable, each occurrence takes the first one left
+    let mut ordered = Vec::with_capacity(entries.len());
+    for previous_digest in previous_digests {
+        if let Some((_, entry)) = entries
+            .iter_mut()
+            .find(|(entry_digest, entry)| entry_digest == previous_digest && entry.is_some())
+        {
+            ordered.extend(entry.take());
+        }
+    }
+    Ok(ordered)
+}
+
+/// WitnessEnvelope carries a witness along with its checksum, such that a witness corrupted in transit
+/// between the witgen and the prover machine is detected before being used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WitnessEnvelope {
+    /// the carried witness
+    pub witness: EigenDAWitness,
+    /// checksum of the witness computed by the sender, see [EigenDAWitness::checksum]
+    pub checksum: B256,
+}
+
+impl WitnessEnvelope {
+    /// Wraps the witness along with its checksum
+    pub fn new(witness: EigenDAWitness) -> Self {
+        let checksum = witness.checksum();
+        Self { witness, checksum }
+    }
+
+    /// Returns the witness once its checksum is verified against the one computed by the sender
+    pub fn open(self) -> Result<EigenDAWitness, WitnessError> {
+        let checksum = self.witness.checksum();
+        if checksum != self.checksum {
+            return Err(WitnessError::ChecksumMismatch {
+                expected: self.checksum,
+                found: checksum,
+            });
+        }
+        Ok(self.witness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eigenda_cert::test_utils::v3_altda_commitment;
+
+    #[test]
+    fn test_summary() {
+        let altda_commitment = v3_altda_commitment();
+        let encoded_payload = EncodedPayload {
+            encoded_payload: vec![7u8; 64].into(),
+        };
+
+        let witness = EigenDAWitness {
+            version: CURRENT_WITNESS_VERSION,
+            recencies: vec![(altda_commitment.clone(), 1); 3],
+            validities: vec![(altda_commitment.clone(), CertValidity::default()); 3],
+            encoded_payloads: vec![
+                (altda_commitment.clone(), encoded_payload, FixedBytes::ZERO);
+                2
+            ],
+            canoe_proof_bytes: vec![vec![9u8; 1024]],
+        };
+
+        let summary = witness.summary();
+        assert_eq!(
+            summary,
+            "EigenDAWitness{recencies:3, validities:3, payloads:2(128B), proofs:1(1024B)}"
+        );
+        // no raw bytes of the payloads leak into the summary
+        assert!(!summary.contains("0x0707"));
+
+        assert_eq!(
+            EigenDAWitness::default().summary(),
+            "EigenDAWitness{recencies:0, validities:0, payloads:0(0B), proofs:0(0B)}"
+        );
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let altda_commitment = v3_altda_commitment();
+        let witness = EigenDAWitness {
+            recencies: vec![(altda_commitment.clone(), 1)],
+            validities: vec![(altda_commitment, CertValidity::default())],
+            canoe_proof_bytes: vec![vec![9u8; 4]],
+            ..Default::default()
+        };
+
+        let decoded = EigenDAWitness::decode(&witness.encode().unwrap()).unwrap();
+        assert_eq!(decoded.version, CURRENT_WITNESS_VERSION);
+        assert_eq!(decoded.summary(), witness.summary());
+
+        assert!(matches!(
+            EigenDAWitness::decode(b"not a witness"),
+            Err(WitnessError::Serialization(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_version_mismatch() {
+        // layout of the witness before versioning was introduced
+        #[derive(Serialize)]
+        struct EigenDAWitnessV0 {
+            recencies: Vec<(AltDACommitment, u64)>,
+            validities: Vec<(AltDACommitment, CertValidity)>,
+            encoded_payloads: Vec<(AltDACommitment, EncodedPayload, FixedBytes<64>)>,
+            canoe_proof_bytes: Option<Vec<u8>>,
+        }
+
+        let v0_witness = EigenDAWitnessV0 {
+            recencies: vec![(v3_altda_commitment(), 1)],
+            validities: vec![],
+            encoded_payloads: vec![],
+            canoe_proof_bytes: None,
+        };
+        let bytes = serde_json::to_vec(&v0_witness).unwrap();
+
+        let err = EigenDAWitness::decode(&bytes).unwrap_err();
+        assert_eq!(
+            err,
+            WitnessError::VersionMismatch {
+                found: 0,
+                expected: CURRENT_WITNESS_VERSION,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            format!("eigenda witness version 0 is not supported, expected version {CURRENT_WITNESS_VERSION}, the witness must be regenerated")
+        );
+
+        // a witness from a future layout is rejected too
+        let future_witness = EigenDAWitness {
+            version: CURRENT_WITNESS_VERSION + 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            EigenDAWitness::decode(&future_witness.encode().unwrap()).unwrap_err(),
+            WitnessError::VersionMismatch {
+                found: CURRENT_WITNESS_VERSION + 1,
+                expected: CURRENT_WITNESS_VERSION,
+            }
+        );
+    }
+
+    #[test]
+    fn test_witness_envelope() {
+        let altda_commitment = v3_altda_commitment();
+        let witness = EigenDAWitness {
+            recencies: vec![(altda_commitment.clone(), 1)],
+            validities: vec![(altda_commitment, CertValidity::default())],
+            canoe_proof_bytes: vec![vec![9u8; 4]],
+            ..Default::default()
+        };
+        assert_eq!(witness.checksum(), witness.clone().checksum());
+
+        let envelope = WitnessEnvelope::new(witness.clone());
+        assert_eq!(
+            envelope.clone().open().unwrap().summary(),
+            witness.summary()
+        );
+
+        // the witness is altered after the checksum is computed
+        let mut corrupted = envelope.clone();
+        corrupted.witness.recencies[0].1 = 2;
+        assert_eq!(
+            corrupted.open().unwrap_err(),
+            WitnessError::ChecksumMismatch {
+                expected: envelope.checksum,
+                found: {
+                    let mut witness = witness.clone();
+                    witness.recencies[0].1 = 2;
+                    witness.checksum()
+                },
+            }
+        );
+
+        // the checksum is altered
+        let mut corrupted = envelope;
+        corrupted.checksum = B256::ZERO;
+        assert!(matches!(
+            corrupted.open(),
+            Err(WitnessError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_canonicalize_errors() {
+        let first = v3_altda_commitment();
+        let mut second = first.clone();
+        second.da_layer_byte = 1;
+        let validity = CertValidity::default();
+
+        // a validity of a cert absent from recencies
+        let mut witness = EigenDAWitness {
+            recencies: vec![(first.clone(), 1)],
+            validities: vec![(second.clone(), validity.clone())],
+            ..Default::default()
+        };
+        assert_eq!(
+            witness.canonicalize(),
+            Err(WitnessError::UnmatchedEntry {
+                layer: "validities",
+                digest: second.to_digest(),
+            })
+        );
+
+        // a cert stale at one occurrence only leaves the position of its validity unknown
+        let mut witness = EigenDAWitness {
+            recencies: vec![(first.clone(), 1), (second.clone(), 1), (first.clone(), 1)],
+            validities: vec![
+                (second.clone(), validity.clone()),
+                (first.clone(), validity),
+            ],
+            ..Default::default()
+        };
+        let before = witness.checksum();
+        assert_eq!(
+            witness.canonicalize(),
+            Err(WitnessError::AmbiguousOrder {
+                layer: "validities",
+                digest: first.to_digest(),
+            })
+        );
+        assert_eq!(witness.checksum(), before);
+    }
+
+    #[test]
+    fn test_canoe_proof_subsets() {
+        let altda_commitment = v3_altda_commitment();
+        let first = CertValidity::default();
+        let mut second = first.clone();
+        second.l1_head_block_hash = B256::repeat_byte(2);
+        let mut witness = EigenDAWitness {
+            validities: vec![
+                (altda_commitment.clone(), first.clone()),
+                (altda_commitment.clone(), second.clone()),
+                (altda_commitment.clone(), first.clone()),
+            ],
+            ..Default::default()
+        };
+
+        // a verifier without proof, then a single proof, check all validities
+        for canoe_proof_bytes in [vec![], vec![vec![1u8]]] {
+            witness.canoe_proof_bytes = canoe_proof_bytes.clone();
+            assert_eq!(
+                witness.canoe_proof_subsets().unwrap(),
+                vec![(
+                    witness.validities.clone(),
+                    canoe_proof_bytes.first().cloned()
+                )]
+            );
+        }
+
+        // one proof per l1 anchor, in order of first occurrence
+        witness.canoe_proof_bytes = vec![vec![1u8], vec![2u8]];
+        assert_eq!(
+            witness.canoe_proof_subsets().unwrap(),
+            vec![
+                (
+                    vec![
+                        (altda_commitment.clone(), first.clone()),
+                        (altda_commitment.clone(), first),
+                    ],
+                    Some(vec![1u8])
+                ),
+                (vec![(altda_commitment, second)], Some(vec![2u8])),
+            ]
+        );
+
+        witness.canoe_proof_bytes = vec![vec![1u8], vec![2u8], vec![3u8]];
+        assert_eq!(
+            witness.canoe_proof_subsets(),
+            Err(WitnessError::CanoeProofCountMismatch {
+                num_proofs: 3,
+                num_anchors: 2,
+            })
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_json_path() {
+        let altda_commitment = v3_altda_commitment();
+        let witness = EigenDAWitness {
+            recencies: vec![(altda_commitment.clone(), 1)],
+            validities: vec![(altda_commitment.clone(), CertValidity::default())],
+            encoded_payloads: vec![(
+                altda_commitment,
+                EncodedPayload {
+                    encoded_payload: vec![7u8; 64].into(),
+                },
+                FixedBytes::ZERO,
+            )],
+            canoe_proof_bytes: vec![vec![9u8; 4]],
+            ..Default::default()
+        };
+
+        let path = std::env::temp_dir().join(format!(
+            "hokulea-witness-{}-{}.json",
+            std::process::id(),
+            witness.checksum()
+        ));
+        std::fs::write(&path, witness.encode().unwrap()).unwrap();
+        let loaded = EigenDAWitness::from_json_path(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.checksum(), witness.checksum());
+        assert_eq!(loaded.summary(), witness.summary());
+
+        assert!(matches!(
+            EigenDAWitness::from_json_path(&path),
+            Err(WitnessError::Io(_))
+        ));
+    }
 }
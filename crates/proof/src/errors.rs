@@ -1,4 +1,5 @@
 use alloc::string::ToString;
+use alloy_primitives::B256;
 use hokulea_eigenda::HokuleaErrorKind;
 use kona_preimage::errors::PreimageOracleError;
 
@@ -12,6 +13,14 @@ pub enum HokuleaOracleProviderError {
     /// <https://github.com/op-rs/kona/blob/174b2ac5ad3756d4469553c7777b04056f9d151c/crates/proof/proof/src/errors.rs#L18>
     #[error("Preimage oracle error: {0}")]
     Preimage(#[from] PreimageOracleError),
+    /// The validity preimage of a cert is not available although the cert was hinted. Unlike an
+    /// invalid cert, this means the host has not processed the cert, i.e. the host and client are out of sync
+    #[error("Validity preimage is missing, the host may not have processed the cert")]
+    MissingValidityPreimage,
+    /// The cert declares a blob of zero field elements, whereas a blob holds at least the header
+    /// field element of the encoded payload
+    #[error("Cert declares a blob of zero field elements")]
+    ZeroLengthBlob,
 }
 
 impl From<HokuleaOracleProviderError> for HokuleaErrorKind {
@@ -23,6 +32,88 @@ impl From<HokuleaOracleProviderError> for HokuleaErrorKind {
             // in kona, all Preimage error are grouped into backend error <https://github.com/op-rs/kona/blob/4ef01882824b84d078ead9f834f4f78213dd6ef3/crates/protocol/derive/src/sources/blobs.rs#L136>
             // which is considered a temp issue
             HokuleaOracleProviderError::Preimage(e) => HokuleaErrorKind::Temporary(e.to_string()),
+            // same as other backend error, the host may still populate the preimage later
+            HokuleaOracleProviderError::MissingValidityPreimage => {
+                HokuleaErrorKind::Temporary(val.to_string())
+            }
+            // the cert is at fault rather than the host, so it is dropped like any ill-formed cert
+            HokuleaOracleProviderError::ZeroLengthBlob => {
+                HokuleaErrorKind::Discard(val.to_string())
+            }
         }
     }
 }
+
+/// Errors of the non panicking getters of [crate::preloaded_eigenda_provider::PreloadedEigenDAPreimageProvider]
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum PreloadedProviderError {
+    /// All preimages of the requested kind have been consumed
+    #[error("preloaded eigenda preimage provider has no preimage left")]
+    Empty,
+    /// The next stored preimage belongs to another altda commitment, identified by digests
+    #[error("preloaded eigenda preimage provider does not match altda commitment requested from derivation pipeline, requested altda commitment is {}, stored is {}", eigenda_cert::short_digest_hex(.requested), eigenda_cert::short_digest_hex(.stored))]
+    Mismatch {
+        /// digest of the altda commitment requested by the derivation pipeline
+        requested: B256,
+        /// digest of the altda commitment of the next stored preimage
+        stored: B256,
+    },
+}
+
+/// Errors of encoding and decoding [crate::eigenda_witness::EigenDAWitness]
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum WitnessError {
+    /// The witness was produced with a layout this crate does not read
+    #[error("eigenda witness version {found} is not supported, expected version {expected}, the witness must be regenerated")]
+    VersionMismatch {
+        /// version carried by the serialized witness
+        found: u32,
+        /// version read by this crate, see [crate::eigenda_witness::CURRENT_WITNESS_VERSION]
+        expected: u32,
+    },
+    /// The witness does not match the checksum computed by its sender
+    #[error(
+        "eigenda witness checksum {found} differs from checksum {expected} computed by the sender"
+    )]
+    ChecksumMismatch {
+        /// checksum computed by the sender
+        expected: B256,
+        /// checksum of the received witness
+        found: B256,
+    },
+    /// The witness bytes are malformed
+    #[error("unable to serialize or deserialize eigenda witness: {0}")]
+    Serialization(alloc::string::String),
+    /// The witness file cannot be read
+    #[error("unable to read eigenda witness file: {0}")]
+    Io(alloc::string::String),
+    /// An entry cannot be matched with an entry of the same cert in the previous layer, see
+    /// [crate::eigenda_witness::EigenDAWitness::canonicalize]
+    #[error("eigenda witness has {layer} entries for cert {digest} without a matching entry in the previous layer")]
+    UnmatchedEntry {
+        /// field of the witness holding the entry
+        layer: &'static str,
+        /// digest of the altda commitment of the entry
+        digest: B256,
+    },
+    /// The cert occurs several times in the previous layer, but only some occurrences have an
+    /// entry, such that the position of the entries in derivation order is unknown
+    #[error("eigenda witness has {layer} entries for cert {digest} with an ambiguous order")]
+    AmbiguousOrder {
+        /// field of the witness holding the entry
+        layer: &'static str,
+        /// digest of the altda commitment of the entry
+        digest: B256,
+    },
+    /// The witness holds several canoe proofs, but not one per l1 anchor of its validities, see
+    /// [crate::eigenda_witness::EigenDAWitness::canoe_proof_subsets]
+    #[error(
+        "eigenda witness has {num_proofs} canoe proofs for validities at {num_anchors} l1 anchors"
+    )]
+    CanoeProofCountMismatch {
+        /// number of canoe proofs of the witness
+        num_proofs: usize,
+        /// number of distinct (l1_chain_id, l1_head_block_hash) of the validities
+        num_anchors: usize,
+    },
+}
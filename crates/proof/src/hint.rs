@@ -12,6 +12,9 @@ use alloc::str::FromStr;
 pub enum ExtendedHintType {
     Original(HintType),
     EigenDACert,
+    /// An altda commitment followed by the 8 bytes big endian number of the l1 block it is
+    /// included in, such that the host can skip the payload of a cert which is not recent
+    EigenDACertWithL1Inclusion,
 }
 
 impl ExtendedHintType {
@@ -28,6 +31,7 @@ impl FromStr for ExtendedHintType {
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
             "eigenda-certificate" => Ok(Self::EigenDACert),
+            "eigenda-certificate-with-l1-inclusion" => Ok(Self::EigenDACertWithL1Inclusion),
 
             _ => Ok(Self::Original(HintType::from_str(value)?)),
         }
@@ -38,6 +42,7 @@ impl From<ExtendedHintType> for &str {
     fn from(value: ExtendedHintType) -> Self {
         match value {
             ExtendedHintType::EigenDACert => "eigenda-certificate",
+            ExtendedHintType::EigenDACertWithL1Inclusion => "eigenda-certificate-with-l1-inclusion",
             ExtendedHintType::Original(hint_type) => hint_type.into(),
         }
     }
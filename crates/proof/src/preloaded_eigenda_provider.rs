@@ -1,5 +1,5 @@
 use crate::eigenda_witness::EigenDAWitness;
-use crate::errors::HokuleaOracleProviderError;
+use crate::errors::{HokuleaOracleProviderError, PreloadedProviderError};
 use alloy_primitives::FixedBytes;
 use ark_bn254::{Fq, G1Affine};
 use ark_ff::PrimeField;
@@ -52,7 +52,9 @@ impl PreloadedEigenDAPreimageProvider {
     /// the vailidity corresponding to it stil remains in the vec. If it is the last altda commitment
     /// the validity is left unused. If it is not the last, the next altda commitment will panic
     /// due to unmatched key.
-    /// The Canoe proof validates all the validity all at once.
+    /// The Canoe proof validates all the validity all at once, or each of several independent
+    /// canoe proofs validates the validities at its l1 anchor, see
+    /// [EigenDAWitness::canoe_proof_subsets].
     pub fn from_witness(
         value: EigenDAWitness,
         canoe_verifier: impl CanoeVerifier,
@@ -71,13 +73,18 @@ impl PreloadedEigenDAPreimageProvider {
         // check all altda commitment validity are supported by zk validity proof
         let mut validity_entries = vec![];
 
-        // if the number of da cert is non-zero, verify the single canoe proof, regardless if the
+        // if the number of da cert is non-zero, verify the canoe proofs, regardless if the
         // da cert is valid or not. Otherwise, skip the verification
         if !value.validities.is_empty() {
-            // check cert validity altogether in one verification
-            canoe_verifier
-                .validate_cert_receipt(value.validities.clone(), value.canoe_proof_bytes)
-                .expect("verification should have been passing");
+            let subsets = value
+                .canoe_proof_subsets()
+                .expect("canoe proofs should cover the l1 anchors of the validities");
+            // check the cert validities of each proof in one verification
+            for (validities, canoe_proof_bytes) in subsets {
+                canoe_verifier
+                    .validate_cert_receipt(validities, canoe_proof_bytes)
+                    .expect("verification should have been passing");
+            }
         }
 
         for (altda_commitment, cert_validity) in &value.validities {
@@ -96,6 +103,18 @@ impl PreloadedEigenDAPreimageProvider {
             // populate entries ahead of time, if something is invalid, batch_verify will abort
             encoded_payload_entries.push((cert.clone(), encoded_payload.clone()));
 
+            // the kzg commitment of the cert is over a blob of the length declared by the cert, a
+            // payload of another length would otherwise only surface as a failed batch_verify
+            let num_field_elements = cert.get_num_field_element().next_power_of_two();
+            assert_eq!(
+                encoded_payload.len_symbols() as usize,
+                num_field_elements,
+                "encoded payload of cert {} has {} field elements, but the cert declares a blob of {} field elements",
+                cert.digest_hex_short(),
+                encoded_payload.len_symbols(),
+                num_field_elements,
+            );
+
             // gather kzg commitment and proof for batch verification
             let blob =
                 Blob::new(encoded_payload.serialize()).expect("should be able to construct a blob");
@@ -121,6 +140,49 @@ impl PreloadedEigenDAPreimageProvider {
             encoded_payload_entries,
         }
     }
+
+    /// Pops the next recency window, which must belong to the altda commitment. Unlike
+    /// [EigenDAPreimageProvider::get_recency_window], it returns an error instead of panicking,
+    /// for hosts and tools that reuse the provider for simulation
+    pub fn try_get_recency_window(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<u64, PreloadedProviderError> {
+        pop_entry(&mut self.recency_entries, altda_commitment)
+    }
+
+    /// Pops the next cert validity, see [PreloadedEigenDAPreimageProvider::try_get_recency_window]
+    pub fn try_get_validity(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<bool, PreloadedProviderError> {
+        pop_entry(&mut self.validity_entries, altda_commitment)
+    }
+
+    /// Pops the next encoded payload, see [PreloadedEigenDAPreimageProvider::try_get_recency_window]
+    pub fn try_get_encoded_payload(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<EncodedPayload, PreloadedProviderError> {
+        pop_entry(&mut self.encoded_payload_entries, altda_commitment)
+    }
+}
+
+/// Pops the last entry, the entries are stored in reverse order. The entry is consumed even if it
+/// belongs to another altda commitment
+fn pop_entry<T>(
+    entries: &mut Vec<(AltDACommitment, T)>,
+    altda_commitment: &AltDACommitment,
+) -> Result<T, PreloadedProviderError> {
+    let (stored_altda_commitment, value) = entries.pop().ok_or(PreloadedProviderError::Empty)?;
+    if stored_altda_commitment == *altda_commitment {
+        Ok(value)
+    } else {
+        Err(PreloadedProviderError::Mismatch {
+            requested: altda_commitment.to_digest(),
+            stored: stored_altda_commitment.to_digest(),
+        })
+    }
 }
 
 #[async_trait]
@@ -128,47 +190,33 @@ impl EigenDAPreimageProvider for PreloadedEigenDAPreimageProvider {
     // The error is a place holder, we intentionally abort everything
     type Error = HokuleaOracleProviderError;
 
+    // It is safe to abort in all getters below, because zkVM is not given the correct preimage to
+    // start with, stop early
     async fn get_recency_window(
         &mut self,
         altda_commitment: &AltDACommitment,
     ) -> Result<u64, Self::Error> {
-        let (stored_altda_commitment, recency) = self.recency_entries.pop().unwrap();
-        if stored_altda_commitment == *altda_commitment {
-            Ok(recency)
-        } else {
-            // It is safe to abort here, because zkVM is not given the correct preimage to start with, stop early
-            panic!("preloaded eigenda preimage provider does not match altda commitment requested from derivation pipeline
-                requested altda commitment is {:?}, stored is {:?}", altda_commitment.to_digest(), stored_altda_commitment.to_digest());
-        }
+        Ok(self
+            .try_get_recency_window(altda_commitment)
+            .expect("preloaded eigenda preimage provider cannot serve the recency window"))
     }
 
     async fn get_validity(
         &mut self,
         altda_commitment: &AltDACommitment,
     ) -> Result<bool, Self::Error> {
-        let (stored_altda_commitment, validity) = self.validity_entries.pop().unwrap();
-        if stored_altda_commitment == *altda_commitment {
-            Ok(validity)
-        } else {
-            // It is safe to abort here, because zkVM is not given the correct preimage to start with, stop early
-            panic!("preloaded eigenda preimage provider does not match altda commitment requested from derivation pipeline
-                requested altda commitment is {:?}, stored is {:?}", altda_commitment.to_digest(), stored_altda_commitment.to_digest());
-        }
+        Ok(self
+            .try_get_validity(altda_commitment)
+            .expect("preloaded eigenda preimage provider cannot serve the validity"))
     }
 
     async fn get_encoded_payload(
         &mut self,
         altda_commitment: &AltDACommitment,
     ) -> Result<EncodedPayload, Self::Error> {
-        let (stored_altda_commitment, encoded_payload) =
-            self.encoded_payload_entries.pop().unwrap();
-        if stored_altda_commitment == *altda_commitment {
-            Ok(encoded_payload)
-        } else {
-            // It is safe to abort here, because zkVM is not given the correct preimage to start with, stop early
-            panic!("preloaded preimage provider does not match altda commitment requested from derivation pipeline
-                requested altda commitment is {:?}, stored is {:?}", altda_commitment.to_digest(), stored_altda_commitment.to_digest());
-        }
+        Ok(self
+            .try_get_encoded_payload(altda_commitment)
+            .expect("preloaded eigenda preimage provider cannot serve the encoded payload"))
     }
 }
 
@@ -179,16 +227,7 @@ pub fn batch_verify(blobs: &[Blob], commitments: &[G1Point], proofs: &[FixedByte
     // transform to rust-kzg-bn254 inputs types
     // TODO should make library do the parsing the return result
     let lib_blobs: &[Blob] = blobs;
-    let lib_commitments: Vec<G1Affine> = commitments
-        .iter()
-        .map(|c| {
-            let a: [u8; 32] = c.x.to_be_bytes();
-            let b: [u8; 32] = c.y.to_be_bytes();
-            let x = Fq::from_be_bytes_mod_order(&a);
-            let y = Fq::from_be_bytes_mod_order(&b);
-            G1Affine::new(x, y)
-        })
-        .collect();
+    let lib_commitments: Vec<G1Affine> = commitments.iter().map(G1Point::to_g1_affine).collect();
     let lib_proofs: Vec<G1Affine> = proofs
         .iter()
         .map(|p| {
@@ -206,10 +245,11 @@ pub fn batch_verify(blobs: &[Blob], commitments: &[G1Point], proofs: &[FixedByte
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::eigenda_witness::CURRENT_WITNESS_VERSION;
     use alloc::vec;
-    use alloy_primitives::{hex, Bytes, U256};
-    use canoe_verifier::{CanoeNoOpVerifier, CertValidity};
-    use eigenda_cert::AltDACommitment;
+    use alloy_primitives::{hex, B256, U256};
+    use canoe_verifier::{CanoeMockVerifier, CanoeNoOpVerifier, CanoeProofBackend, CertValidity};
+    use eigenda_cert::test_utils::v3_altda_commitment;
     use num::BigUint;
     use rust_kzg_bn254_primitives::errors::KzgError;
     use rust_kzg_bn254_primitives::helpers::read_g1_point_from_bytes_be;
@@ -289,8 +329,7 @@ mod tests {
             2, 2, 2, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
             1, 1, 1, 1, 1, 1,
         ];
-        let calldata: Bytes = alloy_primitives::hex::decode("0x010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001").unwrap().into();
-        let mut altda_commitment: AltDACommitment = calldata[..].try_into().unwrap();
+        let mut altda_commitment = v3_altda_commitment();
 
         let claimed_false_cert_validity = CertValidity::default();
         let mut claimed_true_cert_validity = claimed_false_cert_validity.clone();
@@ -299,12 +338,19 @@ mod tests {
         let (_, commitment, proof) =
             compute_kzg_proof_and_commitment(encoded_payload_inner.clone());
         match &mut altda_commitment.versioned_cert {
+            eigenda_cert::EigenDAVersionedCert::V1(_) => unreachable!("fixture is a V2 cert"),
             eigenda_cert::EigenDAVersionedCert::V2(c) => {
                 c.blob_inclusion_info
                     .blob_certificate
                     .blob_header
                     .commitment
                     .commitment = commitment;
+                // the encoded payload consists of 2 field elements
+                c.blob_inclusion_info
+                    .blob_certificate
+                    .blob_header
+                    .commitment
+                    .length = 2;
             }
             eigenda_cert::EigenDAVersionedCert::V3(c) => {
                 c.blob_inclusion_info
@@ -312,10 +358,17 @@ mod tests {
                     .blob_header
                     .commitment
                     .commitment = commitment;
+                // the encoded payload consists of 2 field elements
+                c.blob_inclusion_info
+                    .blob_certificate
+                    .blob_header
+                    .commitment
+                    .length = 2;
             }
         };
 
         EigenDAWitness {
+            version: CURRENT_WITNESS_VERSION,
             recencies: vec![(altda_commitment.clone(), 1)],
             validities: vec![(altda_commitment.clone(), claimed_true_cert_validity.clone())],
             encoded_payloads: vec![(
@@ -325,7 +378,7 @@ mod tests {
                 },
                 proof,
             )],
-            canoe_proof_bytes: Some(Vec::new()),
+            canoe_proof_bytes: Vec::new(),
         }
     }
 
@@ -426,6 +479,171 @@ mod tests {
         let _ = preimage.get_encoded_payload(&altda_commitment).await;
     }
 
+    #[tokio::test]
+    async fn test_from_witness_after_canonicalize() {
+        let ok_data = prepare_ok_data();
+        let first = ok_data.encoded_payloads[0].clone();
+        let cert_validity = ok_data.validities[0].1.clone();
+
+        // a second cert whose blob is another valid encoded payload
+        let encoded_payload_inner = vec![
+            0, 0, 0, 0, 0, 31, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 0, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+            4, 4, 4, 4, 4, 4,
+        ];
+        let (_, commitment, proof) =
+            compute_kzg_proof_and_commitment(encoded_payload_inner.clone());
+        let mut second_cert = first.0.clone();
+        if let eigenda_cert::EigenDAVersionedCert::V3(c) = &mut second_cert.versioned_cert {
+            c.blob_inclusion_info
+                .blob_certificate
+                .blob_header
+                .commitment
+                .commitment = commitment;
+        }
+        let second = (
+            second_cert,
+            EncodedPayload {
+                encoded_payload: encoded_payload_inner.into(),
+            },
+            proof,
+        );
+
+        let witness = EigenDAWitness {
+            recencies: vec![(first.0.clone(), 1), (second.0.clone(), 1)],
+            validities: vec![
+                (first.0.clone(), cert_validity.clone()),
+                (second.0.clone(), cert_validity),
+            ],
+            encoded_payloads: vec![first.clone(), second.clone()],
+            ..ok_data
+        };
+
+        // entries reordered by a tool reserializing the witness
+        let mut shuffled = witness.clone();
+        shuffled.validities.reverse();
+        shuffled.encoded_payloads.reverse();
+        assert_ne!(shuffled.checksum(), witness.checksum());
+        shuffled.canonicalize().unwrap();
+        assert_eq!(shuffled.checksum(), witness.checksum());
+
+        let mut preimage =
+            PreloadedEigenDAPreimageProvider::from_witness(shuffled, CanoeNoOpVerifier {});
+        for (altda_commitment, encoded_payload, _) in [first, second] {
+            assert_eq!(preimage.try_get_recency_window(&altda_commitment), Ok(1));
+            assert_eq!(preimage.try_get_validity(&altda_commitment), Ok(true));
+            assert_eq!(
+                preimage.try_get_encoded_payload(&altda_commitment),
+                Ok(encoded_payload)
+            );
+        }
+    }
+
+    // the cert is included twice, and its validities are attested at two l1 anchors by two
+    // independent proofs
+    fn prepare_data_with_two_canoe_proofs() -> EigenDAWitness {
+        let mut ok_data = prepare_ok_data();
+        let altda_commitment = ok_data.validities[0].0.clone();
+        let mut cert_validity = ok_data.validities[0].1.clone();
+        cert_validity.l1_head_block_hash = B256::repeat_byte(2);
+        cert_validity.claimed_validity = false;
+
+        ok_data.recencies.push((altda_commitment.clone(), 1));
+        ok_data
+            .validities
+            .push((altda_commitment.clone(), cert_validity));
+
+        let canoe_verifier = CanoeMockVerifier::default();
+        ok_data.canoe_proof_bytes = ok_data
+            .validities
+            .iter()
+            .map(|pair| {
+                let journals_bytes = canoe_verifier.to_journals_bytes(vec![pair.clone()]);
+                CanoeProofBackend::Mock.tag(serde_json::to_vec(&journals_bytes).unwrap())
+            })
+            .collect();
+        ok_data
+    }
+
+    #[test]
+    fn test_from_witness_two_canoe_proofs() {
+        let eigenda_witness = prepare_data_with_two_canoe_proofs();
+        let altda_commitment = eigenda_witness.recencies[0].0.clone();
+
+        let mut preimage = PreloadedEigenDAPreimageProvider::from_witness(
+            eigenda_witness,
+            CanoeMockVerifier::default(),
+        );
+        assert_eq!(preimage.try_get_validity(&altda_commitment), Ok(true));
+        assert_eq!(preimage.try_get_validity(&altda_commitment), Ok(false));
+    }
+
+    // each proof covers the validities at its l1 anchor, not the ones of the other proof
+    #[test]
+    #[should_panic]
+    fn test_from_witness_two_canoe_proofs_swapped() {
+        let mut eigenda_witness = prepare_data_with_two_canoe_proofs();
+        eigenda_witness.canoe_proof_bytes.reverse();
+        PreloadedEigenDAPreimageProvider::from_witness(
+            eigenda_witness,
+            CanoeMockVerifier::default(),
+        );
+    }
+
+    #[test]
+    fn test_try_get_empty() {
+        let eigenda_witness = prepare_ok_data();
+        let altda_commitment = eigenda_witness.recencies[0].0.clone();
+        let mut preimage = PreloadedEigenDAPreimageProvider::default();
+
+        assert_eq!(
+            preimage.try_get_recency_window(&altda_commitment),
+            Err(PreloadedProviderError::Empty)
+        );
+        assert_eq!(
+            preimage.try_get_validity(&altda_commitment),
+            Err(PreloadedProviderError::Empty)
+        );
+        assert_eq!(
+            preimage.try_get_encoded_payload(&altda_commitment),
+            Err(PreloadedProviderError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_try_get_mismatch() {
+        let eigenda_witness = prepare_ok_data();
+        let stored = eigenda_witness.recencies[0].0.clone();
+        let mut requested = stored.clone();
+        requested.da_layer_byte = 255;
+        let mismatch = PreloadedProviderError::Mismatch {
+            requested: requested.to_digest(),
+            stored: stored.to_digest(),
+        };
+        // the message identifies both certs by the short form of their digest
+        let message = mismatch.to_string();
+        assert!(message.contains(&requested.digest_hex_short()));
+        assert!(message.contains(&stored.digest_hex_short()));
+        assert!(!message.contains(&alloy_primitives::hex::encode(stored.to_digest())));
+
+        let mut preimage =
+            PreloadedEigenDAPreimageProvider::from_witness(eigenda_witness, CanoeNoOpVerifier {});
+        assert_eq!(
+            preimage.try_get_recency_window(&requested).unwrap_err(),
+            mismatch
+        );
+        assert_eq!(preimage.try_get_validity(&requested).unwrap_err(), mismatch);
+        assert_eq!(
+            preimage.try_get_encoded_payload(&requested).unwrap_err(),
+            mismatch
+        );
+        // the mismatched entry is consumed
+        assert_eq!(
+            preimage.try_get_recency_window(&stored),
+            Err(PreloadedProviderError::Empty)
+        );
+    }
+
     // unknown key
     #[tokio::test]
     #[should_panic]
@@ -506,4 +724,29 @@ mod tests {
             CanoeNoOpVerifier {},
         );
     }
+
+    // the cert declares a blob of 4 field elements, whereas the encoded payload has 2
+    #[tokio::test]
+    #[should_panic(
+        expected = "has 2 field elements, but the cert declares a blob of 4 field elements"
+    )]
+    async fn test_from_witness_encoded_payload_length_mismatch() {
+        let mut eigenda_witness = prepare_ok_data();
+        let mut altda_commitment = eigenda_witness.encoded_payloads[0].0.clone();
+        match &mut altda_commitment.versioned_cert {
+            eigenda_cert::EigenDAVersionedCert::V3(c) => {
+                c.blob_inclusion_info
+                    .blob_certificate
+                    .blob_header
+                    .commitment
+                    .length = 4;
+            }
+            _ => unreachable!("fixture is a V3 cert"),
+        };
+        eigenda_witness.encoded_payloads[0].0 = altda_commitment;
+        let _ = PreloadedEigenDAPreimageProvider::from_witness(
+            eigenda_witness.clone(),
+            CanoeNoOpVerifier {},
+        );
+    }
 }
@@ -3,6 +3,7 @@ use alloy_rlp::Decodable;
 use canoe_provider::{CanoeInput, CanoeProvider};
 use canoe_verifier_address_fetcher::CanoeVerifierAddressFetcher;
 use core::fmt::Debug;
+use eigenda_cert::G1Point;
 use hokulea_proof::eigenda_witness::EigenDAWitness;
 use kona_preimage::{CommsClient, PreimageKey};
 use kona_proof::{BootInfo, FlushableCache};
@@ -48,7 +49,10 @@ where
         info!(target: "canoe witness provider", "producing 1 canoe proof for {} DA certs", wit.validities.len());
     }
 
-    for (altda_commitment, cert_validity) in &mut wit.validities {
+    let expected_kzg_commitments = expected_kzg_commitments(&wit);
+    for ((altda_commitment, cert_validity), expected_kzg_commitment) in
+        wit.validities.iter().zip(expected_kzg_commitments)
+    {
         let canoe_input = CanoeInput {
             altda_commitment: altda_commitment.clone(),
             claimed_validity: cert_validity.claimed_validity,
@@ -57,6 +61,7 @@ where
             l1_chain_id,
             verifier_address: canoe_address_fetcher
                 .fetch_address(l1_chain_id, &altda_commitment.versioned_cert)?,
+            expected_kzg_commitment,
         };
         canoe_inputs.push(canoe_input);
     }
@@ -72,3 +77,109 @@ where
         None => Ok(None),
     }
 }
+
+/// Returns for every validity of the witness the kzg commitment that batch_verify checks the encoded
+/// payload served for the cert against. The preloaded provider serves the encoded payloads in order,
+/// one per cert claimed valid, hence the n-th cert claimed valid is paired with the n-th encoded
+/// payload. A cert claimed invalid, or whose encoded payload is not in the witness, has none
+fn expected_kzg_commitments(witness: &EigenDAWitness) -> Vec<Option<G1Point>> {
+    let mut encoded_payloads = witness.encoded_payloads.iter();
+    witness
+        .validities
+        .iter()
+        .map(|(_, cert_validity)| {
+            if cert_validity.claimed_validity {
+                encoded_payloads
+                    .next()
+                    .map(|(altda_commitment, _, _)| altda_commitment.get_kzg_commitment())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{FixedBytes, U256};
+    use canoe_verifier::CertValidity;
+    use eigenda_cert::test_utils::v3_altda_commitment;
+    use eigenda_cert::EigenDAVersionedCert;
+    use hokulea_eigenda::EncodedPayload;
+
+    #[test]
+    fn test_expected_kzg_commitments() {
+        let valid = CertValidity {
+            claimed_validity: true,
+            ..Default::default()
+        };
+        let invalid = CertValidity::default();
+        let first = v3_altda_commitment();
+        let mut second = first.clone();
+        let mut third = first.clone();
+        for (altda_commitment, offset) in [(&mut second, 1u64), (&mut third, 2)] {
+            let EigenDAVersionedCert::V3(c) = &mut altda_commitment.versioned_cert else {
+                panic!("fixture should be a V3 cert");
+            };
+            c.blob_inclusion_info
+                .blob_certificate
+                .blob_header
+                .commitment
+                .commitment
+                .x += U256::from(offset);
+        }
+        let encoded_payload = |altda_commitment: &eigenda_cert::AltDACommitment| {
+            (
+                altda_commitment.clone(),
+                EncodedPayload::default(),
+                FixedBytes::<64>::default(),
+            )
+        };
+
+        // the second cert is claimed invalid, hence has no encoded payload
+        let mut witness = EigenDAWitness {
+            validities: vec![
+                (first.clone(), valid.clone()),
+                (second.clone(), invalid),
+                (third.clone(), valid.clone()),
+            ],
+            encoded_payloads: vec![encoded_payload(&first), encoded_payload(&third)],
+            ..Default::default()
+        };
+        assert_eq!(
+            expected_kzg_commitments(&witness),
+            vec![
+                Some(first.get_kzg_commitment()),
+                None,
+                Some(third.get_kzg_commitment())
+            ]
+        );
+
+        // a witness serving the blob of another cert yields a mismatching canoe input, which the
+        // host rejects and the zk guests refuse to prove
+        witness.encoded_payloads[1] = encoded_payload(&second);
+        let expected_kzg_commitment = expected_kzg_commitments(&witness)[2];
+        assert_eq!(expected_kzg_commitment, Some(second.get_kzg_commitment()));
+        let canoe_input = CanoeInput {
+            altda_commitment: third,
+            claimed_validity: true,
+            l1_head_block_hash: alloy_primitives::B256::repeat_byte(1),
+            l1_head_block_number: 1,
+            l1_chain_id: 1,
+            verifier_address: alloy_primitives::Address::repeat_byte(1),
+            expected_kzg_commitment,
+        };
+        assert_eq!(
+            canoe_input.validate(),
+            Err(canoe_provider::CanoeInputError::KzgCommitmentMismatch)
+        );
+
+        // the encoded payloads may stop short of the validities
+        witness.encoded_payloads.truncate(1);
+        assert_eq!(
+            expected_kzg_commitments(&witness),
+            vec![Some(first.get_kzg_commitment()), None, None]
+        );
+    }
+}
@@ -1,3 +1,4 @@
 pub mod canoe_witness_provider;
+pub mod onchain_address_fetcher;
 pub mod witness_provider;
 pub use canoe_witness_provider::from_boot_info_to_canoe_proof;
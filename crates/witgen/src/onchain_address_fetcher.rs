@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use alloy_primitives::Address;
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::{BlockId, TransactionInput, TransactionRequest};
+use alloy_sol_types::{sol, SolCall};
+use async_trait::async_trait;
+use canoe_verifier_address_fetcher::{
+    CanoeVerifierAddressFetcher, CanoeVerifierAddressFetcherDeployedByEigenLabs,
+    CanoeVerifierAddressFetcherError,
+};
+use eigenda_cert::EigenDAVersionedCert;
+use tracing::info;
+use url::Url;
+
+sol! {
+    /// The EigenDA directory contract keeps the addresses of all EigenDA contracts by name
+    function getAddress(string name) external view returns (address);
+}
+
+/// Name under which the EigenDA directory registers the cert verifier router
+pub const CERT_VERIFIER_ROUTER_NAME: &str = "CERT_VERIFIER_ROUTER";
+
+/// A registry which knows the cert verifier router of a l1 chain at a given block
+#[async_trait]
+pub trait CertVerifierRegistry: Send + Sync {
+    /// returns the cert verifier router registered at the l1 block number
+    async fn get_cert_verifier_router(&self, block_number: u64) -> anyhow::Result<Address>;
+}
+
+/// A [CertVerifierRegistry] reading the EigenDA directory contract over rpc
+#[derive(Debug, Clone)]
+pub struct RpcCertVerifierRegistry {
+    /// rpc to l1 geth node
+    pub eth_rpc_url: String,
+    /// address of the EigenDA directory contract on the l1 chain
+    pub directory_address: Address,
+}
+
+#[async_trait]
+impl CertVerifierRegistry for RpcCertVerifierRegistry {
+    async fn get_cert_verifier_router(&self, block_number: u64) -> anyhow::Result<Address> {
+        let provider = ProviderBuilder::new().connect_http(Url::from_str(&self.eth_rpc_url)?);
+        let call = getAddressCall {
+            name: CERT_VERIFIER_ROUTER_NAME.to_string(),
+        };
+        let tx = TransactionRequest::default()
+            .to(self.directory_address)
+            .input(TransactionInput::new(call.abi_encode().into()));
+        let returns = provider
+            .call(tx)
+            .block(BlockId::number(block_number))
+            .await?;
+        Ok(getAddressCall::abi_decode_returns(&returns)?)
+    }
+}
+
+/// A [CanoeVerifierAddressFetcher] serving cert verifier routers read from an on-chain registry, such
+/// that a router change does not require a new release. Because fetch_address is sync, the routers
+/// are read ahead of time by [CanoeOnChainAddressFetcher::load], usually once at host startup.
+/// V3 certs use the loaded router, V2 certs keep using the legacy cert verifiers deployed by EigenLabs,
+/// since the router only exposes the abi encode interface
+#[derive(Debug, Clone, Default)]
+pub struct CanoeOnChainAddressFetcher {
+    routers: BTreeMap<u64, Address>,
+}
+
+impl CanoeOnChainAddressFetcher {
+    /// Reads the router of the l1 chain registered at the l1 block number from the registry, and
+    /// caches it for later fetches. It can be called once per l1 chain id
+    pub async fn load(
+        mut self,
+        registry: &impl CertVerifierRegistry,
+        chain_id: u64,
+        block_number: u64,
+    ) -> anyhow::Result<Self> {
+        let router = registry.get_cert_verifier_router(block_number).await?;
+        info!(
+            target: "canoe_onchain_address_fetcher",
+            "loaded cert verifier router {} for chain id {} at block {}",
+            router,
+            chain_id,
+            block_number
+        );
+        self.routers.insert(chain_id, router);
+        Ok(self)
+    }
+}
+
+impl CanoeVerifierAddressFetcher for CanoeOnChainAddressFetcher {
+    fn fetch_address(
+        &self,
+        chain_id: u64,
+        versioned_cert: &EigenDAVersionedCert,
+    ) -> Result<Address, CanoeVerifierAddressFetcherError> {
+        match versioned_cert {
+            EigenDAVersionedCert::V3(_) => self.routers.get(&chain_id).copied().ok_or(
+                CanoeVerifierAddressFetcherError::UnknownChainIDForABIEncodeInterface(chain_id),
+            ),
+            _ => CanoeVerifierAddressFetcherDeployedByEigenLabs {}
+                .fetch_address(chain_id, versioned_cert),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eigenda_cert::test_utils::v3_altda_commitment;
+
+    // a registry serving a fixed router, recording the block it was asked at
+    struct MockRegistry {
+        router: Address,
+        block_number: std::sync::Mutex<Option<u64>>,
+    }
+
+    #[async_trait]
+    impl CertVerifierRegistry for MockRegistry {
+        async fn get_cert_verifier_router(&self, block_number: u64) -> anyhow::Result<Address> {
+            *self.block_number.lock().unwrap() = Some(block_number);
+            Ok(self.router)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_address_uses_loaded_router() {
+        let altda_commitment = v3_altda_commitment();
+
+        let registry = MockRegistry {
+            router: Address::repeat_byte(7),
+            block_number: std::sync::Mutex::new(None),
+        };
+        let fetcher = CanoeOnChainAddressFetcher::default()
+            .load(&registry, 1, 42)
+            .await
+            .unwrap();
+        assert_eq!(*registry.block_number.lock().unwrap(), Some(42));
+
+        assert_eq!(
+            fetcher
+                .fetch_address(1, &altda_commitment.versioned_cert)
+                .unwrap(),
+            registry.router
+        );
+        // the router of a chain that was never loaded is unknown, even if EigenLabs deployed one
+        assert!(matches!(
+            fetcher.fetch_address(11155111, &altda_commitment.versioned_cert),
+            Err(CanoeVerifierAddressFetcherError::UnknownChainIDForABIEncodeInterface(11155111))
+        ));
+    }
+}
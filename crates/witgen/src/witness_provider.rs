@@ -2,7 +2,8 @@ use alloy_primitives::{Address, FixedBytes, B256};
 use async_trait::async_trait;
 use canoe_verifier::CertValidity;
 use eigenda_cert::AltDACommitment;
-use hokulea_eigenda::{EigenDAPreimageProvider, EncodedPayload};
+use hokulea_compute_proof::SRS;
+use hokulea_eigenda::{EigenDAPreimageProvider, EncodedPayload, HokuleaErrorKind};
 use hokulea_proof::eigenda_witness::EigenDAWitness;
 use std::sync::{Arc, Mutex};
 
@@ -19,6 +20,38 @@ pub struct OracleEigenDAWitnessProvider<T: EigenDAPreimageProvider> {
     pub provider: T,
     /// Store witness data
     pub witness: Arc<Mutex<EigenDAWitness>>,
+    /// If true, an encoded payload that cannot be decoded halts the witness generation. Otherwise,
+    /// like in the derivation pipeline, the payload is recorded and later skipped by the data
+    /// source, such that the witness reflects the derivation without reporting the bad data
+    pub strict_decoding: bool,
+}
+
+/// Errors of the [OracleEigenDAWitnessProvider]
+#[derive(Debug, thiserror::Error)]
+pub enum WitnessProviderError<E> {
+    /// Error of the wrapped eigenda preimage provider
+    #[error("{0}")]
+    Provider(E),
+    /// The encoded payload of the cert cannot be decoded, only returned in strict decoding mode
+    #[error("encoded payload of cert {digest} cannot be decoded: {reason}")]
+    UndecodableEncodedPayload {
+        /// digest of the altda commitment whose encoded payload is undecodable
+        digest: B256,
+        /// the decoding error
+        reason: String,
+    },
+}
+
+impl<E: Into<HokuleaErrorKind>> From<WitnessProviderError<E>> for HokuleaErrorKind {
+    fn from(e: WitnessProviderError<E>) -> Self {
+        match e {
+            WitnessProviderError::Provider(e) => e.into(),
+            // the operator asked for the witness generation to stop on bad data
+            WitnessProviderError::UndecodableEncodedPayload { .. } => {
+                HokuleaErrorKind::Critical(e.to_string())
+            }
+        }
+    }
 }
 
 /// Implement EigenDAPreimageProvider for OracleEigenDAWitnessProvider
@@ -31,7 +64,7 @@ pub struct OracleEigenDAWitnessProvider<T: EigenDAPreimageProvider> {
 impl<T: EigenDAPreimageProvider + Send> EigenDAPreimageProvider
     for OracleEigenDAWitnessProvider<T>
 {
-    type Error = T::Error;
+    type Error = WitnessProviderError<T::Error>;
 
     /// Fetch primage about the recency window
     async fn get_recency_window(
@@ -45,7 +78,27 @@ impl<T: EigenDAPreimageProvider + Send> EigenDAPreimageProvider
                 witness.recencies.push((altda_commitment.clone(), recency));
                 Ok(recency)
             }
-            Err(e) => Err(e),
+            Err(e) => Err(WitnessProviderError::Provider(e)),
+        }
+    }
+
+    async fn get_recency_window_at(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+        l1_inclusion_bn: u64,
+    ) -> Result<u64, Self::Error> {
+        match self
+            .provider
+            .get_recency_window_at(altda_commitment, l1_inclusion_bn)
+            .await
+        {
+            Ok(recency) => {
+                let mut witness = self.witness.lock().unwrap();
+
+                witness.recencies.push((altda_commitment.clone(), recency));
+                Ok(recency)
+            }
+            Err(e) => Err(WitnessProviderError::Provider(e)),
         }
     }
 
@@ -72,7 +125,7 @@ impl<T: EigenDAPreimageProvider + Send> EigenDAPreimageProvider
                     .push((altda_commitment.clone(), cert_validity));
                 Ok(validity)
             }
-            Err(e) => Err(e),
+            Err(e) => Err(WitnessProviderError::Provider(e)),
         }
     }
 
@@ -83,23 +136,148 @@ impl<T: EigenDAPreimageProvider + Send> EigenDAPreimageProvider
         // only a single encoded payload is returned from a cert
         match self.provider.get_encoded_payload(altda_commitment).await {
             Ok(encoded_payload) => {
-                // Compute kzg proof for the entire encoded payload on a deterministic random point
-                let kzg_proof =
-                    match hokulea_compute_proof::compute_kzg_proof(encoded_payload.serialize()) {
-                        Ok(p) => p,
-                        Err(e) => panic!("cannot generate a kzg proof: {e}"),
-                    };
-                let fixed_bytes: FixedBytes<64> = FixedBytes::from_slice(kzg_proof.as_ref());
-
+                if self.strict_decoding {
+                    if let Err(e) = encoded_payload.decode() {
+                        return Err(WitnessProviderError::UndecodableEncodedPayload {
+                            digest: altda_commitment.to_digest(),
+                            reason: e.to_string(),
+                        });
+                    }
+                }
+                // the kzg proof is a placeholder until compute_witness_kzg_proofs runs over all
+                // encoded payloads at the end of derivation
                 let mut witness = self.witness.lock().unwrap();
                 witness.encoded_payloads.push((
                     altda_commitment.clone(),
                     encoded_payload.clone(),
-                    fixed_bytes,
+                    FixedBytes::ZERO,
                 ));
                 Ok(encoded_payload)
             }
-            Err(e) => Err(e),
+            Err(e) => Err(WitnessProviderError::Provider(e)),
         }
     }
 }
+
+/// Compute kzg proofs for all the encoded payloads collected by [OracleEigenDAWitnessProvider],
+/// each on a deterministic random point. All proofs are computed in a single batch, which must
+/// be done once the derivation is over and before the witness is handed to the zkVM
+pub fn compute_witness_kzg_proofs(witness: &mut EigenDAWitness) -> anyhow::Result<()> {
+    compute_witness_kzg_proofs_with_srs(witness, &hokulea_compute_proof::G1_SRS)
+}
+
+/// Same as [compute_witness_kzg_proofs], with the g1 points of srs instead of the default srs, for
+/// instance one loaded by [hokulea_compute_proof::load_g1_srs_from_file]
+pub fn compute_witness_kzg_proofs_with_srs(
+    witness: &mut EigenDAWitness,
+    srs: &SRS,
+) -> anyhow::Result<()> {
+    let blobs: Vec<Vec<u8>> = witness
+        .encoded_payloads
+        .iter()
+        .map(|(_, encoded_payload, _)| encoded_payload.serialize().to_vec())
+        .collect();
+    let kzg_proofs = hokulea_compute_proof::compute_kzg_proofs_batch(&blobs, srs)
+        .map_err(|e| anyhow::anyhow!("cannot generate kzg proofs: {e}"))?;
+
+    for ((_, _, kzg_proof), proof) in witness.encoded_payloads.iter_mut().zip(kzg_proofs) {
+        *kzg_proof = proof;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eigenda_cert::test_utils::v3_altda_commitment;
+    use hokulea_eigenda::{Payload, PAYLOAD_ENCODING_VERSION_0};
+
+    // a provider serving a fixed encoded payload for every cert
+    #[derive(Debug, Clone)]
+    struct FixedPayloadProvider {
+        encoded_payload: EncodedPayload,
+    }
+
+    #[async_trait]
+    impl EigenDAPreimageProvider for FixedPayloadProvider {
+        type Error = HokuleaErrorKind;
+
+        async fn get_recency_window(
+            &mut self,
+            _altda_commitment: &AltDACommitment,
+        ) -> Result<u64, Self::Error> {
+            Ok(1)
+        }
+
+        async fn get_validity(
+            &mut self,
+            _altda_commitment: &AltDACommitment,
+        ) -> Result<bool, Self::Error> {
+            Ok(true)
+        }
+
+        async fn get_encoded_payload(
+            &mut self,
+            _altda_commitment: &AltDACommitment,
+        ) -> Result<EncodedPayload, Self::Error> {
+            Ok(self.encoded_payload.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_strict_decoding() {
+        let altda_commitment = v3_altda_commitment();
+        // the header field element must start with a zero byte
+        let undecodable = EncodedPayload {
+            encoded_payload: vec![1u8; 64].into(),
+        };
+        assert!(undecodable.decode().is_err());
+
+        let mut provider = OracleEigenDAWitnessProvider {
+            provider: FixedPayloadProvider {
+                encoded_payload: undecodable.clone(),
+            },
+            witness: Arc::new(Mutex::new(EigenDAWitness::default())),
+            strict_decoding: false,
+        };
+        // by default, the payload is recorded and left to the data source to skip
+        assert_eq!(
+            provider
+                .get_encoded_payload(&altda_commitment)
+                .await
+                .unwrap(),
+            undecodable
+        );
+        assert_eq!(provider.witness.lock().unwrap().encoded_payloads.len(), 1);
+
+        provider.strict_decoding = true;
+        let err = provider
+            .get_encoded_payload(&altda_commitment)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            WitnessProviderError::UndecodableEncodedPayload { digest, .. }
+                if *digest == altda_commitment.to_digest()
+        ));
+        // the witness generation halts instead of skipping the payload
+        assert!(matches!(
+            HokuleaErrorKind::from(err),
+            HokuleaErrorKind::Critical(_)
+        ));
+        assert_eq!(provider.witness.lock().unwrap().encoded_payloads.len(), 1);
+
+        // a well formed encoded payload passes the strict decoding
+        let payload = Payload::from_static(&[1, 2, 3]);
+        let decodable = EncodedPayload::from_payload(&payload, PAYLOAD_ENCODING_VERSION_0);
+        provider.provider.encoded_payload = decodable.clone();
+        assert_eq!(
+            provider
+                .get_encoded_payload(&altda_commitment)
+                .await
+                .unwrap(),
+            decodable
+        );
+        assert_eq!(provider.witness.lock().unwrap().encoded_payloads.len(), 2);
+    }
+}
@@ -2,7 +2,7 @@
 
 extern crate alloc;
 use core::fmt::Debug;
-use kona_preimage::CommsClient;
+use kona_preimage::{errors::PreimageOracleError, CommsClient};
 use kona_proof::{errors::OracleProviderError, BootInfo, FlushableCache};
 
 use hokulea_proof::{
@@ -31,13 +31,17 @@ where
 {
     let boot_info = BootInfo::load(oracle.as_ref()).await?;
     let boot_info_chain_id = boot_info.rollup_config.l1_chain_id;
+    check_supported_chain_id(&canoe_verifier, boot_info_chain_id)?;
     // it is critical that some field of the witness is populated inside the zkVM using known truth within the zkVM
     // force canoe verifier to use l1 chain id from rollup config.
     // it assumes the l1_chain_id from boot_info is trusted or verifiable at early or later stage
     //
     // Note, the chain_config_hash is not provided by via boot info. The l1 boot info is included only after
-    // kona 1.1.3 release. For backward compatibility, we accept the hash returned from the certValidity but
-    // verify it inside Canoe Verifier
+    // kona 1.1.3 release. For backward compatibility, we accept the hash returned from the certValidity, which
+    // the host takes from CanoeProvider::get_config_hash, and leave it untouched here. The canoe verifier is
+    // responsible for it: sp1-cc rejects a missing hash or one inconsistent with the l1 chain id from boot info,
+    // and steel rejects any hash at all. A supplied hash is cross-checked below, such that an inconsistent one
+    // is returned as an error instead of aborting the proof verification
     witness
         .validities
         .iter_mut()
@@ -54,8 +58,150 @@ where
         .iter_mut()
         .for_each(|(_, recency)| *recency = boot_info.rollup_config.seq_window_size);
 
+    check_chain_config_hashes(&canoe_verifier, &witness, boot_info_chain_id)?;
+
     Ok(PreloadedEigenDAPreimageProvider::from_witness(
         witness,
         canoe_verifier,
     ))
 }
+
+/// Rejects an l1 chain id the canoe verifier cannot verify a proof for, see
+/// [CanoeVerifier::supported_chain_ids]. Without it, an unsupported chain only fails once the
+/// chain config hash is checked deep inside the verification
+fn check_supported_chain_id(
+    canoe_verifier: &impl CanoeVerifier,
+    l1_chain_id: u64,
+) -> Result<(), OracleProviderError> {
+    match canoe_verifier.supported_chain_ids() {
+        Some(chain_ids) if !chain_ids.contains(&l1_chain_id) => {
+            Err(OracleProviderError::Preimage(PreimageOracleError::Other(
+                alloc::format!(
+                    "l1 chain id {l1_chain_id} is not supported by the canoe verifier, supported chain ids are {chain_ids:?}"
+                ),
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Cross-checks the chain config hash supplied by the host in every cert validity against the one
+/// the canoe verifier expects for the l1 chain id from boot info, see
+/// [CanoeVerifier::check_chain_config_hash]. A cert validity without hash is left to the verifier
+fn check_chain_config_hashes(
+    canoe_verifier: &impl CanoeVerifier,
+    witness: &EigenDAWitness,
+    l1_chain_id: u64,
+) -> Result<(), OracleProviderError> {
+    for (altda_commitment, cert_validity) in &witness.validities {
+        let Some(chain_config_hash) = cert_validity.chain_config_hash else {
+            continue;
+        };
+        canoe_verifier
+            .check_chain_config_hash(l1_chain_id, chain_config_hash)
+            .map_err(|e| {
+                OracleProviderError::Preimage(PreimageOracleError::Other(alloc::format!(
+                    "chain config hash {chain_config_hash} supplied for cert {} does not match l1 chain id {l1_chain_id}: {e}",
+                    altda_commitment.digest_hex_short()
+                )))
+            })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+    use alloy_primitives::B256;
+    use canoe_verifier::{CanoeNoOpVerifier, CertValidity, HokuleaCanoeVerificationError};
+    use eigenda_cert::test_utils::v3_altda_commitment;
+    use eigenda_cert::AltDACommitment;
+
+    // chain config hash the mainnet verifier expects
+    const MAINNET_CHAIN_CONFIG_HASH: B256 = B256::repeat_byte(1);
+
+    // a verifier which supports the l1 mainnet only
+    #[derive(Clone)]
+    struct MainnetVerifier {}
+
+    impl CanoeVerifier for MainnetVerifier {
+        fn validate_cert_receipt(
+            &self,
+            _cert_validity_pair: Vec<(AltDACommitment, CertValidity)>,
+            _canoe_proof: Option<Vec<u8>>,
+        ) -> Result<(), HokuleaCanoeVerificationError> {
+            Ok(())
+        }
+
+        fn supported_chain_ids(&self) -> Option<Vec<u64>> {
+            Some(vec![1])
+        }
+
+        fn check_chain_config_hash(
+            &self,
+            l1_chain_id: u64,
+            chain_config_hash: B256,
+        ) -> Result<(), HokuleaCanoeVerificationError> {
+            if l1_chain_id != 1 || chain_config_hash != MAINNET_CHAIN_CONFIG_HASH {
+                return Err(HokuleaCanoeVerificationError::InvalidChainConfigHash(
+                    l1_chain_id,
+                ));
+            }
+            Ok(())
+        }
+
+        fn to_journals_bytes(
+            &self,
+            _cert_validity_pairs: Vec<(AltDACommitment, CertValidity)>,
+        ) -> Vec<u8> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_check_supported_chain_id() {
+        assert!(check_supported_chain_id(&MainnetVerifier {}, 1).is_ok());
+
+        let err = check_supported_chain_id(&MainnetVerifier {}, 12345).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("l1 chain id 12345 is not supported"));
+
+        // a verifier without supported chain ids accepts any chain
+        assert!(check_supported_chain_id(&CanoeNoOpVerifier {}, 12345).is_ok());
+    }
+
+    #[test]
+    fn test_check_chain_config_hashes() {
+        let altda_commitment = v3_altda_commitment();
+        let witness_with_hash = |chain_config_hash| EigenDAWitness {
+            validities: vec![(
+                altda_commitment.clone(),
+                CertValidity {
+                    chain_config_hash,
+                    ..Default::default()
+                },
+            )],
+            ..Default::default()
+        };
+
+        let witness = witness_with_hash(Some(MAINNET_CHAIN_CONFIG_HASH));
+        assert!(check_chain_config_hashes(&MainnetVerifier {}, &witness, 1).is_ok());
+        // a missing hash is left to the verifier
+        let witness = witness_with_hash(None);
+        assert!(check_chain_config_hashes(&MainnetVerifier {}, &witness, 1).is_ok());
+
+        // a deliberately wrong hash supplied by the host
+        let witness = witness_with_hash(Some(B256::repeat_byte(2)));
+        let err = check_chain_config_hashes(&MainnetVerifier {}, &witness, 1).unwrap_err();
+        assert!(err.to_string().contains(&alloc::format!(
+            "chain config hash {} supplied for cert {} does not match l1 chain id 1",
+            B256::repeat_byte(2),
+            altda_commitment.digest_hex_short()
+        )));
+
+        // a verifier ignoring the hash accepts any
+        assert!(check_chain_config_hashes(&CanoeNoOpVerifier {}, &witness, 1).is_ok());
+    }
+}
@@ -2,8 +2,7 @@
 //! Note eigenda proxy has not yet supported V3 cert, but it would come soon
 
 use alloy_provider::{Provider, ProviderBuilder};
-use canoe_provider::{CanoeInput, CanoeProvider};
-use canoe_verifier::{CanoeVerifier, CertValidity, HokuleaCanoeVerificationError};
+use canoe_provider::{prove_and_verify, CanoeInput};
 use canoe_verifier_address_fetcher::{
     CanoeVerifierAddressFetcher, CanoeVerifierAddressFetcherDeployedByEigenLabs,
 };
@@ -40,56 +39,22 @@ async fn main() -> anyhow::Result<()> {
         &v2_cert_rlp_vec,
         validity,
         args.eth_rpc_url.clone(),
-        canoe_address_fetcher.clone(),
+        canoe_address_fetcher,
     )
     .await?;
 
-    // value to be used for zk verification
-    let altda_commitment = canoe_input.altda_commitment.clone();
-    let l1_head_block_hash = canoe_input.l1_head_block_hash;
-    let claimed_validity = validity;
-
-    // create canoe proof
+    // create canoe proof and verify it right away
     let canoe_provider = CanoeSteelProvider {
         eth_rpc_url: args.eth_rpc_url.clone(),
     };
-    let receipt = canoe_provider
-        .create_certs_validity_proof(vec![canoe_input])
+    prove_and_verify(&canoe_provider, &CanoeSteelVerifier {}, vec![canoe_input])
         .await
-        .unwrap()?;
-    let canoe_proof_bytes = serde_json::to_vec(&receipt).expect("serde error");
-
-    // prepare value to verify canoe proof
-    let cert_validity = CertValidity {
-        claimed_validity,
-        l1_head_block_hash,
-        l1_chain_id: 11155111,
-        verifier_address: canoe_address_fetcher
-            .fetch_address(11155111, &altda_commitment.versioned_cert)?,
-    };
-    verify_canoe_proof(
-        cert_validity.clone(),
-        altda_commitment.clone(),
-        canoe_proof_bytes,
-    )
-    .expect("correct proof should have passed");
+        .expect("correct proof should have passed");
     println!("cert verification pass");
 
     Ok(())
 }
 
-// this function takes canoe proof and verify it
-pub fn verify_canoe_proof(
-    cert_validity: CertValidity,
-    altda_commitment: AltDACommitment,
-    canoe_proof_bytes: Vec<u8>,
-) -> Result<(), HokuleaCanoeVerificationError> {
-    // verify canoe proof
-    let canoe_verifier = CanoeSteelVerifier {};
-    let validity_cert_pair = (altda_commitment, cert_validity);
-    canoe_verifier.validate_cert_receipt(vec![validity_cert_pair], Some(canoe_proof_bytes))
-}
-
 /// It is a helper function that prepares canoe input which can be used to generate a
 /// zk validity or invalidity proof.
 /// This function provides takes the latest block tip for l1_block_hash and block_number
@@ -135,5 +100,36 @@ pub async fn get_canoe_input(
         l1_chain_id: 11155111,
         verifier_address: canoe_address_fetcher
             .fetch_address(11155111, &altda_commitment.versioned_cert)?,
+        expected_kzg_commitment: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use canoe_provider::CanoeProvider;
+
+    /// run with a sepolia rpc, steel executes the guest without proving in dev mode
+    /// RISC0_DEV_MODE=1 ETH_RPC_URL=.. cargo test -p hokulea-example-canoe-on-sepolia -- --ignored
+    #[tokio::test]
+    #[ignore = "needs a sepolia rpc url"]
+    async fn test_proof_timings() {
+        let eth_rpc_url = std::env::var("ETH_RPC_URL").expect("rpc url should be set");
+        let canoe_input = get_canoe_input(
+            V2_CERT_RLP_BYTES,
+            true,
+            eth_rpc_url.clone(),
+            CanoeVerifierAddressFetcherDeployedByEigenLabs {},
+        )
+        .await
+        .unwrap();
+
+        let (_, timings) = CanoeSteelProvider { eth_rpc_url }
+            .create_certs_validity_proof_with_timings(vec![canoe_input])
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!timings.preflight.is_zero());
+        assert!(!timings.prove.is_zero());
+    }
+}
@@ -1,6 +1,6 @@
 //! Main entrypoint for the example binary, which runs both host and client
 
-use clap::Parser;
+use clap::{Args, Parser};
 use hokulea_host_bin::{cfg::SingleChainHostWithEigenDA, init_tracing_subscriber};
 use hokulea_zkvm_verification::eigenda_witness_to_preloaded_provider;
 use kona_client::fpvm_evm::FpvmOpEvmFactory;
@@ -9,7 +9,6 @@ use kona_preimage::{
     BidirectionalChannel, CommsClient, HintWriter, HintWriterClient, OracleReader,
     PreimageOracleClient,
 };
-use kona_proof::CachingOracle;
 use tokio::task;
 
 use core::fmt::Debug;
@@ -21,32 +20,89 @@ use op_revm::OpSpecId;
 use kona_proof::{l1::OracleBlobProvider, BootInfo, FlushableCache};
 
 use canoe_provider::CanoeProvider;
-use canoe_verifier::CanoeVerifier;
+use canoe_verifier::{CanoeNoOpVerifier, CanoeVerifier};
 use canoe_verifier_address_fetcher::{
     CanoeVerifierAddressFetcher, CanoeVerifierAddressFetcherDeployedByEigenLabs,
 };
 
 use hokulea_client::fp_client;
+use hokulea_client_bin::client::ClientConfig;
+use hokulea_compute_proof::{load_g1_srs_from_file, G1_SRS, MAX_BLOB_SRS_POINTS, SRS};
 use hokulea_proof::{
     eigenda_provider::OracleEigenDAPreimageProvider, eigenda_witness::EigenDAWitness,
 };
-use hokulea_witgen::witness_provider::OracleEigenDAWitnessProvider;
+use hokulea_witgen::witness_provider::{
+    compute_witness_kzg_proofs_with_srs, OracleEigenDAWitnessProvider,
+};
 use std::{
     ops::DerefMut,
+    path::PathBuf,
     sync::{Arc, Mutex},
 };
 
 use tracing::info;
 
+/// The preloader CLI arguments, the host arguments along with options of the witgen phase
+#[derive(Parser, Debug)]
+struct PreloaderCfg {
+    #[clap(flatten)]
+    host: SingleChainHostWithEigenDA,
+
+    #[clap(flatten)]
+    witgen: WitgenCfg,
+}
+
+/// Options of the witgen phase
+#[derive(Args, Debug, Clone, Default)]
+pub struct WitgenCfg {
+    /// Path to which the eigenda witness produced by the witgen phase is dumped as JSON. The
+    /// witness is loadable with EigenDAWitness::from_json_path to replay the zkVM run.
+    #[clap(long)]
+    pub dump_witness: Option<PathBuf>,
+
+    /// Halts the witgen phase with an error when an encoded payload fetched from eigenda cannot
+    /// be decoded, instead of skipping it like the derivation does. Meant for auditing that the
+    /// derivation window contains no bad data.
+    #[clap(long)]
+    pub strict_decoding: bool,
+
+    /// Path to a file of compressed g1 points, in the format of resources/g1.point, from which the
+    /// srs computing the kzg proofs of the witness is loaded instead of the default one. The file
+    /// must hold enough points to commit the largest eigenda blob, and is loaded at startup.
+    #[clap(long)]
+    pub srs_path: Option<PathBuf>,
+
+    /// Runs the derivation to populate the witness without generating a canoe proof, then checks
+    /// the witness with a CanoeNoOpVerifier. Meant for debugging the witness format, the kzg
+    /// consistency and the witness shape are validated without spending prover time.
+    #[clap(long)]
+    pub skip_canoe_proof: bool,
+}
+
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
-    let cfg = SingleChainHostWithEigenDA::try_parse()?;
+    let PreloaderCfg {
+        host: cfg,
+        witgen: witgen_cfg,
+    } = PreloaderCfg::try_parse()?;
     init_tracing_subscriber(cfg.verbose)?;
 
+    let srs = witgen_cfg
+        .srs_path
+        .as_ref()
+        .map(|path| load_g1_srs_from_file(&path.to_string_lossy(), MAX_BLOB_SRS_POINTS))
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("cannot load srs: {e}"))?;
+
     let hint = BidirectionalChannel::new()?;
     let preimage = BidirectionalChannel::new()?;
 
-    let server_task = cfg.start_server(hint.host, preimage.host).await?;
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+    let server_task = cfg
+        .start_server_with_shutdown(hint.host, preimage.host, async {
+            let _ = shutdown_rx.await;
+        })
+        .await?;
 
     cfg_if::cfg_if! {
         if #[cfg(feature = "steel")] {
@@ -85,11 +141,13 @@ async fn main() -> anyhow::Result<()> {
             let canoe_provider = CanoeSp1CCReducedProofProvider{
                 eth_rpc_url: cfg.kona_cfg.l1_node_address.clone().unwrap(),
                 mock_mode,
+                sketch_cache: None,
+                network_rpc_url: None,
+                private_key: None,
             };
-            let canoe_verifier = CanoeSp1CCVerifier{};
+            let canoe_verifier = CanoeSp1CCVerifier::default();
         } else {
             use canoe_provider::CanoeNoOpProvider;
-            use canoe_verifier::CanoeNoOpVerifier;
             let canoe_provider = CanoeNoOpProvider{};
             let canoe_verifier = CanoeNoOpVerifier{};
         }
@@ -108,9 +166,15 @@ async fn main() -> anyhow::Result<()> {
         canoe_provider,
         canoe_verifier,
         canoe_address_fetcher,
+        ClientConfig::default(),
+        witgen_cfg,
+        srs,
     ));
 
-    let (_, client_result) = tokio::try_join!(server_task, client_task)?;
+    let client_result = client_task.await?;
+    // stop the server so that the key value store is flushed before exiting
+    let _ = shutdown_tx.send(());
+    let _ = server_task.await?;
 
     // Bubble up the exit status of the client program if execution completes.
     std::process::exit(client_result.is_err() as i32)
@@ -121,7 +185,12 @@ async fn main() -> anyhow::Result<()> {
 /// The first round runs run_witgen_client only to populate the witness. This produces an artifact
 /// that contains all the necessary preimage to run the derivation.
 /// The second round uses the populated witness to run against
+/// The first round is configured by the witgen_cfg, see [WitgenCfg], and computes the kzg proofs
+/// with srs if provided, otherwise with the default [G1_SRS]. If skip_canoe_proof is set, no canoe
+/// proof is generated and the second round verifies the witness with a [CanoeNoOpVerifier] in
+/// place of canoe_verifier, see [CanoeProofPlan]
 #[allow(clippy::type_complexity)]
+#[allow(clippy::too_many_arguments)]
 #[allow(unused_variables)]
 pub async fn run_witgen_and_zk_verification<P, H, Evm>(
     oracle_client: P,
@@ -130,6 +199,9 @@ pub async fn run_witgen_and_zk_verification<P, H, Evm>(
     canoe_provider: impl CanoeProvider,
     canoe_verifier: impl CanoeVerifier,
     canoe_address_fetcher: impl CanoeVerifierAddressFetcher,
+    client_config: ClientConfig,
+    witgen_cfg: WitgenCfg,
+    srs: Option<SRS>,
 ) -> anyhow::Result<()>
 where
     P: PreimageOracleClient + Send + Sync + Debug + Clone,
@@ -137,30 +209,94 @@ where
     Evm: EvmFactory<Spec = OpSpecId> + Send + Sync + Debug + Clone + 'static,
     <Evm as EvmFactory>::Tx: FromTxWithEncoded<OpTxEnvelope> + FromRecoveredTx<OpTxEnvelope>,
 {
-    const ORACLE_LRU_SIZE: usize = 1024;
+    let oracle = Arc::new(client_config.caching_oracle(oracle_client, hint_client));
+    let srs = srs.as_ref().unwrap_or(&G1_SRS);
+    let plan = CanoeProofPlan::new(witgen_cfg.skip_canoe_proof);
+
+    let wit = match plan {
+        CanoeProofPlan::Prove => {
+            prepare_witness(
+                oracle.clone(),
+                evm_factory.clone(),
+                canoe_provider,
+                canoe_address_fetcher.clone(),
+                witgen_cfg.strict_decoding,
+                srs,
+            )
+            .await?
+        }
+        CanoeProofPlan::Skip => {
+            info!("skipping canoe proof, the witness is populated without proving cert validity");
+            run_witgen_client(
+                oracle.clone(),
+                evm_factory.clone(),
+                witgen_cfg.strict_decoding,
+                srs,
+            )
+            .await?
+        }
+    };
+    plan.check_witness(&wit)?;
 
-    let oracle = Arc::new(CachingOracle::new(
-        ORACLE_LRU_SIZE,
-        oracle_client,
-        hint_client,
-    ));
+    if let Some(path) = witgen_cfg.dump_witness {
+        std::fs::write(&path, wit.encode()?)?;
+        info!("dumped {} to {}", wit.summary(), path.display());
+    }
 
-    let wit = prepare_witness(
-        oracle.clone(),
-        evm_factory.clone(),
-        canoe_provider,
-        canoe_address_fetcher.clone(),
-    )
-    .await?;
+    match plan {
+        CanoeProofPlan::Prove => {
+            run_within_zkvm(
+                oracle,
+                evm_factory,
+                canoe_verifier,
+                canoe_address_fetcher,
+                wit,
+            )
+            .await
+        }
+        CanoeProofPlan::Skip => {
+            run_within_zkvm(
+                oracle,
+                evm_factory,
+                CanoeNoOpVerifier {},
+                canoe_address_fetcher,
+                wit,
+            )
+            .await
+        }
+    }
+}
 
-    run_within_zkvm(
-        oracle,
-        evm_factory,
-        canoe_verifier,
-        canoe_address_fetcher,
-        wit,
-    )
-    .await
+/// How [run_witgen_and_zk_verification] proves and verifies the cert validities of the witness
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanoeProofPlan {
+    /// a canoe proof is generated, and the witness is verified with the canoe verifier
+    Prove,
+    /// no canoe proof is generated, and the witness is verified with a [CanoeNoOpVerifier]
+    Skip,
+}
+
+impl CanoeProofPlan {
+    /// Picks the plan from [WitgenCfg::skip_canoe_proof]
+    pub fn new(skip_canoe_proof: bool) -> Self {
+        if skip_canoe_proof {
+            Self::Skip
+        } else {
+            Self::Prove
+        }
+    }
+
+    /// Checks the witness is populated according to the plan, a skipped canoe proof leaves the
+    /// witness without canoe proof
+    pub fn check_witness(self, wit: &EigenDAWitness) -> anyhow::Result<()> {
+        if self == Self::Skip && !wit.canoe_proof_bytes.is_empty() {
+            anyhow::bail!(
+                "canoe proof is skipped, but the witness carries {} canoe proofs",
+                wit.canoe_proof_bytes.len()
+            );
+        }
+        Ok(())
+    }
 }
 
 /// used internal
@@ -170,6 +306,8 @@ pub async fn prepare_witness<O, Evm>(
     evm_factory: Evm,
     canoe_provider: impl CanoeProvider,
     canoe_address_fetcher: impl CanoeVerifierAddressFetcher,
+    strict_decoding: bool,
+    srs: &SRS,
 ) -> anyhow::Result<EigenDAWitness>
 where
     O: CommsClient + FlushableCache + Send + Sync + Debug,
@@ -177,7 +315,8 @@ where
     <Evm as EvmFactory>::Tx: FromTxWithEncoded<OpTxEnvelope> + FromRecoveredTx<OpTxEnvelope>,
 {
     // Run derivation for the first time to populate the witness data
-    let mut wit: EigenDAWitness = run_witgen_client(oracle.clone(), evm_factory.clone()).await?;
+    let mut wit: EigenDAWitness =
+        run_witgen_client(oracle.clone(), evm_factory.clone(), strict_decoding, srs).await?;
 
     // get l1 header, does not have to come from oracle directly, it is for convenience
     let boot_info = BootInfo::load(oracle.as_ref()).await?;
@@ -215,10 +354,15 @@ where
         };
         match canoe_provider.get_recursive_proof(&proof) {
             Some(recursive_proof) => {
+                // the backend tag lets the verifier reject a proof from another backend
+                let proof_backend = canoe_provider
+                    .proof_backend()
+                    .expect("a provider producing a recursive proof must have a backend");
                 wit.canoe_proof_bytes =
-                    Some(serde_json::to_vec(&recursive_proof).expect("serde error"))
+                    vec![proof_backend
+                        .tag(serde_json::to_vec(&recursive_proof).expect("serde error"))]
             }
-            None => wit.canoe_proof_bytes = None,
+            None => wit.canoe_proof_bytes = Vec::new(),
         }
     }
 
@@ -232,10 +376,16 @@ where
 /// 1. a KZG commitment is consistent to the retrieved encoded payload (i.e. after taking IFFT, the KZG commitment
 ///    with monomial SRS basis yields to the same KZG commitment)
 /// 2. the cert is correct
+///
+/// If strict_decoding is set, the derivation halts on the first encoded payload that cannot be
+/// decoded, see [OracleEigenDAWitnessProvider::strict_decoding]. The kzg proofs of the witness
+/// are computed with the g1 points of srs
 #[allow(clippy::type_complexity)]
 pub async fn run_witgen_client<O, Evm>(
     oracle: Arc<O>,
     evm_factory: Evm,
+    strict_decoding: bool,
+    srs: &SRS,
 ) -> Result<EigenDAWitness, FaultProofProgramError>
 where
     O: CommsClient + FlushableCache + Send + Sync + Debug,
@@ -250,11 +400,13 @@ where
     let eigenda_witness_provider = OracleEigenDAWitnessProvider {
         provider: eigenda_preimage_provider,
         witness: eigenda_witness.clone(),
+        strict_decoding,
     };
 
     fp_client::run_fp_client(oracle, beacon, eigenda_witness_provider, evm_factory).await?;
 
-    let wit = core::mem::take(eigenda_witness.lock().unwrap().deref_mut());
+    let mut wit = core::mem::take(eigenda_witness.lock().unwrap().deref_mut());
+    compute_witness_kzg_proofs_with_srs(&mut wit, srs).expect("cannot generate kzg proofs");
 
     Ok(wit)
 }
@@ -292,3 +444,31 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hokulea_proof::eigenda_witness::CURRENT_WITNESS_VERSION;
+
+    #[test]
+    fn test_canoe_proof_plan() {
+        assert_eq!(CanoeProofPlan::new(false), CanoeProofPlan::Prove);
+        assert_eq!(CanoeProofPlan::new(true), CanoeProofPlan::Skip);
+
+        let mut wit = EigenDAWitness {
+            version: CURRENT_WITNESS_VERSION,
+            recencies: vec![],
+            validities: vec![],
+            encoded_payloads: vec![],
+            canoe_proof_bytes: vec![],
+        };
+        // a witness without canoe proof fits both plans, e.g. a window without cert to prove
+        assert!(CanoeProofPlan::Prove.check_witness(&wit).is_ok());
+        assert!(CanoeProofPlan::Skip.check_witness(&wit).is_ok());
+
+        // a skipped canoe proof never leaves a proof behind
+        wit.canoe_proof_bytes.push(vec![1u8; 32]);
+        assert!(CanoeProofPlan::Prove.check_witness(&wit).is_ok());
+        assert!(CanoeProofPlan::Skip.check_witness(&wit).is_err());
+    }
+}